@@ -1,14 +1,21 @@
 use crate::{
     // accessors,
+    args::CacheRoot,
+    asyncjob::AsyncSingleJob,
     cmdbar::CommandBar,
+    datasets::AsyncDatasetsJob,
     // components::{
         // command_pump, event_pump, CommandInfo, Component, DrawableComponent, FuzzyFinderTarget,
     components::{
-        Component, DrawableComponent,
+        bytes_to_string, command_pump, event_pump, CommandInfo, Component, DrawableComponent,
+    },
+    hub::{
+        self, AccessJournal, AsyncHubNotification, AsyncScanJob, HubCache, ScanResult,
     },
     input::{Input, InputEvent, InputState},
+    modules::{self, AsyncModulesJob},
     keys::{key_match, KeyConfig, SharedKeyConfig},
-    // options::{Options, SharedOptions},
+    options::{Options, SharedOptions},
     // popup_stack::PopupStack,
     // popups::{
     //     AppOption, BlameFilePopup, BranchListPopup, CommitPopup, CompareCommitsPopup, ConfirmPopup,
@@ -19,14 +26,19 @@ use crate::{
     //     TagListPopup, UpdateRemoteUrlPopup,
     // },
     // queue::{Action, AppTabs, InternalEvent, NeedsUpdate, Queue, StackablePopupOpen},
-    queue::{AppTabs, NeedsUpdate},
+    queue::{AppTabs, InternalEvent, NeedsUpdate, Queue},
     // setup_popups,
     strings::{self, ellipsis_trim_start, order},
     // tabs::{FilesTab, Revlog, StashList, Stashing, Status},
-    tabs::{FilesTab, Status},
+    popups::{
+        ConvertPopup, FuzzyFindPopup, MsgPopup, ProblemsPopup, RevisionsPopup,
+        SnapshotDiffPopup, VerifyPopup, ViewInputPopup,
+    },
+    tabs::{DatasetsTab, FilesTab, ModulesTab, Status},
     // try_or_popup,
     ui::style::{SharedTheme, Theme},
-    // AsyncAppNotification, AsyncNotification,
+    AsyncAppNotification,
+    AsyncNotification,
 };
 use anyhow::{bail, Result};
 // use asyncgit::{
@@ -42,17 +54,21 @@ use crossterm::event::{Event, KeyEvent};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Tabs},
+    widgets::{Block, Borders, Gauge, Paragraph, Tabs},
     Frame,
 };
 use std::{
     cell::{Cell, RefCell},
-    path::{Path, PathBuf},
+    collections::BTreeSet,
+    path::PathBuf,
     rc::Rc,
 };
 use unicode_width::UnicodeWidthStr;
 // use unicode_width::UnicodeWidthStr;
 
+/// columns of the cache budget gauge in the top bar
+const BUDGET_GAUGE_WIDTH: u16 = 24;
+
 #[derive(Clone)]
 pub enum QuitState {
     None,
@@ -65,7 +81,7 @@ pub struct App {
     // repo: RepoPathRef,
     do_quit: QuitState,
     // help_popup: HelpPopup,
-    // msg_popup: MsgPopup,
+    msg_popup: MsgPopup,
     // confirm_popup: ConfirmPopup,
     // commit_popup: CommitPopup,
     // blame_file_popup: BlameFilePopup,
@@ -100,13 +116,47 @@ pub struct App {
     // stashing_tab: Stashing,
     // stashlist_tab: StashList,
     files_tab: FilesTab,
-    // queue: Queue,
+    datasets_tab: DatasetsTab,
+    modules_tab: ModulesTab,
+    problems_popup: ProblemsPopup,
+    fuzzy_find_popup: FuzzyFindPopup,
+    revisions_popup: RevisionsPopup,
+    snapshot_diff_popup: SnapshotDiffPopup,
+    view_input_popup: ViewInputPopup,
+    verify_popup: VerifyPopup,
+    convert_popup: ConvertPopup,
+    queue: Queue,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
     input: Input,
+    cache_roots: Vec<CacheRoot>,
+    cache: Rc<HubCache>,
+    scan: AsyncSingleJob<AsyncScanJob>,
+    scanning: Option<AsyncScanJob>,
+    /// repo folders with their root index changed while a scan was running
+    changed_folders: BTreeSet<(usize, PathBuf)>,
+    datasets_root: PathBuf,
+    datasets_scan: AsyncSingleJob<AsyncDatasetsJob>,
+    /// the datasets cache changed while it was scanned
+    datasets_changed: bool,
+    /// the `transformers_modules` folder
+    modules_root: PathBuf,
+    modules_scan: AsyncSingleJob<AsyncModulesJob>,
+    /// the module cache changed while it was scanned
+    modules_changed: bool,
+    /// scan index file, `None` if there is no cache directory
+    index_path: Option<PathBuf>,
+    /// cache as last loaded from or saved to the index
+    indexed: Rc<HubCache>,
     // popup_stack: PopupStack,
-    // options: SharedOptions,
-    // repo_path_text: String,
+    options: SharedOptions,
+    /// last access of every repo, see `hub::AccessJournal`
+    journal: AccessJournal,
+    /// journal file, `None` if there is no data directory
+    journal_path: Option<PathBuf>,
+    /// the journal changed since it was saved
+    journal_changed: bool,
+    cache_root_text: String,
 
     // "Flags"
     requires_redraw: Cell<bool>,
@@ -114,13 +164,14 @@ pub struct App {
 }
 
 pub struct Environment {
-    // pub queue: Queue,
+    pub queue: Queue,
     pub theme: SharedTheme,
     pub key_config: SharedKeyConfig,
+    pub sender_hub: Sender<AsyncHubNotification>,
     // pub repo: RepoPathRef,
-    // pub options: SharedOptions,
+    pub options: SharedOptions,
     // pub sender_git: Sender<AsyncGitNotification>,
-    // pub sender_app: Sender<AsyncAppNotification>,
+    pub sender_app: Sender<AsyncAppNotification>,
 }
 
 // public interface
@@ -130,23 +181,47 @@ impl App {
     pub fn new(
         // repo: RepoPathRef,
         // sender_git: Sender<AsyncGitNotification>,
-        // sender_app: Sender<AsyncAppNotification>,
+        sender_hub: Sender<AsyncHubNotification>,
+        sender_app: Sender<AsyncAppNotification>,
+        cache_roots: Vec<CacheRoot>,
+        datasets_root: PathBuf,
+        modules_root: PathBuf,
         input: Input,
         theme: Theme,
         key_config: KeyConfig,
+        options: Options,
     ) -> Result<Self> {
-        // log::trace!("open repo at: {:?}", &repo);
+        log::trace!("open cache at: {:?}", &cache_roots);
+
+        let cache_root_text = strings::cache_roots(&cache_roots);
+
+        let index_path = hub::index_path()
+            .map_err(|e| log::error!("scan index: {e}"))
+            .ok();
+        let cache = Rc::new(
+            index_path
+                .as_deref()
+                .and_then(|path| hub::load_index(path, &cache_roots))
+                .unwrap_or_else(|| HubCache::new(cache_roots.clone())),
+        );
 
-        // let repo_path_text = repo_work_dir(&repo.borrow()).unwrap_or_default();
+        let journal_path = hub::journal_path()
+            .map_err(|e| log::error!("access journal: {e}"))
+            .ok();
+        let journal = journal_path
+            .as_deref()
+            .map(AccessJournal::load)
+            .unwrap_or_default();
 
         let env = Environment {
-            // queue: Queue::new(),
+            queue: Queue::new(),
             theme: Rc::new(theme),
             key_config: Rc::new(key_config),
-            // options: Options::new(repo.clone()),
+            sender_hub,
+            options: Rc::new(options),
             // repo,
             // sender_git,
-            // sender_app,
+            sender_app,
         };
 
         // let tab = env.options.borrow().current_tab();
@@ -184,25 +259,53 @@ impl App {
             do_quit: QuitState::None,
             cmdbar: RefCell::new(CommandBar::new(env.theme.clone(), env.key_config.clone())),
             // help_popup: HelpPopup::new(&env),
-            // msg_popup: MsgPopup::new(&env),
+            msg_popup: MsgPopup::new(&env),
             // revlog: Revlog::new(&env),
             status_tab: Status::new(&env),
             // stashing_tab: Stashing::new(&env),
             // stashlist_tab: StashList::new(&env),
             files_tab: FilesTab::new(&env),
+            datasets_tab: DatasetsTab::new(&env),
+            modules_tab: ModulesTab::new(&env),
+            problems_popup: ProblemsPopup::new(&env),
+            fuzzy_find_popup: FuzzyFindPopup::new(&env),
+            revisions_popup: RevisionsPopup::new(&env),
+            snapshot_diff_popup: SnapshotDiffPopup::new(&env),
+            view_input_popup: ViewInputPopup::new(&env),
+            verify_popup: VerifyPopup::new(&env),
+            convert_popup: ConvertPopup::new(&env),
             tab: 0,
-            // queue: env.queue,
+            scan: AsyncSingleJob::new(env.sender_hub.clone()),
+            datasets_scan: AsyncSingleJob::new(env.sender_hub.clone()),
+            datasets_root,
+            datasets_changed: false,
+            modules_scan: AsyncSingleJob::new(env.sender_hub.clone()),
+            modules_root: modules_root.join(modules::TRANSFORMERS_MODULES),
+            modules_changed: false,
+            queue: env.queue,
             theme: env.theme,
-            // options: env.options,
+            options: env.options,
             key_config: env.key_config,
             requires_redraw: Cell::new(false),
             file_to_open: None,
+            indexed: cache.clone(),
+            cache,
+            index_path,
+            journal,
+            journal_path,
+            journal_changed: false,
+            cache_roots,
+            scanning: None,
+            changed_folders: BTreeSet::new(),
             // repo: env.repo,
-            // repo_path_text,
+            cache_root_text,
             // popup_stack: PopupStack::default(),
         };
 
         app.set_tab(tab)?;
+        // render the indexed repos right away, the first scan revalidates them
+        app.cache_changed()?;
+        app.check_free_space()?;
 
         Ok(app)
     }
@@ -242,14 +345,16 @@ impl App {
             match self.tab {
                 0 => self.status_tab.draw(f, chunks_main[1])?,
                 // 1 => self.revlog.draw(f, chunks_main[1])?,
-                1 => self.files_tab.draw(f, fsize)?,
+                1 => self.files_tab.draw(f, chunks_main[1])?,
+                2 => self.datasets_tab.draw(f, chunks_main[1])?,
+                3 => self.modules_tab.draw(f, chunks_main[1])?,
                 // 3 => self.stashing_tab.draw(f, chunks_main[1])?,
                 // 4 => self.stashlist_tab.draw(f, chunks_main[1])?,
                 _ => bail!("unknown tab"),
             }
         }
 
-        // self.draw_popups(f)?;
+        self.draw_popups(f)?;
 
         Ok(())
     }
@@ -264,10 +369,9 @@ impl App {
 
             let mut flags = NeedsUpdate::empty();
 
-            // if event_pump(&ev, self.components_mut().as_mut_slice())?.is_consumed() {
-            //     flags.insert(NeedsUpdate::COMMANDS);
-            // } else if let Event::Key(k) = &ev {
-            if let Event::Key(k) = &ev {
+            if event_pump(&ev, self.components_mut().as_mut_slice())?.is_consumed() {
+                flags.insert(NeedsUpdate::COMMANDS);
+            } else if let Event::Key(k) = &ev {
                 // let new_flags = if key_match(k, self.key_config.keys.tab_toggle) {
                 //     self.toggle_tabs(false)?;
                 //     NeedsUpdate::COMMANDS
@@ -278,11 +382,20 @@ impl App {
                 let new_flags = if key_match(k, self.key_config.keys.tab_status)
                     // || key_match(k, self.key_config.keys.tab_log)
                     || key_match(k, self.key_config.keys.tab_files)
+                    || key_match(k, self.key_config.keys.tab_datasets)
+                    || key_match(k, self.key_config.keys.tab_modules)
                     // || key_match(k, self.key_config.keys.tab_stashing)
                     // || key_match(k, self.key_config.keys.tab_stashes)
                 {
                     self.switch_tab(k)?;
                     NeedsUpdate::COMMANDS
+                } else if key_match(k, self.key_config.keys.open_problems) {
+                    self.problems_popup
+                        .open(self.cache.clone(), self.files_tab.selected_repo_id())?;
+                    NeedsUpdate::COMMANDS
+                } else if key_match(k, self.key_config.keys.file_find) {
+                    self.fuzzy_find_popup.open(&self.cache)?;
+                    NeedsUpdate::COMMANDS
                 // } else if key_match(k, self.key_config.keys.cmd_bar_toggle) {
                 //     self.cmdbar.borrow_mut().toggle_more();
                 //     NeedsUpdate::empty()
@@ -296,7 +409,7 @@ impl App {
                 flags.insert(new_flags);
             }
 
-            // self.process_queue(flags)?;
+            self.process_queue(flags)?;
         }
         // } else if let InputEvent::State(polling_state) = ev {
         //     self.external_editor_popup.hide();
@@ -322,6 +435,165 @@ impl App {
         Ok(())
     }
 
+    /// starts a background rescan of the caches unless one is running already
+    pub fn update(&mut self) -> Result<()> {
+        log::trace!("update");
+
+        if !self.scan.is_pending() {
+            // a full scan covers all changes seen so far
+            self.changed_folders.clear();
+            self.spawn_scan(AsyncScanJob::new(
+                self.cache_roots.clone(),
+                self.cache.repos.clone(),
+            ));
+        }
+
+        self.datasets_changed = true;
+        self.rescan_datasets();
+        self.modules_changed = true;
+        self.rescan_modules();
+
+        self.update_scan_progress()?;
+
+        Ok(())
+    }
+
+    /// rescans the repos containing the changed `paths`
+    pub fn update_paths(&mut self, paths: Vec<PathBuf>) -> Result<()> {
+        log::trace!("update_paths: {}", paths.len());
+
+        if paths.iter().any(|p| p.starts_with(&self.datasets_root)) {
+            self.datasets_changed = true;
+            self.rescan_datasets();
+        }
+        if paths.iter().any(|p| p.starts_with(&self.modules_root)) {
+            self.modules_changed = true;
+            self.rescan_modules();
+        }
+
+        let roots = &self.cache_roots;
+        self.changed_folders.extend(paths.iter().filter_map(|p| {
+            roots
+                .iter()
+                .enumerate()
+                .find_map(|(idx, root)| Some((idx, hub::repo_folder_of(&root.path, p)?)))
+        }));
+
+        self.rescan_changed();
+        self.update_scan_progress()?;
+
+        Ok(())
+    }
+
+    /// applies partial and final scan results and forwards verification progress
+    pub fn update_async(&mut self, ev: AsyncNotification) -> Result<()> {
+        log::trace!("update_async: {:?}", ev);
+
+        let ev = match ev {
+            AsyncNotification::App(ev) => {
+                self.files_tab.update_app(ev);
+                self.modules_tab.update_app(ev);
+                return Ok(());
+            }
+            AsyncNotification::Hub(ev) => ev,
+        };
+
+        match ev {
+            AsyncHubNotification::VerifyProgress | AsyncHubNotification::Verify => {
+                self.verify_popup.update_hub(ev);
+            }
+            AsyncHubNotification::ConvertProgress | AsyncHubNotification::Convert => {
+                if let Some(folder) = self.convert_popup.update_hub(ev) {
+                    self.changed_folders.insert(folder);
+                    self.rescan_changed();
+                }
+            }
+            AsyncHubNotification::Datasets => {
+                match self
+                    .datasets_scan
+                    .take_last()
+                    .and_then(|job| job.take_result())
+                {
+                    Some(Ok(cache)) => self.datasets_tab.set_cache(Rc::new(cache)),
+                    Some(Err(e)) => log::error!("datasets scan failed: {e}"),
+                    None => (),
+                }
+
+                self.rescan_datasets();
+            }
+            AsyncHubNotification::Modules => {
+                match self
+                    .modules_scan
+                    .take_last()
+                    .and_then(|job| job.take_result())
+                {
+                    Some(Ok(cache)) => self.modules_tab.set_modules(Rc::new(cache)),
+                    Some(Err(e)) => log::error!("modules scan failed: {e}"),
+                    None => (),
+                }
+
+                self.rescan_modules();
+            }
+            AsyncHubNotification::ScanProgress => {
+                let mut repos = self
+                    .scanning
+                    .as_ref()
+                    .map(AsyncScanJob::take_partial)
+                    .unwrap_or_default();
+
+                if !repos.is_empty() {
+                    self.journal_changed |= self.journal.update(&mut repos);
+                    Rc::make_mut(&mut self.cache).merge(repos);
+                    self.cache_changed()?;
+                }
+            }
+            AsyncHubNotification::Scan => {
+                self.scanning = None;
+
+                match self.scan.take_last().and_then(|job| job.take_result()) {
+                    Some(Ok(ScanResult::Cache(mut cache))) => {
+                        self.journal_changed |= self.journal.update(&mut cache.repos);
+                        self.cache = Rc::new(cache);
+                    }
+                    Some(Ok(ScanResult::Repos {
+                        mut updated,
+                        removed,
+                    })) => {
+                        self.journal_changed |= self.journal.update(&mut updated);
+                        let cache = Rc::make_mut(&mut self.cache);
+                        for folder in &removed {
+                            cache.remove(folder);
+                        }
+                        cache.merge(updated);
+                    }
+                    Some(Err(e)) => log::error!("cache scan failed: {e}"),
+                    None => (),
+                }
+
+                self.cache_changed()?;
+                self.status_tab.update_free_space();
+                self.save_index();
+                self.save_journal();
+                self.rescan_changed();
+            }
+        }
+
+        self.update_scan_progress()?;
+
+        Ok(())
+    }
+
+    /// true while a background job is running
+    pub fn any_work_pending(&self) -> bool {
+        self.scan.is_pending()
+            || self.datasets_scan.is_pending()
+            || self.modules_scan.is_pending()
+            || self.files_tab.any_work_pending()
+            || self.modules_tab.any_work_pending()
+            || self.verify_popup.any_work_pending()
+            || self.convert_popup.any_work_pending()
+    }
+
 //     //TODO: do we need this?
 //     /// forward ticking to components that require it
 //     pub fn update(&mut self) -> Result<()> {
@@ -488,9 +760,9 @@ impl App {
 //     );
 
     fn check_quit(&mut self, ev: &Event) -> bool {
-        // if self.any_popup_visible() {
-        //     return false;
-        // }
+        if self.any_popup_visible() {
+            return false;
+        }
         if let Event::Key(e) = ev {
             if key_match(e, self.key_config.keys.quit) {
                 self.do_quit = QuitState::Close;
@@ -510,11 +782,57 @@ impl App {
         false
     }
 
+    fn any_popup_visible(&self) -> bool {
+        self.msg_popup.is_visible()
+            || self.problems_popup.is_visible()
+            || self.fuzzy_find_popup.is_visible()
+            || self.revisions_popup.is_visible()
+            || self.snapshot_diff_popup.is_visible()
+            || self.view_input_popup.is_visible()
+            || self.verify_popup.is_visible()
+            || self.convert_popup.is_visible()
+    }
+
+    fn draw_popups(&self, f: &mut Frame) -> Result<()> {
+        let size = f.area();
+
+        self.problems_popup.draw(f, size)?;
+        self.fuzzy_find_popup.draw(f, size)?;
+        self.snapshot_diff_popup.draw(f, size)?;
+        self.revisions_popup.draw(f, size)?;
+        self.view_input_popup.draw(f, size)?;
+        self.verify_popup.draw(f, size)?;
+        self.convert_popup.draw(f, size)?;
+        self.msg_popup.draw(f, size)?;
+
+        Ok(())
+    }
+
+    /// popups first so they get to see events before the tabs
+    fn components_mut(&mut self) -> Vec<&mut dyn Component> {
+        vec![
+            &mut self.msg_popup,
+            &mut self.problems_popup,
+            &mut self.fuzzy_find_popup,
+            &mut self.revisions_popup,
+            &mut self.snapshot_diff_popup,
+            &mut self.view_input_popup,
+            &mut self.verify_popup,
+            &mut self.convert_popup,
+            &mut self.status_tab,
+            &mut self.files_tab,
+            &mut self.datasets_tab,
+            &mut self.modules_tab,
+        ]
+    }
+
     fn get_tabs(&mut self) -> Vec<&mut dyn Component> {
         vec![
             &mut self.status_tab,
             // &mut self.revlog,
             &mut self.files_tab,
+            &mut self.datasets_tab,
+            &mut self.modules_tab,
             // &mut self.stashing_tab,
             // &mut self.stashlist_tab,
         ]
@@ -538,6 +856,10 @@ impl App {
             // self.switch_to_tab(&AppTabs::Log)?;
         } else if key_match(k, self.key_config.keys.tab_files) {
             self.switch_to_tab(&AppTabs::Files)?;
+        } else if key_match(k, self.key_config.keys.tab_datasets) {
+            self.switch_to_tab(&AppTabs::Datasets)?;
+        } else if key_match(k, self.key_config.keys.tab_modules) {
+            self.switch_to_tab(&AppTabs::Modules)?;
         // } else if key_match(k, self.key_config.keys.tab_stashing) {
         //     self.switch_to_tab(&AppTabs::Stashing)?;
         // } else if key_match(k, self.key_config.keys.tab_stashes) {
@@ -568,18 +890,227 @@ impl App {
             AppTabs::Status => self.set_tab(0)?,
             // AppTabs::Log => self.set_tab(1)?,
             AppTabs::Files => self.set_tab(1)?,
+            AppTabs::Datasets => self.set_tab(2)?,
+            AppTabs::Modules => self.set_tab(3)?,
             // AppTabs::Stashing => self.set_tab(3)?,
             // AppTabs::Stashlist => self.set_tab(4)?,
         }
         Ok(())
     }
 
-//     fn update_commands(&mut self) {
-//         if self.help_popup.is_visible() {
-//             self.help_popup.set_cmds(self.commands(true));
-//         }
-//         self.cmdbar.borrow_mut().set_cmds(self.commands(false));
-//     }
+    /// warns about cache roots on file systems below the free space floor
+    fn check_free_space(&mut self) -> Result<()> {
+        let Some(floor) = self.options.min_free_space() else {
+            return Ok(());
+        };
+
+        let warnings: Vec<String> = self
+            .cache_roots
+            .iter()
+            .filter_map(|root| {
+                let free = hub::free_space(&root.path)?;
+                (free < floor).then(|| {
+                    strings::low_free_space(root, &bytes_to_string(free), &bytes_to_string(floor))
+                })
+            })
+            .collect();
+
+        if !warnings.is_empty() {
+            self.msg_popup.show_warning(&warnings.join("\n"))?;
+        }
+
+        Ok(())
+    }
+
+    fn cache_changed(&mut self) -> Result<()> {
+        self.status_tab.set_cache(self.cache.clone());
+        self.modules_tab.set_hub(self.cache.clone());
+        self.files_tab.set_cache(self.cache.clone())
+    }
+
+    fn save_index(&mut self) {
+        if *self.cache == *self.indexed {
+            return;
+        }
+
+        if let Some(path) = &self.index_path {
+            if let Err(e) = hub::save_index(path, &self.cache) {
+                log::error!("saving scan index: {e}");
+            }
+        }
+
+        self.indexed = self.cache.clone();
+    }
+
+    fn save_journal(&mut self) {
+        if !self.journal_changed {
+            return;
+        }
+
+        if let Some(path) = &self.journal_path {
+            if let Err(e) = self.journal.save(path) {
+                log::error!("saving access journal: {e}");
+            }
+        }
+
+        self.journal_changed = false;
+    }
+
+    fn spawn_scan(&mut self, job: AsyncScanJob) {
+        self.scanning = Some(job.clone());
+        self.scan.spawn(job);
+    }
+
+    fn rescan_changed(&mut self) {
+        if self.changed_folders.is_empty() || self.scan.is_pending() {
+            return;
+        }
+
+        let folders = std::mem::take(&mut self.changed_folders);
+        self.spawn_scan(AsyncScanJob::repos(folders.into_iter().collect()));
+    }
+
+    fn rescan_datasets(&mut self) {
+        if !self.datasets_changed || self.datasets_scan.is_pending() {
+            return;
+        }
+
+        self.datasets_changed = false;
+        self.datasets_scan
+            .spawn(AsyncDatasetsJob::new(self.datasets_root.clone()));
+    }
+
+    fn rescan_modules(&mut self) {
+        if !self.modules_changed || self.modules_scan.is_pending() {
+            return;
+        }
+
+        self.modules_changed = false;
+        self.modules_scan
+            .spawn(AsyncModulesJob::new(self.modules_root.clone()));
+    }
+
+    fn update_scan_progress(&mut self) -> Result<()> {
+        let progress = if self.scan.is_pending() {
+            self.scan.progress()
+        } else {
+            None
+        };
+
+        self.files_tab.set_scan_progress(progress);
+        self.update_commands();
+
+        Ok(())
+    }
+
+    fn update_commands(&mut self) {
+        // if self.help_popup.is_visible() {
+        //     self.help_popup.set_cmds(self.commands(true));
+        // }
+        self.cmdbar.borrow_mut().set_cmds(self.commands(false));
+    }
+
+    fn process_queue(&mut self, flags: NeedsUpdate) -> Result<()> {
+        let mut flags = flags;
+        flags.insert(self.process_internal_events()?);
+
+        if flags.contains(NeedsUpdate::ALL) {
+            self.update()?;
+        }
+        if flags.contains(NeedsUpdate::COMMANDS) {
+            self.update_commands();
+        }
+
+        Ok(())
+    }
+
+    fn process_internal_events(&mut self) -> Result<NeedsUpdate> {
+        let mut flags = NeedsUpdate::empty();
+
+        while let Some(ev) = self.queue.pop() {
+            match ev {
+                InternalEvent::OpenVerify(open) => {
+                    self.verify_popup.open(open)?;
+                    flags.insert(NeedsUpdate::COMMANDS);
+                }
+                InternalEvent::OpenConvert(open) => {
+                    self.convert_popup.open(open)?;
+                    flags.insert(NeedsUpdate::COMMANDS);
+                }
+                InternalEvent::OpenRevisions(open) => {
+                    self.revisions_popup.open(open)?;
+                    flags.insert(NeedsUpdate::COMMANDS);
+                }
+                InternalEvent::SelectRevision(revision) => {
+                    self.files_tab.select_revision(&revision);
+                    flags.insert(NeedsUpdate::COMMANDS);
+                }
+                InternalEvent::CompareRevision(revision) => {
+                    self.files_tab.compare_revision(&revision);
+                }
+                InternalEvent::OpenSnapshotDiff(open) => {
+                    self.snapshot_diff_popup.open(open)?;
+                    flags.insert(NeedsUpdate::COMMANDS);
+                }
+                InternalEvent::OpenFoundFile(found) => {
+                    self.switch_to_tab(&AppTabs::Files)?;
+                    self.files_tab.open_found_file(&found)?;
+                    flags.insert(NeedsUpdate::COMMANDS);
+                }
+                InternalEvent::OpenViewInput(kind) => {
+                    self.view_input_popup.open(kind)?;
+                    flags.insert(NeedsUpdate::COMMANDS);
+                }
+                InternalEvent::ViewJump(jump) => {
+                    self.files_tab.view_jump(&jump);
+                    self.modules_tab.view_jump(&jump);
+                    flags.insert(NeedsUpdate::COMMANDS);
+                }
+            }
+        }
+
+        Ok(flags)
+    }
+
+    fn commands(&self, force_all: bool) -> Vec<CommandInfo> {
+        let mut res = Vec::new();
+
+        command_pump(
+            &mut res,
+            force_all,
+            &[
+                &self.msg_popup,
+                &self.problems_popup,
+                &self.fuzzy_find_popup,
+                &self.revisions_popup,
+                &self.snapshot_diff_popup,
+                &self.view_input_popup,
+                &self.verify_popup,
+                &self.convert_popup,
+                &self.status_tab,
+                &self.files_tab,
+                &self.datasets_tab,
+                &self.modules_tab,
+            ],
+        );
+
+        res.push(CommandInfo::new(
+            strings::commands::open_problems(&self.key_config),
+            true,
+            !self.any_popup_visible() || force_all,
+        ));
+        res.push(CommandInfo::new(
+            strings::commands::find_file(&self.key_config),
+            !self.cache.repos.is_empty(),
+            !self.any_popup_visible() || force_all,
+        ));
+
+        res.push(
+            CommandInfo::new(strings::commands::quit(&self.key_config), true, true).order(100),
+        );
+
+        res
+    }
 
 //     fn process_queue(&mut self, flags: NeedsUpdate) -> Result<()> {
 //         let mut flags = flags;
@@ -1005,10 +1536,9 @@ impl App {
 
         let tab_labels = [
             Span::raw(strings::tab_status(&self.key_config)),
-            Span::raw(strings::tab_log(&self.key_config)),
             Span::raw(strings::tab_files(&self.key_config)),
-            Span::raw(strings::tab_stashing(&self.key_config)),
-            Span::raw(strings::tab_stashes(&self.key_config)),
+            Span::raw(strings::tab_datasets(&self.key_config)),
+            Span::raw(strings::tab_modules(&self.key_config)),
         ];
         let divider = strings::tab_divider(&self.key_config);
 
@@ -1028,7 +1558,16 @@ impl App {
             .split(r);
 
         let table_area = r; // use entire area to allow drawing the horizontal separator line
-        let text_area = left_right[1];
+        let (text_area, gauge_area) = match self.options.cache_budget() {
+            Some(budget) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), Constraint::Length(BUDGET_GAUGE_WIDTH)])
+                    .split(left_right[1]);
+                (chunks[0], Some((chunks[1], budget)))
+            }
+            None => (left_right[1], None),
+        };
 
         let tabs: Vec<Line> = tab_labels.into_iter().map(Line::from).collect();
 
@@ -1046,13 +1585,43 @@ impl App {
             table_area,
         );
 
-        // f.render_widget(
-        //     Paragraph::new(Line::from(vec![Span::styled(
-        //         ellipsis_trim_start(&self.repo_path_text, text_area.width as usize),
-        //         self.theme.title(false),
-        //     )]))
-        //     .alignment(Alignment::Right),
-        //     text_area,
-        // );
+        f.render_widget(
+            Paragraph::new(Line::from(vec![Span::styled(
+                ellipsis_trim_start(&self.cache_root_text, text_area.width as usize),
+                self.theme.title(false),
+            )]))
+            .alignment(Alignment::Right),
+            text_area,
+        );
+
+        if let Some((area, budget)) = gauge_area {
+            self.draw_budget_gauge(f, area, budget);
+        }
+    }
+
+    /// cache size against `budget`, in the first line of `area`,
+    /// read-only roots do not count as the user cannot free them
+    fn draw_budget_gauge(&self, f: &mut Frame, area: Rect, budget: u64) {
+        let used: u64 = self
+            .cache
+            .repos
+            .iter()
+            .filter(|r| self.cache.root_of(r).is_some_and(|root| root.writable))
+            .map(|r| r.sizes.unique)
+            .sum();
+
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = used as f64 / budget.max(1) as f64;
+
+        f.render_widget(
+            Gauge::default()
+                .label(strings::budget_gauge(
+                    &bytes_to_string(used),
+                    &bytes_to_string(budget),
+                ))
+                .gauge_style(self.theme.budget_gauge(used > budget))
+                .ratio(ratio.min(1.0)),
+            Rect { height: 1, ..area },
+        );
     }
 }
@@ -2,17 +2,63 @@ use clap::{
     builder::ArgPredicate, crate_authors, crate_description, crate_name, Arg, Command as ClapApp,
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use std::{
     env,
+    fmt,
     fs::{self, File},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+/// folder name of the app's own cache and data directories
+pub const APP_NAME: &str = "lazyhf";
+
+/// rule that selected the hub cache location
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheRootSource {
+    Argument,
+    HfHubCache,
+    HfHome,
+    XdgCacheHome,
+    Default,
+}
+
+impl fmt::Display for CacheRootSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Argument => "--directory",
+            Self::HfHubCache => "HF_HUB_CACHE",
+            Self::HfHome => "HF_HOME",
+            Self::XdgCacheHome => "XDG_CACHE_HOME",
+            Self::Default => "default",
+        })
+    }
+}
+
+/// label of the root resolved from the environment when no `--directory` is given
+pub const DEFAULT_ROOT_LABEL: &str = "local";
+
+/// resolved hub cache location
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheRoot {
+    pub path: PathBuf,
+    pub source: CacheRootSource,
+    /// short name shown next to repos of this root
+    pub label: String,
+    /// false for shared caches that must never be modified
+    pub writable: bool,
+}
+
 pub struct CliArgs {
     pub theme: PathBuf,
     pub workdir: PathBuf,
     pub notify_watcher: bool,
+    /// all cache roots, the first one is the primary
+    pub cache_roots: Vec<CacheRoot>,
+    /// cache of the `datasets` library, may not exist
+    pub datasets_root: PathBuf,
+    /// dynamic modules of `trust_remote_code` models, may not exist
+    pub modules_root: PathBuf,
 }
 
 pub fn process_cmdline() -> Result<CliArgs> {
@@ -34,13 +80,212 @@ pub fn process_cmdline() -> Result<CliArgs> {
 
     let notify_watcher: bool = *arg_matches.get_one("watcher").unwrap_or(&false);
 
+    let values = |id: &str| -> Vec<String> {
+        arg_matches
+            .get_many::<String>(id)
+            .map(|v| v.cloned().collect())
+            .unwrap_or_default()
+    };
+
+    let mut cache_roots = resolve_cache_roots(
+        &values("directory"),
+        &values("read-only"),
+        |name| env::var(name).ok(),
+        dirs::home_dir(),
+    )?;
+
+    // a missing default is fine as long as other roots were given
+    if cache_roots.len() > 1 {
+        cache_roots.retain(|root| {
+            root.source == CacheRootSource::Argument || root.path.is_dir()
+        });
+    }
+
+    for root in &mut cache_roots {
+        if !root.path.is_dir() {
+            bail!(
+                "hugging face cache directory not found: {} (selected by {})",
+                root.path.display(),
+                root.source
+            );
+        }
+        // watcher events report absolute paths
+        root.path = root.path.canonicalize()?;
+    }
+
+    if let Some(root) = cache_roots
+        .iter()
+        .enumerate()
+        .find(|(idx, root)| cache_roots[..*idx].iter().any(|r| r.path == root.path))
+        .map(|(_, root)| root)
+    {
+        bail!("cache directory given twice: {}", root.path.display());
+    }
+
+    let datasets_root = resolve_datasets_root(|name| env::var(name).ok(), dirs::home_dir())?;
+    // watcher events report absolute paths
+    let datasets_root = datasets_root.canonicalize().unwrap_or(datasets_root);
+
+    let modules_root = resolve_modules_root(|name| env::var(name).ok(), dirs::home_dir())?;
+    let modules_root = modules_root.canonicalize().unwrap_or(modules_root);
+
     Ok(CliArgs {
         theme,
         workdir,
         notify_watcher,
+        cache_roots,
+        datasets_root,
+        modules_root,
     })
 }
 
+/// all cache roots: the writable `directories` (or the one resolved by
+/// `resolve_cache_root` if there are none) followed by the `read_only` ones,
+/// each given as `[LABEL=]DIR`
+pub fn resolve_cache_roots(
+    directories: &[String],
+    read_only: &[String],
+    var: impl Fn(&str) -> Option<String>,
+    home: Option<PathBuf>,
+) -> Result<Vec<CacheRoot>> {
+    let mut roots = Vec::new();
+
+    if directories.is_empty() {
+        roots.push(resolve_cache_root(&var, home.clone())?);
+    }
+
+    let specs = directories
+        .iter()
+        .map(|spec| (spec, true))
+        .chain(read_only.iter().map(|spec| (spec, false)));
+
+    for (spec, writable) in specs {
+        let (label, path) = parse_root_spec(spec);
+        let label = label.map_or_else(
+            || format!("root{}", roots.len() + 1),
+            String::from,
+        );
+
+        roots.push(CacheRoot {
+            path: expand_home(path, home.as_deref()),
+            source: CacheRootSource::Argument,
+            label,
+            writable,
+        });
+    }
+
+    if let Some(root) = roots
+        .iter()
+        .enumerate()
+        .find(|(idx, root)| roots[..*idx].iter().any(|r| r.label == root.label))
+        .map(|(_, root)| root)
+    {
+        bail!("cache label used twice: {}", root.label);
+    }
+
+    Ok(roots)
+}
+
+/// splits `label=dir` into its parts, a plain `dir` has no label
+fn parse_root_spec(spec: &str) -> (Option<&str>, &Path) {
+    match spec.split_once('=') {
+        Some((label, path)) if !label.is_empty() && !label.contains('/') => {
+            (Some(label), Path::new(path))
+        }
+        _ => (None, Path::new(spec)),
+    }
+}
+
+/// picks the hub cache like `huggingface_hub` does:
+/// `HF_HUB_CACHE`, `HF_HOME/hub`, `XDG_CACHE_HOME/huggingface/hub`, `~/.cache/huggingface/hub`
+pub fn resolve_cache_root(
+    var: impl Fn(&str) -> Option<String>,
+    home: Option<PathBuf>,
+) -> Result<CacheRoot> {
+
+    let var = |name: &str| {
+        var(name)
+            .filter(|v| !v.is_empty())
+            .map(|v| expand_home(Path::new(&v), home.as_deref()))
+    };
+
+    let (path, source) = if let Some(path) = var("HF_HUB_CACHE") {
+        (path, CacheRootSource::HfHubCache)
+    } else if let Some(path) = var("HF_HOME") {
+        (path.join("hub"), CacheRootSource::HfHome)
+    } else if let Some(path) = var("XDG_CACHE_HOME") {
+        (
+            path.join("huggingface").join("hub"),
+            CacheRootSource::XdgCacheHome,
+        )
+    } else {
+        let home = home
+            .as_ref()
+            .ok_or_else(|| anyhow!("failed to find home dir."))?;
+        (
+            home.join(".cache").join("huggingface").join("hub"),
+            CacheRootSource::Default,
+        )
+    };
+
+    Ok(CacheRoot {
+        path,
+        source,
+        label: DEFAULT_ROOT_LABEL.to_string(),
+        writable: true,
+    })
+}
+
+/// picks the `datasets` cache like the `datasets` library does:
+/// `HF_DATASETS_CACHE`, `HF_HOME/datasets`, `XDG_CACHE_HOME/huggingface/datasets`, `~/.cache/huggingface/datasets`
+pub fn resolve_datasets_root(
+    var: impl Fn(&str) -> Option<String>,
+    home: Option<PathBuf>,
+) -> Result<PathBuf> {
+    resolve_hf_home_folder("HF_DATASETS_CACHE", "datasets", var, home)
+}
+
+/// picks the dynamic modules folder like `transformers` does:
+/// `HF_MODULES_CACHE`, `HF_HOME/modules`, `XDG_CACHE_HOME/huggingface/modules`, `~/.cache/huggingface/modules`
+pub fn resolve_modules_root(
+    var: impl Fn(&str) -> Option<String>,
+    home: Option<PathBuf>,
+) -> Result<PathBuf> {
+    resolve_hf_home_folder("HF_MODULES_CACHE", "modules", var, home)
+}
+
+/// `folder` below the hf home unless the variable `override_var` is set
+fn resolve_hf_home_folder(
+    override_var: &str,
+    folder: &str,
+    var: impl Fn(&str) -> Option<String>,
+    home: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let var = |name: &str| {
+        var(name)
+            .filter(|v| !v.is_empty())
+            .map(|v| expand_home(Path::new(&v), home.as_deref()))
+    };
+
+    if let Some(path) = var(override_var) {
+        Ok(path)
+    } else if let Some(path) = var("HF_HOME") {
+        Ok(path.join(folder))
+    } else if let Some(path) = var("XDG_CACHE_HOME") {
+        Ok(path.join("huggingface").join(folder))
+    } else {
+        let home = home.ok_or_else(|| anyhow!("failed to find home dir."))?;
+        Ok(home.join(".cache").join("huggingface").join(folder))
+    }
+}
+
+fn expand_home(path: &Path, home: Option<&Path>) -> PathBuf {
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
 fn app() -> ClapApp {
     ClapApp::new(crate_name!())
 		.author(crate_authors!())
@@ -92,10 +337,20 @@ fn app() -> ClapApp {
 		)
 		.arg(
 			Arg::new("directory")
-				.help("Set the hugging face cache directory")
+				.help("Add a writable hugging face cache directory, optionally labeled")
 				.short('d')
 				.long("directory")
-				.env("GIT_DIR")
+				.value_name("[LABEL=]CACHE_DIR")
+				.action(clap::ArgAction::Append)
+				.num_args(1),
+		)
+		.arg(
+			Arg::new("read-only")
+				.help("Add a shared hugging face cache directory that is never modified")
+				.short('r')
+				.long("read-only")
+				.value_name("[LABEL=]CACHE_DIR")
+				.action(clap::ArgAction::Append)
 				.num_args(1),
 		)
 		.arg(
@@ -119,3 +374,185 @@ pub fn get_app_config_path() -> Result<PathBuf> {
     path.push("gitui");
     Ok(path)
 }
+
+/// directory for data that can be rebuilt, like the scan index
+pub fn get_app_cache_path() -> Result<PathBuf> {
+    let mut path = if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|h| h.join(".cache"))
+    } else {
+        dirs::cache_dir()
+    }
+    .ok_or_else(|| anyhow!("failed to find os cache dir."))?;
+
+    path.push(APP_NAME);
+    Ok(path)
+}
+
+/// directory for state that cannot be rebuilt, like the access journal
+pub fn get_app_data_path() -> Result<PathBuf> {
+    let mut path = if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|h| h.join(".local").join("share"))
+    } else {
+        dirs::data_local_dir()
+    }
+    .ok_or_else(|| anyhow!("failed to find os data dir."))?;
+
+    path.push(APP_NAME);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn resolve(vars: &[(&str, &str)]) -> CacheRoot {
+        resolve_cache_root(
+            |name| {
+                vars.iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| (*v).to_string())
+            },
+            Some(PathBuf::from("/home/u")),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cache_root_precedence() {
+        let all = [
+            ("HF_HUB_CACHE", "/hub"),
+            ("HF_HOME", "/hf"),
+            ("XDG_CACHE_HOME", "/xdg"),
+        ];
+
+        assert_eq!(resolve(&all).path, PathBuf::from("/hub"));
+        assert_eq!(resolve(&all[1..]).path, PathBuf::from("/hf/hub"));
+        assert_eq!(
+            resolve(&all[2..]).path,
+            PathBuf::from("/xdg/huggingface/hub")
+        );
+        assert_eq!(
+            resolve(&[]),
+            CacheRoot {
+                path: PathBuf::from("/home/u/.cache/huggingface/hub"),
+                source: CacheRootSource::Default,
+                label: DEFAULT_ROOT_LABEL.to_string(),
+                writable: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_cache_root_expands_home() {
+        assert_eq!(
+            resolve(&[("HF_HOME", "~/hf"), ("HF_HUB_CACHE", "")]),
+            CacheRoot {
+                path: PathBuf::from("/home/u/hf/hub"),
+                source: CacheRootSource::HfHome,
+                label: DEFAULT_ROOT_LABEL.to_string(),
+                writable: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_cache_roots() {
+        let home = Some(PathBuf::from("/home/u"));
+        let var = |name: &str| (name == "HF_HOME").then(|| "/hf".to_string());
+        let labels = |roots: &[CacheRoot]| -> Vec<(String, PathBuf, bool)> {
+            roots
+                .iter()
+                .map(|r| (r.label.clone(), r.path.clone(), r.writable))
+                .collect()
+        };
+
+        let roots = resolve_cache_roots(
+            &[],
+            &["shared=/nfs/hub".to_string()],
+            var,
+            home.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            labels(&roots),
+            vec![
+                ("local".to_string(), PathBuf::from("/hf/hub"), true),
+                ("shared".to_string(), PathBuf::from("/nfs/hub"), false),
+            ]
+        );
+
+        let roots = resolve_cache_roots(
+            &["nvme=~/hub".to_string(), "/a=b".to_string()],
+            &[],
+            var,
+            home.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            labels(&roots),
+            vec![
+                ("nvme".to_string(), PathBuf::from("/home/u/hub"), true),
+                ("root2".to_string(), PathBuf::from("/a=b"), true),
+            ]
+        );
+
+        assert!(resolve_cache_roots(
+            &["x=/a".to_string()],
+            &["x=/b".to_string()],
+            var,
+            home,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_datasets_root() {
+        let resolve = |vars: &[(&str, &str)]| {
+            resolve_datasets_root(
+                |name| {
+                    vars.iter()
+                        .find(|(k, _)| *k == name)
+                        .map(|(_, v)| (*v).to_string())
+                },
+                Some(PathBuf::from("/home/u")),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            resolve(&[("HF_DATASETS_CACHE", "~/ds"), ("HF_HOME", "/hf")]),
+            PathBuf::from("/home/u/ds")
+        );
+        assert_eq!(resolve(&[("HF_HOME", "/hf")]), PathBuf::from("/hf/datasets"));
+        assert_eq!(
+            resolve(&[]),
+            PathBuf::from("/home/u/.cache/huggingface/datasets")
+        );
+    }
+
+    #[test]
+    fn test_modules_root() {
+        let resolve = |vars: &[(&str, &str)]| {
+            resolve_modules_root(
+                |name| {
+                    vars.iter()
+                        .find(|(k, _)| *k == name)
+                        .map(|(_, v)| (*v).to_string())
+                },
+                Some(PathBuf::from("/home/u")),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            resolve(&[("HF_MODULES_CACHE", "/m"), ("HF_HOME", "/hf")]),
+            PathBuf::from("/m")
+        );
+        assert_eq!(resolve(&[("HF_HOME", "/hf")]), PathBuf::from("/hf/modules"));
+        assert_eq!(
+            resolve(&[("XDG_CACHE_HOME", "/xdg")]),
+            PathBuf::from("/xdg/huggingface/modules")
+        );
+    }
+}
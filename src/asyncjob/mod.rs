@@ -0,0 +1,245 @@
+//! provides `AsyncJob` trait and `AsyncSingleJob` struct
+
+use anyhow::Result;
+use crossbeam_channel::Sender;
+use std::{
+	sync::{Arc, Mutex, RwLock},
+	thread,
+};
+
+/// passed to `AsyncJob::run` allowing sending intermediate progress notifications
+pub struct RunParams<
+	T: Copy + Send,
+	P: Clone + Send + Sync + PartialEq,
+> {
+	sender: Sender<T>,
+	progress: Arc<RwLock<P>>,
+}
+
+impl<T: Copy + Send, P: Clone + Send + Sync + PartialEq>
+	RunParams<T, P>
+{
+	/// send an intermediate update notification.
+	/// do not confuse this with the return value of `run`.
+	/// `send` should only be used about progress notifications
+	/// and not for the final notification indicating the end of the async job.
+	/// see `run` for more info
+	pub fn send(&self, notification: T) -> Result<()> {
+		self.sender.send(notification).map_err(disconnected)?;
+		Ok(())
+	}
+
+	/// set the current progress,
+	/// returns `true` if it changed
+	pub fn set_progress(&self, p: P) -> Result<bool> {
+		Ok(if *self.progress.read().map_err(poisoned)? == p {
+			false
+		} else {
+			*(self.progress.write().map_err(poisoned)?) = p;
+			true
+		})
+	}
+}
+
+/// trait that defines an async task we can run on a threadpool
+pub trait AsyncJob: Send + Sync + Clone {
+	/// defines what notification type is used to communicate outside
+	type Notification: Copy + Send;
+	/// type of progress
+	type Progress: Clone + Default + Send + Sync + PartialEq;
+
+	/// can run a synchronous time intensive task.
+	/// the returned notification is used to tell interested parties
+	/// that the job finished and the job can be access via `take_last`.
+	/// prior to this final notification it is not safe to assume `take_last`
+	/// will already return the correct job
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification>;
+}
+
+/// Abstraction for a FIFO task queue that will only queue up **one** `next` job.
+/// It keeps overwriting the next job until it is actually taken to be processed
+#[derive(Debug, Clone)]
+pub struct AsyncSingleJob<J: AsyncJob> {
+	next: Arc<Mutex<Option<J>>>,
+	last: Arc<Mutex<Option<J>>>,
+	progress: Arc<RwLock<J::Progress>>,
+	sender: Sender<J::Notification>,
+	pending: Arc<Mutex<()>>,
+}
+
+impl<J: 'static + AsyncJob> AsyncSingleJob<J> {
+	/// creates an idle job queue reporting to `sender`
+	pub fn new(sender: Sender<J::Notification>) -> Self {
+		Self {
+			next: Arc::new(Mutex::new(None)),
+			last: Arc::new(Mutex::new(None)),
+			pending: Arc::new(Mutex::new(())),
+			progress: Arc::new(RwLock::new(J::Progress::default())),
+			sender,
+		}
+	}
+
+	/// returns true if the job is still running
+	pub fn is_pending(&self) -> bool {
+		self.pending.try_lock().is_err()
+	}
+
+	/// take out last finished job
+	pub fn take_last(&self) -> Option<J> {
+		self.last.lock().map_or(None, |mut last| last.take())
+	}
+
+	/// spawns `task` if nothing is running currently,
+	/// otherwise schedules as `next` overwriting if `next` was set before.
+	/// return `true` if the new task gets started right away.
+	pub fn spawn(&self, task: J) -> bool {
+		self.schedule_next(task);
+		self.check_for_job()
+	}
+
+	/// progress of the running job
+	pub fn progress(&self) -> Option<J::Progress> {
+		self.progress.read().ok().map(|d| (*d).clone())
+	}
+
+	fn check_for_job(&self) -> bool {
+		if self.is_pending() {
+			return false;
+		}
+
+		if let Some(task) = self.take_next() {
+			let self_clone = (*self).clone();
+			thread::spawn(move || {
+				if let Err(e) = self_clone.run_job(task) {
+					log::error!("async job error: {e}");
+				}
+			});
+
+			return true;
+		}
+
+		false
+	}
+
+	fn run_job(&self, mut task: J) -> Result<()> {
+		//limit the pending scope
+		let notification = {
+			let _pending = self.pending.lock().map_err(poisoned)?;
+
+			let notification = task.run(RunParams {
+				progress: self.progress.clone(),
+				sender: self.sender.clone(),
+			})?;
+
+			if let Ok(mut last) = self.last.lock() {
+				*last = Some(task);
+			}
+
+			notification
+		};
+
+		// sent once no longer pending so receivers can spawn the next job right away
+		self.sender.send(notification).map_err(disconnected)?;
+
+		self.check_for_job();
+
+		Ok(())
+	}
+
+	fn schedule_next(&self, task: J) {
+		if let Ok(mut next) = self.next.lock() {
+			*next = Some(task);
+		}
+	}
+
+	fn take_next(&self) -> Option<J> {
+		self.next.lock().map_or(None, |mut next| next.take())
+	}
+}
+
+fn poisoned<T>(_: T) -> anyhow::Error {
+	anyhow::anyhow!("lock poisoned")
+}
+
+fn disconnected<T>(_: T) -> anyhow::Error {
+	anyhow::anyhow!("notification receiver disconnected")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crossbeam_channel::unbounded;
+	use pretty_assertions::assert_eq;
+	use std::{
+		sync::atomic::{AtomicU32, Ordering},
+		time::Duration,
+	};
+
+	#[derive(Clone)]
+	struct TestJob {
+		v: Arc<AtomicU32>,
+		finish: Arc<AtomicU32>,
+		value_to_add: u32,
+	}
+
+	type TestNotificaton = ();
+
+	impl AsyncJob for TestJob {
+		type Notification = TestNotificaton;
+		type Progress = ();
+
+		fn run(
+			&mut self,
+			_params: RunParams<Self::Notification, Self::Progress>,
+		) -> Result<Self::Notification> {
+			while self.finish.load(Ordering::SeqCst) == 0 {
+				thread::sleep(Duration::from_millis(10));
+			}
+
+			self.v.fetch_add(self.value_to_add, Ordering::SeqCst);
+
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn test_overwrite() {
+		let (sender, receiver) = unbounded();
+
+		let job: AsyncSingleJob<TestJob> = AsyncSingleJob::new(sender);
+
+		let task = TestJob {
+			v: Arc::new(AtomicU32::new(1)),
+			finish: Arc::new(AtomicU32::new(0)),
+			value_to_add: 1,
+		};
+
+		assert!(job.spawn(task.clone()));
+		thread::sleep(Duration::from_millis(10));
+
+		for i in 2..=4 {
+			job.spawn(TestJob {
+				value_to_add: i,
+				..task.clone()
+			});
+		}
+
+		assert!(job.is_pending());
+
+		task.finish.store(1, Ordering::SeqCst);
+
+		receiver.recv().unwrap();
+		receiver.recv().unwrap();
+		assert!(receiver.is_empty());
+
+		assert_eq!(
+			task.v.load(Ordering::SeqCst),
+			// first task + last task
+			1 + 1 + 4
+		);
+		assert!(job.take_last().is_some());
+	}
+}
@@ -0,0 +1,250 @@
+use super::{
+	utils::{bytes_to_string, string_width_align, time_to_string},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState,
+};
+use crate::{
+	app::Environment,
+	hub::{
+		free_space, newest_revision, CacheStats, HubCache, RepoType,
+		LARGEST_REPOS,
+	},
+	strings,
+	ui::style::SharedTheme,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	style::Style,
+	text::{Line, Span},
+	widgets::{Block, Borders, Paragraph},
+	Frame,
+};
+use std::rc::Rc;
+
+const VALUE_COLUMN_WIDTH: usize = 12;
+/// summary rows besides the free space of every root:
+/// total, unique, one per repo type and problems
+const SUMMARY_ROWS: usize = 3 + RepoType::ALL.len();
+
+/// overview of the whole cache: sizes, repo counts, the largest and
+/// newest repos, problems and free space
+pub struct CacheDashboardComponent {
+	cache: Rc<HubCache>,
+	stats: CacheStats,
+	/// free space of the file system of every root, `None` if unknown
+	free: Vec<Option<u64>>,
+	theme: SharedTheme,
+}
+
+impl CacheDashboardComponent {
+	/// lines needed to show all figures including borders
+	pub fn height(&self) -> u16 {
+		let rows =
+			(SUMMARY_ROWS + self.cache.roots.len()).max(LARGEST_REPOS);
+		u16::try_from(rows + 2).unwrap_or(u16::MAX)
+	}
+
+	/// creates an empty dashboard, filled by `set_cache`
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			cache: Rc::default(),
+			stats: CacheStats::default(),
+			free: Vec::new(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	/// recomputes all figures from `cache`
+	pub fn set_cache(&mut self, cache: Rc<HubCache>) {
+		self.stats = CacheStats::new(&cache);
+		self.cache = cache;
+	}
+
+	/// asks the file systems of all roots for their free space,
+	/// once per finished scan rather than for every streamed result
+	pub fn update_free_space(&mut self) {
+		self.free = self
+			.cache
+			.roots
+			.iter()
+			.map(|root| free_space(&root.path))
+			.collect();
+	}
+
+	fn row<'a>(
+		label: &str,
+		value: String,
+		width: usize,
+		style: Style,
+	) -> Line<'a> {
+		Line::from(vec![
+			Span::styled(
+				string_width_align(
+					label,
+					width.saturating_sub(VALUE_COLUMN_WIDTH),
+				),
+				style,
+			),
+			Span::styled(format!("{value:>VALUE_COLUMN_WIDTH$}"), style),
+		])
+	}
+
+	fn repo_name(&self, idx: usize) -> String {
+		let repo = &self.cache.repos[idx];
+		format!(
+			"{} {}",
+			strings::repo_type_icon(repo.repo_type),
+			repo.repo_id
+		)
+	}
+
+	fn summary_lines(&self, width: usize) -> Vec<Line<'static>> {
+		let text = self.theme.text(true, false);
+		let stats = &self.stats;
+
+		let mut lines = vec![
+			Self::row(
+				&strings::summary_total(),
+				bytes_to_string(stats.apparent),
+				width,
+				text,
+			),
+			Self::row(
+				&strings::summary_unique(),
+				bytes_to_string(stats.unique),
+				width,
+				text,
+			),
+		];
+		lines.extend(RepoType::ALL.into_iter().map(|t| {
+			Self::row(
+				strings::repo_type_name(t),
+				stats.repo_count(t).to_string(),
+				width,
+				self.theme.repo_type(t, false),
+			)
+		}));
+		lines.push(Self::row(
+			&strings::summary_problems(),
+			stats.problems.to_string(),
+			width,
+			if stats.problems > 0 {
+				self.theme.text_danger()
+			} else {
+				text
+			},
+		));
+		lines.extend(self.cache.roots.iter().enumerate().map(
+			|(idx, root)| {
+				Self::row(
+					&strings::summary_free(root),
+					self.free.get(idx).copied().flatten().map_or_else(
+						strings::summary_unknown,
+						bytes_to_string,
+					),
+					width,
+					text,
+				)
+			},
+		));
+
+		lines
+	}
+
+	fn largest_lines(&self, width: usize) -> Vec<Line<'static>> {
+		self.stats
+			.largest
+			.iter()
+			.map(|idx| {
+				Self::row(
+					&self.repo_name(*idx),
+					bytes_to_string(self.cache.repos[*idx].sizes.unique),
+					width,
+					self.theme.text(true, false),
+				)
+			})
+			.collect()
+	}
+
+	fn recent_lines(&self, width: usize) -> Vec<Line<'static>> {
+		self.stats
+			.recent
+			.iter()
+			.map(|idx| {
+				let newest = newest_revision(&self.cache.repos[*idx]);
+				Self::row(
+					&self.repo_name(*idx),
+					time_to_string(newest.unwrap_or_default(), true),
+					width,
+					self.theme.text(true, false),
+				)
+			})
+			.collect()
+	}
+
+	fn draw_panel(
+		&self,
+		f: &mut Frame,
+		area: Rect,
+		title: String,
+		lines: impl FnOnce(usize) -> Vec<Line<'static>>,
+	) {
+		let block = Block::default()
+			.title(Span::styled(title, self.theme.title(false)))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(false));
+		let width = usize::from(block.inner(area).width);
+
+		f.render_widget(Paragraph::new(lines(width)).block(block), area);
+	}
+}
+
+impl DrawableComponent for CacheDashboardComponent {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		let chunks = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints([
+				Constraint::Percentage(30),
+				Constraint::Percentage(40),
+				Constraint::Percentage(30),
+			])
+			.split(area);
+
+		self.draw_panel(
+			f,
+			chunks[0],
+			strings::title_cache_summary(),
+			|width| self.summary_lines(width),
+		);
+		self.draw_panel(
+			f,
+			chunks[1],
+			strings::title_largest_repos(),
+			|width| self.largest_lines(width),
+		);
+		self.draw_panel(
+			f,
+			chunks[2],
+			strings::title_recent_repos(),
+			|width| self.recent_lines(width),
+		);
+
+		Ok(())
+	}
+}
+
+impl Component for CacheDashboardComponent {
+	fn commands(
+		&self,
+		_out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, _ev: &Event) -> Result<EventState> {
+		Ok(EventState::NotConsumed)
+	}
+}
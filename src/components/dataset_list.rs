@@ -0,0 +1,375 @@
+use super::{
+	utils::{
+		bytes_to_string, scroll_vertical::VerticalScroll,
+		string_width_align,
+	},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, ScrollType,
+};
+use crate::{
+	app::Environment,
+	datasets::{DatasetBuild, DatasetsCache},
+	keys::{key_match, SharedKeyConfig},
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	style::Style,
+	text::{Line, Span},
+	widgets::{Block, Borders, Paragraph},
+	Frame,
+};
+use std::{borrow::Cow, cell::Cell, rc::Rc};
+
+const NUMBER_COLUMN_WIDTH: usize = 12;
+
+/// `(dataset, config, build)` indices
+type BuildIdx = (usize, usize, usize);
+
+#[derive(Clone, Copy)]
+enum Row {
+	Dataset(usize),
+	Build(BuildIdx),
+	Split(BuildIdx, usize),
+	/// map results still in use
+	CacheFiles(BuildIdx),
+	Orphans(BuildIdx),
+}
+
+/// datasets grouped by config with their splits and map results
+pub struct DatasetListComponent {
+	cache: Rc<DatasetsCache>,
+	rows: Vec<Row>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	focused: bool,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DatasetListComponent {
+	/// creates an empty list, filled by `set_cache`
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			cache: Rc::default(),
+			rows: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			focused: true,
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// lists every dataset followed by its builds
+	pub fn set_cache(&mut self, cache: Rc<DatasetsCache>) {
+		let mut rows = Vec::new();
+
+		for (d, dataset) in cache.datasets.iter().enumerate() {
+			rows.push(Row::Dataset(d));
+
+			for (c, config) in dataset.configs.iter().enumerate() {
+				for (b, build) in config.builds.iter().enumerate() {
+					let idx = (d, c, b);
+					rows.push(Row::Build(idx));
+					rows.extend(
+						(0..build.splits.len())
+							.map(|s| Row::Split(idx, s)),
+					);
+					if build.cache_files.len() > build.orphans().count()
+					{
+						rows.push(Row::CacheFiles(idx));
+					}
+					if build.orphans().next().is_some() {
+						rows.push(Row::Orphans(idx));
+					}
+				}
+			}
+		}
+
+		self.rows = rows;
+		self.cache = cache;
+		self.selection =
+			self.selection.min(self.rows.len().saturating_sub(1));
+	}
+
+	fn move_selection(&mut self, scroll: ScrollType) -> bool {
+		let count = self.rows.len();
+		if count == 0 {
+			return false;
+		}
+
+		let page = self.current_height.get().saturating_sub(1).max(1);
+		let old = self.selection;
+		self.selection = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => count - 1,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(count - 1);
+
+		old != self.selection
+	}
+
+	fn build(&self, (d, c, b): BuildIdx) -> &DatasetBuild {
+		&self.cache.datasets[d].configs[c].builds[b]
+	}
+
+	fn columns<'a>(
+		name: &str,
+		rows: Option<u64>,
+		size: u64,
+		width: usize,
+		style: Style,
+	) -> Line<'a> {
+		let name_width = width.saturating_sub(2 * NUMBER_COLUMN_WIDTH);
+		let rows = rows.map(|r| r.to_string()).unwrap_or_default();
+
+		Line::from(vec![
+			Span::styled(string_width_align(name, name_width), style),
+			Span::styled(format!("{rows:>NUMBER_COLUMN_WIDTH$}"), style),
+			Span::styled(
+				format!(
+					"{:>NUMBER_COLUMN_WIDTH$}",
+					bytes_to_string(size)
+				),
+				style,
+			),
+		])
+	}
+
+	fn row_to_line(
+		&self,
+		row: Row,
+		width: usize,
+		selected: bool,
+	) -> Line<'_> {
+		let text = self.theme.text(true, selected);
+
+		match row {
+			Row::Dataset(d) => {
+				let dataset = &self.cache.datasets[d];
+				Self::columns(
+					&dataset.name,
+					None,
+					dataset.size(),
+					width,
+					text,
+				)
+			}
+			Row::Build(idx) => {
+				let build = self.build(idx);
+				let config =
+					&self.cache.datasets[idx.0].configs[idx.1];
+				let hash = strings::short_hash(&build.hash);
+				Self::columns(
+					&format!(
+						"  {} {} {hash}",
+						config.name, build.version
+					),
+					build.num_examples(),
+					build.size,
+					width,
+					self.theme.commit_hash(selected),
+				)
+			}
+			Row::Split(idx, s) => {
+				let split = &self.build(idx).splits[s];
+				Self::columns(
+					&strings::dataset_split(&split.name, split.files),
+					split.num_examples,
+					split.size,
+					width,
+					text,
+				)
+			}
+			Row::CacheFiles(idx) => {
+				let build = self.build(idx);
+				let files = build
+					.cache_files
+					.iter()
+					.filter(|f| f.orphaned.is_none());
+				let (count, size) =
+					files.fold((0, 0), |(count, size), f| {
+						(count + 1, size + f.size)
+					});
+				Self::columns(
+					&strings::dataset_cache_files(count),
+					None,
+					size,
+					width,
+					text,
+				)
+			}
+			Row::Orphans(idx) => {
+				let build = self.build(idx);
+				Self::columns(
+					&strings::dataset_orphans(
+						build.orphans().count(),
+					),
+					None,
+					build.orphaned_size(),
+					width,
+					self.theme.text_danger(),
+				)
+			}
+		}
+	}
+}
+
+impl DrawableComponent for DatasetListComponent {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		let block = Block::default()
+			.title(Span::styled(
+				strings::title_datasets(
+					self.cache.datasets.len(),
+					&bytes_to_string(self.cache.orphaned_size()),
+				),
+				self.theme.title(self.focused),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(self.focused));
+
+		let inner = block.inner(area);
+		f.render_widget(block, area);
+
+		let chunks = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints([Constraint::Length(1), Constraint::Min(0)])
+			.split(inner);
+
+		let width = usize::from(inner.width);
+		let height = usize::from(chunks[1].height);
+		self.current_height.set(height);
+
+		let [name, rows, size] = strings::dataset_table_header();
+		f.render_widget(
+			Paragraph::new(Line::from(vec![
+				Span::styled(
+					string_width_align(
+						&name,
+						width.saturating_sub(2 * NUMBER_COLUMN_WIDTH),
+					),
+					self.theme.title(false),
+				),
+				Span::styled(
+					format!(
+						"{rows:>NUMBER_COLUMN_WIDTH$}{size:>NUMBER_COLUMN_WIDTH$}"
+					),
+					self.theme.title(false),
+				),
+			])),
+			chunks[0],
+		);
+
+		if self.rows.is_empty() {
+			ui::draw_list_block(
+				f,
+				chunks[1],
+				Block::default(),
+				std::iter::once(Span::styled(
+					Cow::from(strings::datasets_empty(
+						&self.cache.root.to_string_lossy(),
+					)),
+					self.theme.text(false, false),
+				)),
+			);
+			return Ok(());
+		}
+
+		self.scroll.update(self.selection, self.rows.len(), height);
+
+		let items = self
+			.rows
+			.iter()
+			.enumerate()
+			.skip(self.scroll.get_top())
+			.take(height)
+			.map(|(idx, row)| {
+				self.row_to_line(
+					*row,
+					width,
+					self.focused && idx == self.selection,
+				)
+			});
+
+		ui::draw_list_block(f, chunks[1], Block::default(), items);
+
+		if self.focused {
+			self.scroll.draw(f, area, &self.theme);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for DatasetListComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		out.push(
+			CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				!self.rows.is_empty(),
+				self.focused,
+			)
+			.order(order::NAV),
+		);
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.focused {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			let scroll = if key_match(key, keys.move_up) {
+				Some(ScrollType::Up)
+			} else if key_match(key, keys.move_down) {
+				Some(ScrollType::Down)
+			} else if key_match(key, keys.home)
+				|| key_match(key, keys.shift_up)
+			{
+				Some(ScrollType::Home)
+			} else if key_match(key, keys.end)
+				|| key_match(key, keys.shift_down)
+			{
+				Some(ScrollType::End)
+			} else if key_match(key, keys.page_up) {
+				Some(ScrollType::PageUp)
+			} else if key_match(key, keys.page_down) {
+				Some(ScrollType::PageDown)
+			} else {
+				None
+			};
+
+			if let Some(scroll) = scroll {
+				self.move_selection(scroll);
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+	}
+}
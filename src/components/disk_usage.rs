@@ -0,0 +1,663 @@
+use super::{
+	utils::{
+		bytes_to_string, scroll_vertical::VerticalScroll,
+		string_width_align,
+	},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, ScrollType,
+};
+use crate::{
+	app::Environment,
+	hub::{folder_usage, CachedRepo, HubCache},
+	keys::{key_match, SharedKeyConfig},
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	style::Style,
+	text::{Line, Span},
+	widgets::{Block, Borders, Paragraph},
+	Frame,
+};
+use std::{
+	cell::Cell,
+	cmp::Reverse,
+	path::{Path, PathBuf},
+	rc::Rc,
+};
+
+const SIZE_COLUMN_WIDTH: usize = 11;
+/// apparent, on disk, shared and freed bytes
+const SIZE_COLUMNS: usize = 4;
+const BAR_WIDTH: usize = 20;
+
+/// part of the cache shown, repos and revisions are kept by
+/// folder and commit so that the view survives rescans
+#[derive(Clone, PartialEq, Eq)]
+enum Level {
+	Cache,
+	/// revisions of the repo stored in the folder
+	Repo(PathBuf),
+	/// entries of `folder`, relative to the snapshot of `commit`
+	Snapshot {
+		repo: PathBuf,
+		commit: String,
+		folder: PathBuf,
+	},
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+	Repo(usize),
+	Revision(usize),
+	Folder,
+	File,
+}
+
+struct Entry {
+	kind: EntryKind,
+	name: String,
+	/// bytes as seen through the snapshot links,
+	/// only known for repos and revisions
+	apparent: Option<u64>,
+	/// bytes on disk
+	size: u64,
+	/// part of `size` also referenced by other revisions
+	shared: u64,
+	/// bytes freed by deleting the entry
+	frees: u64,
+}
+
+impl Entry {
+	/// the size columns as shown, in the order of
+	/// `strings::disk_usage_header`
+	fn size_columns(&self) -> [String; SIZE_COLUMNS] {
+		[
+			self.apparent.map(bytes_to_string).unwrap_or_default(),
+			bytes_to_string(self.size),
+			bytes_to_string(self.shared),
+			bytes_to_string(self.frees),
+		]
+	}
+}
+
+/// one entry per repo of `cache`
+fn repo_entries(cache: &HubCache) -> Vec<Entry> {
+	cache
+		.repos
+		.iter()
+		.enumerate()
+		.map(|(idx, repo)| Entry {
+			kind: EntryKind::Repo(idx),
+			name: match cache.root_of(repo) {
+				Some(root) if cache.roots.len() > 1 => {
+					format!(
+						"{} {}",
+						repo.repo_id,
+						strings::root_tag(root)
+					)
+				}
+				_ => repo.repo_id.clone(),
+			},
+			apparent: Some(repo.sizes.apparent),
+			size: repo.sizes.unique,
+			shared: repo.sizes.shared,
+			frees: repo.sizes.reclaimable,
+		})
+		.collect()
+}
+
+/// one entry per revision of `repo`
+fn revision_entries(repo: &CachedRepo) -> Vec<Entry> {
+	repo.revisions
+		.iter()
+		.enumerate()
+		.map(|(idx, rev)| Entry {
+			kind: EntryKind::Revision(idx),
+			name: format!(
+				"{} {}",
+				strings::short_hash(&rev.commit_hash),
+				rev.refs.join(" ")
+			),
+			apparent: Some(rev.sizes.apparent),
+			size: rev.sizes.unique,
+			shared: rev.sizes.shared,
+			frees: rev.sizes.reclaimable,
+		})
+		.collect()
+}
+
+/// ncdu like view of the disk usage, descending from the cache
+/// through repos and revisions down to single files
+pub struct DiskUsageComponent {
+	cache: Rc<HubCache>,
+	level: Level,
+	/// entries of `level`, largest first
+	entries: Vec<Entry>,
+	/// selection of every level above the current one
+	parents: Vec<usize>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	focused: bool,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl DiskUsageComponent {
+	/// creates an empty view, filled by `set_cache`
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			cache: Rc::default(),
+			level: Level::Cache,
+			entries: Vec::new(),
+			parents: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			focused: true,
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// applies a new scan result, staying at the current level
+	/// unless its repo or revision is gone
+	pub fn set_cache(&mut self, cache: Rc<HubCache>) {
+		self.cache = cache;
+		self.refresh();
+	}
+
+	fn refresh(&mut self) {
+		self.entries = self.load_entries().unwrap_or_else(|| {
+			self.level = Level::Cache;
+			self.parents.clear();
+			repo_entries(&self.cache)
+		});
+		self.entries.sort_by_key(|e| Reverse(e.size));
+		self.selection =
+			self.selection.min(self.entries.len().saturating_sub(1));
+	}
+
+	fn repo(&self, path: &Path) -> Option<&CachedRepo> {
+		self.cache.repos.iter().find(|r| r.path == path)
+	}
+
+	/// entries of `level`, `None` if it no longer exists
+	fn load_entries(&self) -> Option<Vec<Entry>> {
+		match &self.level {
+			Level::Cache => Some(repo_entries(&self.cache)),
+			Level::Repo(path) => self.repo(path).map(revision_entries),
+			Level::Snapshot {
+				repo,
+				commit,
+				folder,
+			} => {
+				let repo = self.repo(repo)?;
+				let rev = repo
+					.revisions
+					.iter()
+					.position(|r| r.commit_hash == *commit)?;
+				Some(
+					folder_usage(repo, rev, folder)
+						.into_iter()
+						.map(|entry| Entry {
+							kind: if entry.is_folder {
+								EntryKind::Folder
+							} else {
+								EntryKind::File
+							},
+							name: entry.name,
+							apparent: None,
+							size: entry.size,
+							shared: entry.size - entry.frees,
+							frees: entry.frees,
+						})
+						.collect(),
+				)
+			}
+		}
+	}
+
+	/// opens the selected entry, false for files
+	fn descend(&mut self) -> bool {
+		let Some(entry) = self.entries.get(self.selection) else {
+			return false;
+		};
+
+		let level = match (&self.level, entry.kind) {
+			(Level::Cache, EntryKind::Repo(idx)) => {
+				Level::Repo(self.cache.repos[idx].path.clone())
+			}
+			(Level::Repo(path), EntryKind::Revision(idx)) => {
+				let Some(rev) =
+					self.repo(path).and_then(|r| r.revisions.get(idx))
+				else {
+					return false;
+				};
+				Level::Snapshot {
+					repo: path.clone(),
+					commit: rev.commit_hash.clone(),
+					folder: PathBuf::new(),
+				}
+			}
+			(
+				Level::Snapshot {
+					repo,
+					commit,
+					folder,
+				},
+				EntryKind::Folder,
+			) => Level::Snapshot {
+				repo: repo.clone(),
+				commit: commit.clone(),
+				folder: folder.join(&entry.name),
+			},
+			_ => return false,
+		};
+
+		self.parents.push(self.selection);
+		self.level = level;
+		self.selection = 0;
+		self.refresh();
+
+		true
+	}
+
+	/// goes back to the level above, reselecting the entry it came from
+	fn climb(&mut self) -> bool {
+		self.level = match &self.level {
+			Level::Cache => return false,
+			Level::Repo(_) => Level::Cache,
+			Level::Snapshot { repo, folder, .. }
+				if folder.as_os_str().is_empty() =>
+			{
+				Level::Repo(repo.clone())
+			}
+			Level::Snapshot {
+				repo,
+				commit,
+				folder,
+			} => Level::Snapshot {
+				repo: repo.clone(),
+				commit: commit.clone(),
+				folder: folder
+					.parent()
+					.map(Path::to_path_buf)
+					.unwrap_or_default(),
+			},
+		};
+		self.selection = self.parents.pop().unwrap_or_default();
+		self.refresh();
+
+		true
+	}
+
+	/// opens the selected entry and below it always the largest one,
+	/// down to a single file
+	fn descend_recursive(&mut self) -> bool {
+		let descended = self.descend();
+		while self.descend() {}
+		descended
+	}
+
+	/// climbs back up to the whole cache
+	fn climb_recursive(&mut self) -> bool {
+		let climbed = self.climb();
+		while self.climb() {}
+		climbed
+	}
+
+	fn move_selection(&mut self, scroll: ScrollType) -> bool {
+		let count = self.entries.len();
+		if count == 0 {
+			return false;
+		}
+
+		let page = self.current_height.get().saturating_sub(1).max(1);
+		let old = self.selection;
+		self.selection = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => count - 1,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(count - 1);
+
+		old != self.selection
+	}
+
+	/// path of the current level, `None` for the whole cache
+	fn location(&self) -> Option<String> {
+		match &self.level {
+			Level::Cache => None,
+			Level::Repo(path) => {
+				self.repo(path).map(|r| r.repo_id.clone())
+			}
+			Level::Snapshot {
+				repo,
+				commit,
+				folder,
+			} => self.repo(repo).map(|r| {
+				let hash = strings::short_hash(commit);
+				let location = format!("{}@{hash}", r.repo_id);
+				if folder.as_os_str().is_empty() {
+					location
+				} else {
+					format!("{location}/{}", folder.display())
+				}
+			}),
+		}
+	}
+
+	/// bytes on disk of the current level
+	fn total(&self) -> u64 {
+		match &self.level {
+			Level::Repo(path) => {
+				self.repo(path).map_or(0, |r| r.sizes.unique)
+			}
+			Level::Cache | Level::Snapshot { .. } => {
+				self.entries.iter().map(|e| e.size).sum()
+			}
+		}
+	}
+
+	fn columns<'a>(
+		sizes: [String; SIZE_COLUMNS],
+		bar: String,
+		name: Vec<Span<'a>>,
+		style: Style,
+	) -> Line<'a> {
+		let mut spans: Vec<_> = sizes
+			.iter()
+			.map(|size| {
+				Span::styled(format!("{size:>SIZE_COLUMN_WIDTH$}"), style)
+			})
+			.collect();
+		spans.push(Span::styled(format!(" {bar:BAR_WIDTH$} "), style));
+		spans.extend(name);
+
+		Line::from(spans)
+	}
+
+	/// `size` relative to the largest entry, `max`
+	fn bar(size: u64, max: u64) -> String {
+		let filled = u128::from(size) * BAR_WIDTH as u128
+			/ u128::from(max.max(1));
+		let filled = usize::try_from(filled).unwrap_or(BAR_WIDTH);
+
+		format!(
+			"{}{}",
+			strings::symbol::BAR_FILLED.repeat(filled),
+			strings::symbol::BAR_EMPTY.repeat(BAR_WIDTH - filled)
+		)
+	}
+
+	fn entry_to_line(
+		&self,
+		entry: &Entry,
+		max: u64,
+		name_width: usize,
+		selected: bool,
+	) -> Line<'_> {
+		let theme = &self.theme;
+		let name = match entry.kind {
+			EntryKind::Repo(idx) => {
+				let repo_type = self.cache.repos[idx].repo_type;
+				vec![
+					Span::styled(
+						format!("{} ", strings::repo_type_icon(repo_type)),
+						theme.repo_type(repo_type, selected),
+					),
+					Span::styled(
+						string_width_align(
+							&entry.name,
+							name_width.saturating_sub(2),
+						),
+						theme.text(true, selected),
+					),
+				]
+			}
+			EntryKind::Revision(_) => vec![Span::styled(
+				string_width_align(&entry.name, name_width),
+				theme.commit_hash(selected),
+			)],
+			EntryKind::Folder => vec![Span::styled(
+				string_width_align(
+					&format!("{}/", entry.name),
+					name_width,
+				),
+				theme.file_tree_item(true, selected),
+			)],
+			EntryKind::File => vec![Span::styled(
+				string_width_align(&entry.name, name_width),
+				theme.file_tree_item(false, selected),
+			)],
+		};
+
+		Self::columns(
+			entry.size_columns(),
+			Self::bar(entry.size, max),
+			name,
+			theme.text(true, selected),
+		)
+	}
+}
+
+impl DrawableComponent for DiskUsageComponent {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		let block = Block::default()
+			.title(Span::styled(
+				strings::title_disk_usage(
+					self.location().as_deref(),
+					&bytes_to_string(self.total()),
+				),
+				self.theme.title(self.focused),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(self.focused));
+
+		let inner = block.inner(area);
+		f.render_widget(block, area);
+
+		let chunks = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints([Constraint::Length(1), Constraint::Min(0)])
+			.split(inner);
+
+		let width = usize::from(inner.width);
+		let height = usize::from(chunks[1].height);
+		self.current_height.set(height);
+
+		let name_width = width.saturating_sub(
+			SIZE_COLUMNS * SIZE_COLUMN_WIDTH + BAR_WIDTH + 2,
+		);
+
+		let [apparent, size, shared, frees, name] =
+			strings::disk_usage_header();
+		let style = self.theme.title(false);
+		f.render_widget(
+			Paragraph::new(Self::columns(
+				[apparent, size, shared, frees],
+				String::new(),
+				vec![Span::styled(name, style)],
+				style,
+			)),
+			chunks[0],
+		);
+
+		self.scroll.update(self.selection, self.entries.len(), height);
+
+		let max = self.entries.first().map_or(0, |e| e.size);
+		let items = self
+			.entries
+			.iter()
+			.enumerate()
+			.skip(self.scroll.get_top())
+			.take(height)
+			.map(|(idx, entry)| {
+				self.entry_to_line(
+					entry,
+					max,
+					name_width,
+					self.focused && idx == self.selection,
+				)
+			});
+
+		ui::draw_list_block(f, chunks[1], Block::default(), items);
+
+		if self.focused {
+			self.scroll.draw(f, area, &self.theme);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for DiskUsageComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		out.push(
+			CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				!self.entries.is_empty(),
+				self.focused,
+			)
+			.order(order::NAV),
+		);
+		out.push(CommandInfo::new(
+			strings::commands::usage_descend(&self.key_config),
+			self.entries
+				.get(self.selection)
+				.is_some_and(|e| e.kind != EntryKind::File),
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::usage_climb(&self.key_config),
+			self.level != Level::Cache,
+			self.focused,
+		));
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.focused {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			let scroll = if key_match(key, keys.move_up) {
+				Some(ScrollType::Up)
+			} else if key_match(key, keys.move_down) {
+				Some(ScrollType::Down)
+			} else if key_match(key, keys.home)
+				|| key_match(key, keys.shift_up)
+			{
+				Some(ScrollType::Home)
+			} else if key_match(key, keys.end)
+				|| key_match(key, keys.shift_down)
+			{
+				Some(ScrollType::End)
+			} else if key_match(key, keys.page_up) {
+				Some(ScrollType::PageUp)
+			} else if key_match(key, keys.page_down) {
+				Some(ScrollType::PageDown)
+			} else {
+				None
+			};
+
+			let consumed = if let Some(scroll) = scroll {
+				self.move_selection(scroll);
+				true
+			} else if key_match(key, keys.enter)
+				|| key_match(key, keys.move_right)
+			{
+				self.descend()
+			} else if key_match(key, keys.move_left) {
+				self.climb()
+			} else if key_match(key, keys.tree_expand_recursive) {
+				self.descend_recursive()
+			} else if key_match(key, keys.tree_collapse_recursive) {
+				self.climb_recursive()
+			} else {
+				false
+			};
+
+			if consumed {
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::hub::{CachedRevision, SizeInfo};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_size_columns() {
+		let sizes = |apparent, unique, shared, reclaimable| SizeInfo {
+			apparent,
+			unique,
+			shared,
+			reclaimable,
+		};
+		let cache = HubCache {
+			repos: vec![CachedRepo {
+				repo_id: "org/model".to_string(),
+				revisions: vec![CachedRevision {
+					commit_hash: "aaaa".to_string(),
+					sizes: sizes(2048, 1024, 512, 512),
+					..Default::default()
+				}],
+				sizes: sizes(4096, 2048, 512, 2048),
+				..Default::default()
+			}],
+			..Default::default()
+		};
+
+		let columns = |entries: Vec<Entry>| {
+			entries.iter().map(Entry::size_columns).collect::<Vec<_>>()
+		};
+		assert_eq!(
+			columns(repo_entries(&cache)),
+			vec![[
+				bytes_to_string(4096),
+				bytes_to_string(2048),
+				bytes_to_string(512),
+				bytes_to_string(2048),
+			]]
+		);
+		assert_eq!(
+			columns(revision_entries(&cache.repos[0])),
+			vec![[
+				bytes_to_string(2048),
+				bytes_to_string(1024),
+				bytes_to_string(512),
+				bytes_to_string(512),
+			]]
+		);
+	}
+}
@@ -0,0 +1,479 @@
+use super::{
+	utils::hex::{
+		detect_format, read_at, AsyncHexSearchJob, BlobFormat,
+	},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, ViewJump,
+};
+use crate::{
+	app::Environment,
+	asyncjob::AsyncSingleJob,
+	hub::open_untouched,
+	keys::{key_match, SharedKeyConfig},
+	popups::ViewInputKind,
+	progress::ProgressPercent,
+	queue::{InternalEvent, Queue},
+	strings::{self, order},
+	ui::{
+		self, common_nav, style::SharedTheme, ParagraphState,
+		StatefulParagraph,
+	},
+	AsyncAppNotification, HexSearchProgress,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use filetreelist::MoveSelection;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span, Text},
+	widgets::{Block, Borders},
+	Frame,
+};
+use std::{
+	cell::Cell,
+	fs::File,
+	path::{Path, PathBuf},
+};
+
+/// bytes per row, the widest fitting the pane is used
+const ROW_SIZES: [u64; 3] = [16, 8, 4];
+/// bytes read to tell the format apart
+const MAGIC_SIZE: u64 = 64;
+/// hex digits of the offset column at least
+const MIN_OFFSET_DIGITS: usize = 8;
+
+/// pages through a file on disk as hex and ASCII,
+/// reading only the shown rows
+pub struct HexViewComponent {
+	file: Option<(PathBuf, File)>,
+	len: u64,
+	format: Option<BlobFormat>,
+	/// offset of the first shown row
+	top: Cell<u64>,
+	row_size: Cell<u64>,
+	page_rows: Cell<u64>,
+	paragraph_state: Cell<ParagraphState>,
+	/// highlighted bytes: the last match or the offset jumped to
+	mark: Option<(u64, u64)>,
+	pattern: Vec<u8>,
+	search: AsyncSingleJob<AsyncHexSearchJob>,
+	search_progress: Option<ProgressPercent>,
+	/// outcome of the last jump or search
+	message: Option<String>,
+	focused: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl HexViewComponent {
+	/// creates an empty view
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			file: None,
+			len: 0,
+			format: None,
+			top: Cell::new(0),
+			row_size: Cell::new(ROW_SIZES[0]),
+			page_rows: Cell::new(0),
+			paragraph_state: Cell::new(ParagraphState::default()),
+			mark: None,
+			pattern: Vec::new(),
+			search: AsyncSingleJob::new(env.sender_app.clone()),
+			search_progress: None,
+			message: None,
+			focused: false,
+			queue: env.queue.clone(),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// opens the file at `path`, reading its first bytes only
+	pub fn load_file(&mut self, path: &Path) -> Result<()> {
+		self.clear();
+
+		let file = open_untouched(path)?;
+		self.len = file.metadata()?.len();
+		self.format =
+			detect_format(&read_at(&file, 0, MAGIC_SIZE)?, self.len);
+		self.file = Some((path.to_path_buf(), file));
+
+		Ok(())
+	}
+
+	/// shows nothing
+	pub fn clear(&mut self) {
+		self.file = None;
+		self.len = 0;
+		self.format = None;
+		self.top.set(0);
+		self.mark = None;
+		self.pattern.clear();
+		self.search_progress = None;
+		self.message = None;
+	}
+
+	/// picks up progress and results of the pattern search
+	pub fn update(&mut self, ev: AsyncAppNotification) {
+		let AsyncAppNotification::HexSearch(progress) = ev else {
+			return;
+		};
+
+		match progress {
+			HexSearchProgress::Progress => {
+				self.search_progress = self.search.progress();
+			}
+			HexSearchProgress::Done => {
+				// the notification may be meant for another view
+				if !self.search.is_pending() {
+					self.search_progress = None;
+				}
+				let Some(job) = self.search.take_last() else {
+					return;
+				};
+				if self.path() != Some(job.path()) {
+					return;
+				}
+
+				self.message = Some(match job.take_result() {
+					Some(Ok(Some(offset))) => {
+						self.select(offset, self.pattern.len() as u64);
+						strings::hex_found(offset)
+					}
+					Some(Ok(None)) => strings::hex_not_found(),
+					Some(Err(e)) => strings::code_load_error(&e.to_string()),
+					None => return,
+				});
+			}
+		}
+	}
+
+	/// true while a search runs
+	pub fn any_work_pending(&self) -> bool {
+		self.search.is_pending()
+	}
+
+	/// applies a jump or search asked for in the input popup,
+	/// if this view has focus
+	pub fn jump(&mut self, jump: &ViewJump) {
+		if !self.focused || self.file.is_none() {
+			return;
+		}
+
+		match jump {
+			ViewJump::Offset(offset) => {
+				if *offset < self.len {
+					self.select(*offset, 1);
+					self.message = None;
+				} else {
+					self.message = Some(strings::hex_beyond_end(self.len));
+				}
+			}
+			ViewJump::Bytes(pattern) => {
+				self.pattern.clone_from(pattern);
+				self.search_from(self.top.get());
+			}
+			ViewJump::Text(_) => {}
+		}
+	}
+
+	fn path(&self) -> Option<&Path> {
+		self.file.as_ref().map(|(path, _)| path.as_path())
+	}
+
+	fn search_from(&mut self, from: u64) {
+		let Some(path) = self.path() else {
+			return;
+		};
+
+		let job =
+			AsyncHexSearchJob::new(path, from, self.pattern.clone());
+		self.search_progress = Some(ProgressPercent::default());
+		self.message = None;
+		self.search.spawn(job);
+	}
+
+	fn search_next(&mut self) -> bool {
+		if self.pattern.is_empty() || self.search.is_pending() {
+			return false;
+		}
+
+		let from = self
+			.mark
+			.map_or_else(|| self.top.get(), |(offset, _)| offset + 1);
+		self.search_from(from);
+
+		true
+	}
+
+	/// marks `len` bytes at `offset` and scrolls them to the top
+	fn select(&mut self, offset: u64, len: u64) {
+		self.mark = Some((offset, len));
+		self.top.set(offset);
+	}
+
+	/// top row offset keeping the last page full
+	fn max_top(&self) -> u64 {
+		let row = self.row_size.get();
+		let rows = self.len.div_ceil(row);
+
+		rows.saturating_sub(self.page_rows.get()) * row
+	}
+
+	fn scroll(&self, nav: MoveSelection) -> bool {
+		let row = self.row_size.get();
+		let page = self.page_rows.get().saturating_sub(1).max(1) * row;
+		let top = self.top.get();
+
+		let new_top = match nav {
+			MoveSelection::Down => top.saturating_add(row),
+			MoveSelection::Up => top.saturating_sub(row),
+			MoveSelection::Top => 0,
+			MoveSelection::End => self.max_top(),
+			MoveSelection::PageUp => top.saturating_sub(page),
+			MoveSelection::PageDown => top.saturating_add(page),
+			MoveSelection::Left | MoveSelection::Right => top,
+		}
+		.min(self.max_top());
+
+		self.top.set(new_top);
+
+		new_top != top
+	}
+
+	fn offset_digits(&self) -> usize {
+		let digits = (u64::BITS - self.len.leading_zeros()).div_ceil(4);
+
+		(digits as usize).max(MIN_OFFSET_DIGITS)
+	}
+
+	/// widest row size whose offset, hex and ASCII columns fit `width`
+	fn fitting_row_size(&self, width: usize) -> u64 {
+		let offset = self.offset_digits();
+
+		ROW_SIZES
+			.iter()
+			.copied()
+			.find(|size| {
+				let size = *size as usize;
+				// a gap after every 8 bytes
+				let gaps = (size / 8).saturating_sub(1);
+				offset + 2 + size * 3 + gaps + size + 2 <= width
+			})
+			.unwrap_or(ROW_SIZES[ROW_SIZES.len() - 1])
+	}
+
+	fn row_to_line(&self, offset: u64, bytes: &[u8]) -> Line<'static> {
+		let row = self.row_size.get() as usize;
+		let marked = |idx: usize| {
+			let pos = offset + idx as u64;
+			self.mark.is_some_and(|(start, len)| {
+				pos >= start && pos < start + len
+			})
+		};
+		let byte_style = |idx: usize, byte: u8| {
+			self.theme.text(byte != 0 || marked(idx), marked(idx))
+		};
+
+		let mut spans = vec![Span::styled(
+			format!("{offset:0width$x}  ", width = self.offset_digits()),
+			self.theme.commit_hash(false),
+		)];
+
+		for idx in 0..row {
+			let gap = if idx % 8 == 7 && idx + 1 < row { "  " } else { " " };
+			spans.push(match bytes.get(idx) {
+				Some(byte) => {
+					Span::styled(format!("{byte:02x}"), byte_style(idx, *byte))
+				}
+				None => Span::raw("  "),
+			});
+			spans.push(Span::raw(gap));
+		}
+
+		spans.push(Span::styled("|", self.theme.text(false, false)));
+		for (idx, byte) in bytes.iter().enumerate() {
+			let c = if byte.is_ascii_graphic() || *byte == b' ' {
+				char::from(*byte)
+			} else {
+				'.'
+			};
+			spans.push(Span::styled(
+				c.to_string(),
+				self.theme.text(
+					c != '.' || marked(idx),
+					marked(idx),
+				),
+			));
+		}
+		spans.push(Span::styled("|", self.theme.text(false, false)));
+
+		Line::from(spans)
+	}
+
+	fn page_text(&self) -> Text<'static> {
+		let Some((_, file)) = &self.file else {
+			return Text::default();
+		};
+
+		let row = self.row_size.get();
+		let top = self.top.get();
+		let page = match read_at(file, top, self.page_rows.get() * row) {
+			Ok(page) => page,
+			Err(e) => {
+				return Text::from(strings::code_load_error(&e.to_string()));
+			}
+		};
+
+		Text::from(
+			page.chunks(row as usize)
+				.enumerate()
+				.map(|(idx, bytes)| {
+					self.row_to_line(top + idx as u64 * row, bytes)
+				})
+				.collect::<Vec<_>>(),
+		)
+	}
+
+	fn title(&self) -> String {
+		let name = self
+			.path()
+			.and_then(Path::file_name)
+			.map_or_else(strings::title_code, |n| {
+				n.to_string_lossy().into_owned()
+			});
+
+		strings::title_hex(&name, self.format)
+	}
+
+	fn status(&self) -> String {
+		let position = strings::hex_position(self.top.get(), self.len);
+
+		match (self.search_progress, &self.message) {
+			(Some(p), _) => {
+				format!("{position}  {}", strings::hex_searching(p.progress))
+			}
+			(None, Some(msg)) => format!("{position}  {msg}"),
+			(None, None) => position,
+		}
+	}
+}
+
+impl DrawableComponent for HexViewComponent {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		let width = usize::from(area.width.saturating_sub(2));
+		let row = self.fitting_row_size(width);
+		self.row_size.set(row);
+		self.page_rows.set(u64::from(area.height.saturating_sub(2)));
+
+		// align to the row, keeping the last page full
+		let top = self.top.get().min(self.max_top());
+		self.top.set(top - top % row);
+
+		let content = StatefulParagraph::new(self.page_text()).block(
+			Block::default()
+				.title(Span::styled(
+					self.title(),
+					self.theme.title(self.focused),
+				))
+				.title_bottom(Span::styled(
+					self.status(),
+					self.theme.text(self.focused, false),
+				))
+				.borders(Borders::ALL)
+				.border_style(self.theme.block(self.focused)),
+		);
+
+		let mut state = self.paragraph_state.get();
+		f.render_stateful_widget(content, area, &mut state);
+		self.paragraph_state.set(state);
+
+		if self.focused {
+			let row = self.row_size.get();
+			ui::draw_scrollbar(
+				f,
+				area,
+				&self.theme,
+				usize::try_from(self.max_top() / row).unwrap_or(usize::MAX),
+				usize::try_from(self.top.get() / row)
+					.unwrap_or(usize::MAX),
+				ui::Orientation::Vertical,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for HexViewComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		let open = self.file.is_some();
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				open,
+				self.focused,
+			)
+			.order(order::NAV),
+		);
+		out.push(CommandInfo::new(
+			strings::commands::goto_offset(&self.key_config),
+			open,
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::search_bytes(&self.key_config),
+			open,
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::search_next(&self.key_config),
+			open && !self.pattern.is_empty() && !self.search.is_pending(),
+			self.focused,
+		));
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.focused || self.file.is_none() {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			if let Some(nav) = common_nav(key, &self.key_config) {
+				self.scroll(nav);
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.goto_offset) {
+				self.queue.push(InternalEvent::OpenViewInput(
+					ViewInputKind::Offset,
+				));
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.search) {
+				self.queue.push(InternalEvent::OpenViewInput(
+					ViewInputKind::Pattern,
+				));
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.search_next) {
+				return Ok(self.search_next().into());
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+	}
+}
@@ -1,7 +1,15 @@
 
 mod command;
-mod utils;
+mod dashboard;
+mod dataset_list;
+mod disk_usage;
+mod hex_view;
+mod module_list;
+mod repo_list;
 mod revision_files;
+mod syntax_text;
+mod text_stream;
+mod utils;
 
 use crate::ui::style::Theme;
 use anyhow::Result;
@@ -14,8 +22,62 @@ use ratatui::{
 };
 
 
-pub use revision_files::RevisionFilesComponent;
 pub use command::{CommandInfo, CommandText};
+pub use dashboard::CacheDashboardComponent;
+pub use dataset_list::DatasetListComponent;
+pub use disk_usage::DiskUsageComponent;
+pub use hex_view::HexViewComponent;
+pub use module_list::ModuleListComponent;
+pub use repo_list::RepoListComponent;
+pub use revision_files::RevisionFilesComponent;
+pub use syntax_text::{SyntaxTextComponent, ViewJump};
+pub use text_stream::TextStreamComponent;
+pub use utils::{
+	bytes_delta_to_string, bytes_to_string,
+	hex::{self, BlobFormat},
+	is_binary, read_head,
+	scroll_vertical::VerticalScroll,
+};
+
+/// forward event to all components until one consumes it
+pub fn event_pump(
+	ev: &Event,
+	components: &mut [&mut dyn Component],
+) -> Result<EventState> {
+	for c in components {
+		if c.event(ev)?.is_consumed() {
+			return Ok(EventState::Consumed);
+		}
+	}
+
+	Ok(EventState::NotConsumed)
+}
+
+/// collect commands of all components until one is blocking
+pub fn command_pump(
+	out: &mut Vec<CommandInfo>,
+	force_all: bool,
+	components: &[&dyn Component],
+) {
+	for c in components {
+		if c.commands(out, force_all) != CommandBlocking::PassingOn
+			&& !force_all
+		{
+			break;
+		}
+	}
+}
+
+/// visible components block the ones below them
+pub fn visibility_blocking<T: Component>(
+	comp: &T,
+) -> CommandBlocking {
+	if comp.is_visible() {
+		CommandBlocking::Blocking
+	} else {
+		CommandBlocking::PassingOn
+	}
+}
 
 #[derive(Copy, Clone)]
 pub enum ScrollType {
@@ -39,13 +101,36 @@ pub enum Direction {
 	Down,
 }
 
-///
+/// whether commands of components behind this one are still listed
 #[derive(PartialEq, Eq)]
 pub enum CommandBlocking {
 	Blocking,
 	PassingOn,
 }
 
+/// whether a component handled an event, see `event_pump`
+#[derive(PartialEq, Eq)]
+pub enum EventState {
+	Consumed,
+	NotConsumed,
+}
+
+impl EventState {
+	pub fn is_consumed(&self) -> bool {
+		*self == Self::Consumed
+	}
+}
+
+impl From<bool> for EventState {
+	fn from(consumed: bool) -> Self {
+		if consumed {
+			Self::Consumed
+		} else {
+			Self::NotConsumed
+		}
+	}
+}
+
 
 pub trait DrawableComponent {
 	///
@@ -54,14 +139,14 @@ pub trait DrawableComponent {
 /// base component trait
 pub trait Component {
 	///
-	// fn commands(
-	// 	&self,
-	// 	out: &mut Vec<CommandInfo>,
-	// 	force_all: bool,
-	// ) -> CommandBlocking;
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking;
 
 	///
-	// fn event(&mut self, ev: &Event) -> Result<EventState>;
+	fn event(&mut self, ev: &Event) -> Result<EventState>;
 
 	///
 	fn focused(&self) -> bool {
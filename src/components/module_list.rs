@@ -0,0 +1,350 @@
+use super::{
+	utils::{
+		bytes_to_string, scroll_vertical::VerticalScroll,
+		string_width_align,
+	},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, ScrollType,
+};
+use crate::{
+	app::Environment,
+	hub::HubCache,
+	keys::{key_match, SharedKeyConfig},
+	modules::{module_link, ModuleLink, ModulesCache},
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	style::Style,
+	text::{Line, Span},
+	widgets::{Block, Borders},
+	Frame,
+};
+use std::{borrow::Cow, cell::Cell, path::PathBuf, rc::Rc};
+
+const LINK_COLUMN_WIDTH: usize = 15;
+const SIZE_COLUMN_WIDTH: usize = 12;
+
+#[derive(Clone, Copy)]
+enum Row {
+	Module(usize),
+	/// `(module, revision)`
+	Revision(usize, usize),
+	/// `(module, revision, file)`
+	File(usize, usize, usize),
+}
+
+/// module copies grouped by repo, with their revisions and files
+pub struct ModuleListComponent {
+	modules: Rc<ModulesCache>,
+	hub: Rc<HubCache>,
+	/// link of every revision, indexed like `modules`
+	links: Vec<Vec<ModuleLink>>,
+	rows: Vec<Row>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	focused: bool,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl ModuleListComponent {
+	/// creates an empty list, filled by `set_modules`
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			modules: Rc::default(),
+			hub: Rc::default(),
+			links: Vec::new(),
+			rows: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			focused: true,
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// lists every module followed by its revisions and their files
+	pub fn set_modules(&mut self, modules: Rc<ModulesCache>) {
+		let mut rows = Vec::new();
+
+		for (m, module) in modules.modules.iter().enumerate() {
+			rows.push(Row::Module(m));
+
+			for (r, revision) in module.revisions.iter().enumerate() {
+				rows.push(Row::Revision(m, r));
+				rows.extend(
+					(0..revision.files.len()).map(|f| Row::File(m, r, f)),
+				);
+			}
+		}
+
+		self.rows = rows;
+		self.modules = modules;
+		self.selection =
+			self.selection.min(self.rows.len().saturating_sub(1));
+		self.update_links();
+	}
+
+	/// relinks the modules to a new scan of the hub cache
+	pub fn set_hub(&mut self, hub: Rc<HubCache>) {
+		self.hub = hub;
+		self.update_links();
+	}
+
+	/// absolute path of the file under the cursor
+	pub fn selected_file(&self) -> Option<PathBuf> {
+		match self.rows.get(self.selection)? {
+			Row::File(m, r, f) => {
+				let revision = &self.modules.modules[*m].revisions[*r];
+				Some(revision.path.join(&revision.files[*f].path))
+			}
+			Row::Module(_) | Row::Revision(..) => None,
+		}
+	}
+
+	fn update_links(&mut self) {
+		self.links = self
+			.modules
+			.modules
+			.iter()
+			.map(|module| {
+				module
+					.revisions
+					.iter()
+					.map(|r| module_link(&self.hub, &module.repo_id, r))
+					.collect()
+			})
+			.collect();
+	}
+
+	fn stale_count(&self) -> usize {
+		self.links.iter().flatten().filter(|l| l.is_stale()).count()
+	}
+
+	fn move_selection(&mut self, scroll: ScrollType) -> bool {
+		let count = self.rows.len();
+		if count == 0 {
+			return false;
+		}
+
+		let page = self.current_height.get().saturating_sub(1).max(1);
+		let old = self.selection;
+		self.selection = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => count - 1,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(count - 1);
+
+		old != self.selection
+	}
+
+	fn columns<'a>(
+		name: &str,
+		link: &str,
+		size: u64,
+		width: usize,
+		style: Style,
+	) -> Line<'a> {
+		let name_width = width
+			.saturating_sub(LINK_COLUMN_WIDTH + SIZE_COLUMN_WIDTH);
+
+		Line::from(vec![
+			Span::styled(string_width_align(name, name_width), style),
+			Span::styled(format!("{link:>LINK_COLUMN_WIDTH$}"), style),
+			Span::styled(
+				format!(
+					"{:>SIZE_COLUMN_WIDTH$}",
+					bytes_to_string(size)
+				),
+				style,
+			),
+		])
+	}
+
+	fn row_to_line(
+		&self,
+		row: Row,
+		width: usize,
+		selected: bool,
+	) -> Line<'_> {
+		let text = self.theme.text(true, selected);
+
+		match row {
+			Row::Module(m) => {
+				let module = &self.modules.modules[m];
+				Self::columns(
+					&module.repo_id,
+					"",
+					module.size(),
+					width,
+					text,
+				)
+			}
+			Row::Revision(m, r) => {
+				let revision = &self.modules.modules[m].revisions[r];
+				let link = self.links[m][r];
+				let name = revision.commit_hash.as_ref().map_or_else(
+					|| format!("  {}", revision.path.display()),
+					|hash| {
+						format!(
+							"  {}",
+							strings::short_hash(hash)
+						)
+					},
+				);
+				Self::columns(
+					&name,
+					strings::module_link(link),
+					revision.size(),
+					width,
+					if link.is_stale() {
+						self.theme.text_danger()
+					} else {
+						self.theme.commit_hash(selected)
+					},
+				)
+			}
+			Row::File(m, r, f) => {
+				let file = &self.modules.modules[m].revisions[r].files[f];
+				Self::columns(
+					&format!("    {}", file.path.display()),
+					"",
+					file.size,
+					width,
+					text,
+				)
+			}
+		}
+	}
+}
+
+impl DrawableComponent for ModuleListComponent {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		let height = usize::from(area.height.saturating_sub(2));
+		let width = usize::from(area.width.saturating_sub(2));
+		self.current_height.set(height);
+
+		let block = Block::default()
+			.title(Span::styled(
+				strings::title_modules(
+					self.modules.modules.len(),
+					self.stale_count(),
+				),
+				self.theme.title(self.focused),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(self.focused));
+
+		if self.rows.is_empty() {
+			ui::draw_list_block(
+				f,
+				area,
+				block,
+				std::iter::once(Span::styled(
+					Cow::from(strings::modules_empty(
+						&self.modules.root.to_string_lossy(),
+					)),
+					self.theme.text(false, false),
+				)),
+			);
+			return Ok(());
+		}
+
+		self.scroll.update(self.selection, self.rows.len(), height);
+
+		let items = self
+			.rows
+			.iter()
+			.enumerate()
+			.skip(self.scroll.get_top())
+			.take(height)
+			.map(|(idx, row)| {
+				self.row_to_line(
+					*row,
+					width,
+					self.focused && idx == self.selection,
+				)
+			});
+
+		ui::draw_list_block(f, area, block, items);
+
+		if self.focused {
+			self.scroll.draw(f, area, &self.theme);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for ModuleListComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		out.push(
+			CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				!self.rows.is_empty(),
+				self.focused,
+			)
+			.order(order::NAV),
+		);
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.focused {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			let scroll = if key_match(key, keys.move_up) {
+				Some(ScrollType::Up)
+			} else if key_match(key, keys.move_down) {
+				Some(ScrollType::Down)
+			} else if key_match(key, keys.home)
+				|| key_match(key, keys.shift_up)
+			{
+				Some(ScrollType::Home)
+			} else if key_match(key, keys.end)
+				|| key_match(key, keys.shift_down)
+			{
+				Some(ScrollType::End)
+			} else if key_match(key, keys.page_up) {
+				Some(ScrollType::PageUp)
+			} else if key_match(key, keys.page_down) {
+				Some(ScrollType::PageDown)
+			} else {
+				None
+			};
+
+			if let Some(scroll) = scroll {
+				self.move_selection(scroll);
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+	}
+}
@@ -0,0 +1,638 @@
+use super::{
+	utils::{
+		bytes_to_string, now_secs, scroll_horizontal::HorizontalScroll,
+		scroll_vertical::VerticalScroll, string_width_align,
+		time_to_relative_string, time_to_string,
+	},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, HorizontalScrollType, ScrollType,
+};
+use crate::{
+	app::Environment,
+	args::CacheRoot,
+	hub::{newest_revision, CachedRepo, HubCache, RepoType},
+	keys::{key_match, SharedKeyConfig},
+	progress::ProgressPercent,
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Line, Span},
+	widgets::{Block, Borders, Paragraph},
+	Frame,
+};
+use std::{borrow::Cow, cell::Cell, cmp::Ordering, path::Path, rc::Rc};
+
+/// the repo column keeps at least this width before others scroll out
+const MIN_REPO_WIDTH: usize = 30;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Column {
+	Repo,
+	Type,
+	Size,
+	Revisions,
+	Files,
+	Refs,
+	Modified,
+	Accessed,
+}
+
+impl Column {
+	/// all columns in display order, as in `strings::repo_table_header`
+	const ALL: [Self; 8] = [
+		Self::Repo,
+		Self::Type,
+		Self::Size,
+		Self::Revisions,
+		Self::Files,
+		Self::Refs,
+		Self::Modified,
+		Self::Accessed,
+	];
+
+	/// width of the column, the minimum for `Repo` which takes the rest
+	const fn width(self) -> usize {
+		match self {
+			Self::Repo => MIN_REPO_WIDTH,
+			Self::Type => 10,
+			Self::Size => 11,
+			Self::Revisions => 6,
+			Self::Files => 7,
+			Self::Refs => 16,
+			Self::Modified => 12,
+			Self::Accessed => 14,
+		}
+	}
+
+	const fn right_aligned(self) -> bool {
+		matches!(
+			self,
+			Self::Size
+				| Self::Revisions
+				| Self::Files
+				| Self::Modified
+				| Self::Accessed
+		)
+	}
+
+	const fn next(self) -> Self {
+		match self {
+			Self::Repo => Self::Type,
+			Self::Type => Self::Size,
+			Self::Size => Self::Revisions,
+			Self::Revisions => Self::Files,
+			Self::Files => Self::Refs,
+			Self::Refs => Self::Modified,
+			Self::Modified => Self::Accessed,
+			Self::Accessed => Self::Repo,
+		}
+	}
+
+	fn compare(self, a: &CachedRepo, b: &CachedRepo) -> Ordering {
+		match self {
+			Self::Repo => a.repo_id.cmp(&b.repo_id),
+			Self::Type => a.repo_type.cmp(&b.repo_type),
+			Self::Size => a.sizes.unique.cmp(&b.sizes.unique),
+			Self::Revisions => {
+				a.revisions.len().cmp(&b.revisions.len())
+			}
+			Self::Files => a.file_count().cmp(&b.file_count()),
+			Self::Refs => a.refs.len().cmp(&b.refs.len()),
+			Self::Modified => {
+				newest_revision(a).cmp(&newest_revision(b))
+			}
+			Self::Accessed => a.last_accessed.cmp(&b.last_accessed),
+		}
+	}
+}
+
+/// table of all repos found in the hub cache, sortable by every column
+pub struct RepoListComponent {
+	cache: Rc<HubCache>,
+	/// only repos of this type are listed, all if `None`
+	filter: Option<RepoType>,
+	/// indices into `cache.repos` passing `filter`, in sort order
+	shown: Vec<usize>,
+	sort: Column,
+	reverse: bool,
+	scan_progress: Option<ProgressPercent>,
+	/// seconds without access after which a repo is highlighted
+	stale_after: i64,
+	selection: usize,
+	scroll: VerticalScroll,
+	/// index of the first column shown after the repo column
+	hscroll: HorizontalScroll,
+	current_height: Cell<usize>,
+	focused: bool,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl RepoListComponent {
+	/// creates an empty list, filled by `set_cache`
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			cache: Rc::default(),
+			filter: None,
+			shown: Vec::new(),
+			sort: Column::Repo,
+			reverse: false,
+			scan_progress: None,
+			stale_after: env.options.stale_after_secs(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			hscroll: HorizontalScroll::new(),
+			current_height: Cell::new(0),
+			focused: true,
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// replaces the listed repos, keeping the selected repo if it still exists
+	pub fn set_cache(&mut self, cache: Rc<HubCache>) {
+		self.cache = cache;
+		self.update_shown();
+	}
+
+	/// switches to the next filter: all, models, datasets, spaces
+	pub fn cycle_filter(&mut self) {
+		self.filter = match self.filter {
+			None => Some(RepoType::Model),
+			Some(RepoType::Model) => Some(RepoType::Dataset),
+			Some(RepoType::Dataset) => Some(RepoType::Space),
+			Some(RepoType::Space) => None,
+		};
+		self.update_shown();
+	}
+
+	/// sorts by the next column, ascending
+	pub fn cycle_sort(&mut self) {
+		self.sort = self.sort.next();
+		self.reverse = false;
+		self.update_shown();
+	}
+
+	/// flips the sort order
+	pub fn reverse_sort(&mut self) {
+		self.reverse = !self.reverse;
+		self.update_shown();
+	}
+
+	fn update_shown(&mut self) {
+		let selected = self
+			.selected_repo()
+			.map(|r| (r.repo_id.clone(), r.repo_type, r.root));
+
+		self.shown = self
+			.cache
+			.repos
+			.iter()
+			.enumerate()
+			.filter(|(_, r)| self.filter.is_none_or(|t| r.repo_type == t))
+			.map(|(idx, _)| idx)
+			.collect();
+
+		let repos = &self.cache.repos;
+		let (sort, reverse) = (self.sort, self.reverse);
+		self.shown.sort_by(|a, b| {
+			let order = sort.compare(&repos[*a], &repos[*b]);
+			if reverse {
+				order.reverse()
+			} else {
+				order
+			}
+		});
+
+		self.selection = selected
+			.and_then(|(id, repo_type, root)| {
+				self.shown.iter().position(|idx| {
+					let r = &self.cache.repos[*idx];
+					r.repo_id == id
+						&& r.repo_type == repo_type
+						&& r.root == root
+				})
+			})
+			.unwrap_or_default()
+			.min(self.shown.len().saturating_sub(1));
+	}
+
+	/// progress shown in the title while a scan is running
+	pub fn set_scan_progress(&mut self, progress: Option<ProgressPercent>) {
+		self.scan_progress = progress;
+	}
+
+	/// repo under the cursor
+	pub fn selected_repo(&self) -> Option<&CachedRepo> {
+		self.shown
+			.get(self.selection)
+			.and_then(|idx| self.cache.repos.get(*idx))
+	}
+
+	/// moves the cursor to the repo in `folder`,
+	/// dropping the filter if it hides the repo
+	pub fn select_repo(&mut self, folder: &Path) -> bool {
+		let find = |list: &Self| {
+			list.shown
+				.iter()
+				.position(|idx| list.cache.repos[*idx].path == folder)
+		};
+
+		let mut position = find(self);
+		if position.is_none() && self.filter.is_some() {
+			self.filter = None;
+			self.update_shown();
+			position = find(self);
+		}
+
+		position.is_some_and(|idx| {
+			self.selection = idx;
+			true
+		})
+	}
+
+	/// cache root holding the repo under the cursor
+	pub fn selected_root(&self) -> Option<&CacheRoot> {
+		self.cache.root_of(self.selected_repo()?)
+	}
+
+	fn move_selection(&mut self, scroll: ScrollType) -> bool {
+		let count = self.shown.len();
+		if count == 0 {
+			return false;
+		}
+
+		let page = self.current_height.get().saturating_sub(1).max(1);
+		let old = self.selection;
+		self.selection = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => count - 1,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(count - 1);
+
+		old != self.selection
+	}
+
+	/// widest root tag, zero with a single root where tags are left out
+	fn root_tag_width(&self) -> usize {
+		if self.cache.roots.len() < 2 {
+			return 0;
+		}
+
+		self.cache
+			.roots
+			.iter()
+			.map(|root| strings::root_tag(root).len() + 1)
+			.max()
+			.unwrap_or_default()
+	}
+
+	/// columns after the repo column fitting into `width`,
+	/// starting at the horizontal scroll position
+	fn visible_columns(&self, width: usize) -> &'static [Column] {
+		let columns = &Column::ALL[1..];
+		let available = width.saturating_sub(MIN_REPO_WIDTH);
+		let fit = |start: usize| {
+			columns[start..]
+				.iter()
+				.scan(0, |sum, c| {
+					*sum += c.width();
+					Some(*sum)
+				})
+				.take_while(|sum| *sum <= available)
+				.count()
+		};
+
+		let start = self.hscroll.update_no_selection(
+			columns.len(),
+			fit(self.hscroll.get_right()),
+		);
+
+		&columns[start..start + fit(start)]
+	}
+
+	/// text of `column` for `repo`, access times relative to `now`
+	fn cell(repo: &CachedRepo, column: Column, now: i64) -> String {
+		let time = |secs: Option<i64>, relative: bool| {
+			secs.map_or_else(strings::time_unknown, |secs| {
+				if relative {
+					time_to_relative_string(secs, now)
+				} else {
+					time_to_string(secs, true)
+				}
+			})
+		};
+
+		match column {
+			Column::Repo => repo.repo_id.clone(),
+			Column::Type => {
+				strings::repo_type_name(repo.repo_type).to_string()
+			}
+			Column::Size => bytes_to_string(repo.sizes.unique),
+			Column::Revisions => repo.revisions.len().to_string(),
+			Column::Files => repo.file_count().to_string(),
+			Column::Refs => repo
+				.refs
+				.iter()
+				.map(|r| r.name.as_str())
+				.collect::<Vec<_>>()
+				.join(","),
+			Column::Modified => time(newest_revision(repo), false),
+			Column::Accessed => time(repo.last_accessed, true),
+		}
+	}
+
+	fn align(text: &str, column: Column) -> String {
+		let width = column.width() - 1;
+		if column.right_aligned() {
+			format!(" {text:>width$}")
+		} else {
+			format!(" {}", string_width_align(text, width))
+		}
+	}
+
+	fn header_line(
+		&self,
+		columns: &[Column],
+		repo_width: usize,
+	) -> Line<'_> {
+		let header = strings::repo_table_header();
+		let title = |column: Column| {
+			let name = &header[column as usize];
+			if column == self.sort {
+				format!("{name}{}", strings::sort_symbol(self.reverse))
+			} else {
+				name.clone()
+			}
+		};
+		let style = self.theme.title(false);
+
+		let mut spans = vec![Span::styled(
+			string_width_align(&title(Column::Repo), repo_width),
+			style,
+		)];
+		spans.extend(columns.iter().map(|c| {
+			Span::styled(Self::align(&title(*c), *c), style)
+		}));
+
+		Line::from(spans)
+	}
+
+	fn repo_to_line(
+		&self,
+		idx: usize,
+		columns: &[Column],
+		repo_width: usize,
+		tag_width: usize,
+		now: i64,
+		selected: bool,
+	) -> Line<'_> {
+		let theme = &self.theme;
+		let repo = &self.cache.repos[idx];
+		let badge = strings::repo_health_badge(repo.problems.len());
+		let duplicate = if tag_width == 0 {
+			String::new()
+		} else {
+			format!(
+				"{:>3} ",
+				strings::duplicate_badge(
+					self.cache.duplicates(idx).count() + 1
+				)
+			)
+		};
+		let tag = self
+			.cache
+			.root_of(repo)
+			.filter(|_| tag_width > 0)
+			.map(|root| format!("{:tag_width$}", strings::root_tag(root)))
+			.unwrap_or_default();
+		let id_width = repo_width.saturating_sub(
+			tag.len() + duplicate.len() + badge.len() + 3,
+		);
+
+		let stale = repo
+			.last_accessed
+			.is_some_and(|secs| now - secs > self.stale_after);
+		let text_style = if stale {
+			theme.stale(selected)
+		} else {
+			theme.text(true, selected)
+		};
+		let badge_style = if repo.problems.is_empty() {
+			text_style
+		} else {
+			theme.text_danger()
+		};
+
+		let mut spans = vec![
+			Span::styled(
+				format!("{} ", strings::repo_type_icon(repo.repo_type)),
+				theme.repo_type(repo.repo_type, selected),
+			),
+			Span::styled(
+				format!(
+					"{} ",
+					string_width_align(&repo.repo_id, id_width)
+				),
+				text_style,
+			),
+			Span::styled(tag, theme.tags(selected)),
+			Span::styled(duplicate, theme.branch(selected, true)),
+			Span::styled(badge, badge_style),
+		];
+		spans.extend(columns.iter().map(|c| {
+			Span::styled(
+				Self::align(&Self::cell(repo, *c, now), *c),
+				text_style,
+			)
+		}));
+
+		Line::from(spans)
+	}
+}
+
+impl DrawableComponent for RepoListComponent {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		let title = strings::title_repos(
+			self.shown.len(),
+			self.filter,
+			self.scan_progress.map(|p| p.progress),
+		);
+		let block = Block::default()
+			.title(Span::styled(title, self.theme.title(self.focused)))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(self.focused));
+
+		let inner = block.inner(area);
+		f.render_widget(block, area);
+
+		let chunks = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints([Constraint::Length(1), Constraint::Min(0)])
+			.split(inner);
+
+		let width = usize::from(inner.width);
+		let height = usize::from(chunks[1].height);
+		self.current_height.set(height);
+
+		let columns = self.visible_columns(width);
+		let repo_width = width
+			.saturating_sub(columns.iter().map(|c| c.width()).sum());
+
+		f.render_widget(
+			Paragraph::new(self.header_line(columns, repo_width)),
+			chunks[0],
+		);
+
+		if self.shown.is_empty() {
+			ui::draw_list_block(
+				f,
+				chunks[1],
+				Block::default(),
+				std::iter::once(Span::styled(
+					Cow::from(strings::repo_list_empty()),
+					self.theme.text(false, false),
+				)),
+			);
+		} else {
+			self.scroll.update(self.selection, self.shown.len(), height);
+
+			let tag_width = self.root_tag_width();
+			let now = now_secs();
+			let items = self
+				.shown
+				.iter()
+				.enumerate()
+				.skip(self.scroll.get_top())
+				.take(height)
+				.map(|(row, idx)| {
+					self.repo_to_line(
+						*idx,
+						columns,
+						repo_width,
+						tag_width,
+						now,
+						self.focused && row == self.selection,
+					)
+				});
+
+			ui::draw_list_block(f, chunks[1], Block::default(), items);
+		}
+
+		if self.focused {
+			self.scroll.draw(f, area, &self.theme);
+			self.hscroll.draw(f, area, &self.theme);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for RepoListComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		out.push(
+			CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				!self.shown.is_empty(),
+				self.focused,
+			)
+			.order(order::NAV),
+		);
+		out.push(CommandInfo::new(
+			strings::commands::repo_type_filter(&self.key_config),
+			!self.cache.repos.is_empty(),
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::sort_column(&self.key_config),
+			!self.shown.is_empty(),
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::sort_reverse(&self.key_config),
+			!self.shown.is_empty(),
+			self.focused,
+		));
+		out.push(
+			CommandInfo::new(
+				strings::commands::scroll_columns(&self.key_config),
+				!self.shown.is_empty(),
+				self.focused,
+			)
+			.order(order::NAV),
+		);
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.focused {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			let scroll = if key_match(key, keys.move_up) {
+				Some(ScrollType::Up)
+			} else if key_match(key, keys.move_down) {
+				Some(ScrollType::Down)
+			} else if key_match(key, keys.home)
+				|| key_match(key, keys.shift_up)
+			{
+				Some(ScrollType::Home)
+			} else if key_match(key, keys.end)
+				|| key_match(key, keys.shift_down)
+			{
+				Some(ScrollType::End)
+			} else if key_match(key, keys.page_up) {
+				Some(ScrollType::PageUp)
+			} else if key_match(key, keys.page_down) {
+				Some(ScrollType::PageDown)
+			} else {
+				None
+			};
+
+			if let Some(scroll) = scroll {
+				self.move_selection(scroll);
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.repo_type_filter) {
+				self.cycle_filter();
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.sort_column) {
+				self.cycle_sort();
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.sort_reverse) {
+				self.reverse_sort();
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.move_left) {
+				self.hscroll.move_right(HorizontalScrollType::Left);
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.move_right) {
+				self.hscroll.move_right(HorizontalScrollType::Right);
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+	}
+}
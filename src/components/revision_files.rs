@@ -4,18 +4,20 @@
 // 	FuzzyFinderTarget, SyntaxTextComponent,
 // };
 use super::{
-	utils::scroll_vertical::VerticalScroll, CommandBlocking,
-	Component, DrawableComponent
+	utils::{bytes_to_string, scroll_vertical::VerticalScroll},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, SyntaxTextComponent, ViewJump,
 };
 use crate::{
 	app::Environment,
+	hub::{CachedRepo, CachedRevision, FileKind, RevisionRef},
 	keys::{key_match, SharedKeyConfig},
 	// popups::{BlameFileOpen, FileRevOpen},
 	// queue::{InternalEvent, Queue, StackablePopupOpen},
 	strings::{self, order, symbol},
 	// try_or_popup,
 	ui::{self, common_nav, style::SharedTheme},
-	// AsyncNotification,
+	AsyncAppNotification,
 };
 use anyhow::Result;
 // use asyncgit::{
@@ -29,66 +31,249 @@ use crossterm::event::Event;
 use filetreelist::{FileTree, FileTreeItem};
 use ratatui::{
 	layout::{Constraint, Direction, Layout, Rect},
-	text::Span,
+	text::{Line, Span},
 	widgets::{Block, Borders},
 	Frame,
 };
-use std::{borrow::Cow, fmt::Write};
-use std::{collections::BTreeSet, path::Path};
-use unicode_truncate::UnicodeTruncateStr;
-use unicode_width::UnicodeWidthStr;
+use std::borrow::Cow;
+use std::{
+	collections::BTreeSet,
+	path::{Path, PathBuf},
+};
+
+/// width of the size column, the kind column and their separators
+const INFO_WIDTH: usize = 14;
 
 enum Focus {
 	Tree,
 	File,
 }
 
-/// `tree_files` returns a list of `FileTree`
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct TreeFile {
-	/// path of this file
-	/// unix filemode
-	pub filemode: i32,
-	// internal object id
+/// tree folder holding the files of a revision as reached through a ref
+struct RevisionFolder {
+	/// `<ref>/<commit>` below the tree root
+	path: PathBuf,
+	/// index into `RevisionFilesComponent::revisions`
+	revision: usize,
+	reference: RevisionRef,
 }
+
 pub struct RevisionFilesComponent {
 	// repo: RepoPathRef,
 	// queue: Queue,
 	theme: SharedTheme,
-	//TODO: store TreeFiles in `tree`
-	files: Option<Vec<TreeFile>>,
+	repo_id: String,
+	revisions: Option<Vec<CachedRevision>>,
+	folders: Vec<RevisionFolder>,
 	// async_treefiles: AsyncSingleJob<AsyncTreeFilesJob>,
-	// current_file: SyntaxTextComponent,
+	current_file: SyntaxTextComponent,
 	tree: FileTree,
 	scroll: VerticalScroll,
 	visible: bool,
 	// revision: Option<CommitInfo>,
 	focus: Focus,
+	focused: bool,
 	key_config: SharedKeyConfig,
 }
 
 impl RevisionFilesComponent {
-	///
+	/// creates an empty component, filled by `set_repo`
 	pub fn new(env: &Environment) -> Self {
 		Self {
 			// queue: env.queue.clone(),
 			tree: FileTree::default(),
 			scroll: VerticalScroll::new(),
-			// current_file: SyntaxTextComponent::new(env),
+			current_file: SyntaxTextComponent::new(env),
 			theme: env.theme.clone(),
-			files: None,
+			repo_id: String::new(),
+			revisions: None,
+			folders: Vec::new(),
 			// async_treefiles: AsyncSingleJob::new(
 			// 	env.sender_git.clone(),
 			// ),
 			// revision: None,
 			focus: Focus::Tree,
+			focused: false,
 			key_config: env.key_config.clone(),
 			// repo: env.repo.clone(),
 			visible: false,
 		}
 	}
 
-	///
+	/// shows the refs, commits and files of all revisions of `repo`,
+	/// opening its default revision
+	pub fn set_repo(&mut self, repo: &CachedRepo) -> Result<()> {
+		self.folders = repo
+			.revision_refs()
+			.into_iter()
+			.filter_map(|reference| {
+				let revision = repo.revisions.iter().position(|rev| {
+					rev.commit_hash == reference.commit_hash
+				})?;
+				let name = reference
+					.name
+					.clone()
+					.unwrap_or_else(strings::refs_detached);
+
+				Some(RevisionFolder {
+					path: Path::new(&name).join(&reference.commit_hash),
+					revision,
+					reference,
+				})
+			})
+			.collect();
+
+		let paths: Vec<PathBuf> = self
+			.folders
+			.iter()
+			.flat_map(|folder| {
+				repo.revisions[folder.revision].files.iter().map(|f| {
+					Path::new("./").join(&folder.path).join(&f.path)
+				})
+			})
+			.collect();
+		let paths: Vec<&Path> =
+			paths.iter().map(PathBuf::as_path).collect();
+
+		self.tree = FileTree::new(&paths, &BTreeSet::new())?;
+		self.tree.collapse_but_root();
+		self.scroll.reset();
+		self.repo_id.clone_from(&repo.repo_id);
+		self.revisions = Some(repo.revisions.clone());
+		self.focus_tree();
+
+		let default = repo.default_revision().and_then(|default| {
+			self.folders.iter().position(|folder| {
+				folder.reference.commit_hash == default.commit_hash
+			})
+		});
+		match default {
+			Some(idx) => self.select_folder(idx),
+			None => self.current_file.clear(),
+		}
+
+		Ok(())
+	}
+
+	/// rebuilds the tree after `repo` changed on disk, keeping the
+	/// selected item and the focus if they still exist
+	pub fn reload_repo(&mut self, repo: &CachedRepo) -> Result<()> {
+		let selected = self.selected_location().map(|(folder, path)| {
+			(folder.reference.clone(), path.to_path_buf())
+		});
+		let file_focused = matches!(self.focus, Focus::File);
+
+		self.set_repo(repo)?;
+
+		if let Some((reference, path)) = selected {
+			self.select_file(&reference, &path);
+		}
+		if file_focused && self.current_file.path().is_some() {
+			self.focus_file();
+		}
+
+		Ok(())
+	}
+
+	/// shows an empty tree
+	pub fn clear(&mut self) {
+		self.tree = FileTree::default();
+		self.scroll.reset();
+		self.repo_id.clear();
+		self.revisions = Some(Vec::new());
+		self.folders.clear();
+		self.focus_tree();
+		self.current_file.clear();
+	}
+
+	/// opens `revision` in the tree and selects its first file
+	pub fn select_revision(&mut self, revision: &RevisionRef) {
+		if let Some(idx) =
+			self.folders.iter().position(|f| f.reference == *revision)
+		{
+			self.focus_tree();
+			self.select_folder(idx);
+		}
+	}
+
+	/// opens `revision` in the tree and selects its file at `path`
+	pub fn select_file(&mut self, revision: &RevisionRef, path: &Path) {
+		let Some(folder) =
+			self.folders.iter().find(|f| f.reference == *revision)
+		else {
+			return;
+		};
+		let path = Path::new("./").join(&folder.path).join(path);
+
+		self.focus_tree();
+		self.tree.collapse_but_root();
+		self.tree.select_file(&path);
+		self.selection_changed();
+	}
+
+	/// ref and commit of the revision the selection is in
+	pub fn selected_ref(&self) -> Option<&RevisionRef> {
+		self.selected_location().map(|(folder, _)| &folder.reference)
+	}
+
+	/// revision the selection is in
+	pub fn selected_revision(&self) -> Option<&CachedRevision> {
+		let (folder, _) = self.selected_location()?;
+		self.revisions.as_ref()?.get(folder.revision)
+	}
+
+	fn select_folder(&mut self, idx: usize) {
+		let first = self.revisions.as_ref().and_then(|revisions| {
+			let folder = &self.folders[idx];
+			revisions[folder.revision].files.first().map(|file| {
+				Path::new("./").join(&folder.path).join(&file.path)
+			})
+		});
+
+		if let Some(first) = first {
+			self.tree.select_file(&first);
+		}
+		self.selection_changed();
+	}
+
+	/// folder of the revision holding the tree item at `path`
+	/// and the path of the item within the snapshot
+	fn locate<'a>(
+		&self,
+		path: &'a str,
+	) -> Option<(&RevisionFolder, &'a Path)> {
+		let path = Path::new(path.strip_prefix("./")?);
+
+		self.folders.iter().find_map(|folder| {
+			let rest = path.strip_prefix(&folder.path).ok()?;
+			Some((folder, rest))
+		})
+	}
+
+	fn selected_location(&self) -> Option<(&RevisionFolder, &Path)> {
+		let selection = self.tree.visual_selection()?;
+		let (item, _) =
+			self.tree.iterate(selection.index, 1).next()?;
+
+		self.locate(item.info().full_path_str())
+	}
+
+	/// picks up progress and results of the preview highlighting
+	pub fn update(&mut self, ev: AsyncAppNotification) {
+		self.current_file.update(ev);
+	}
+
+	/// true while the previewed file is highlighted or searched
+	pub fn any_work_pending(&self) -> bool {
+		self.current_file.any_work_pending()
+	}
+
+	/// forwards a jump or search to the hex or stream preview
+	pub fn view_jump(&mut self, jump: &ViewJump) {
+		self.current_file.view_jump(jump);
+	}
+
+	// ///
 	// pub fn set_commit(&mut self, commit: CommitId) -> Result<()> {
 	// 	self.show()?;
 
@@ -156,21 +341,65 @@ impl RevisionFilesComponent {
 	// 	Ok(())
 	// }
 
-	///
+	// ///
 	// pub fn any_work_pending(&self) -> bool {
 	// 	self.current_file.any_work_pending()
 	// 		|| self.async_treefiles.is_pending()
 	// }
 
-	fn tree_item_to_span<'a>(
+	/// kind and size of a file, summed size of a folder,
+	/// nothing for folders above the commits
+	fn item_info(
+		&self,
+		item: &FileTreeItem,
+		revision: Option<(&CachedRevision, &Path)>,
+	) -> (Option<FileKind>, Option<u64>) {
+		let Some((revision, path)) = revision else {
+			return (None, None);
+		};
+		let files = &revision.files;
+
+		if item.kind().is_path() {
+			let size = files
+				.iter()
+				.filter(|f| f.path.starts_with(path))
+				.map(|f| f.size)
+				.sum();
+			return (None, Some(size));
+		}
+
+		files.binary_search_by(|f| f.path.as_path().cmp(path)).map_or(
+			(None, None),
+			|idx| (Some(files[idx].kind()), Some(files[idx].size)),
+		)
+	}
+
+	fn tree_item_to_line<'a>(
+		&self,
 		item: &'a FileTreeItem,
-		theme: &SharedTheme,
 		width: usize,
 		selected: bool,
-	) -> Span<'a> {
-		let path = item.info().path_str();
+	) -> Line<'a> {
+		let theme = &self.theme;
 		let indent = item.info().indent();
 
+		let revision = self
+			.locate(item.info().full_path_str())
+			.and_then(|(folder, rest)| {
+				let revisions = self.revisions.as_ref()?;
+				Some((&revisions[folder.revision], rest))
+			});
+
+		// folded folders may include the commit, shown abbreviated
+		let mut path = item.info().path_str().to_string();
+		let commit = revision
+			.map(|(rev, _)| rev)
+			.filter(|rev| path.contains(&rev.commit_hash));
+		if let Some(rev) = commit {
+			let hash = strings::short_hash(&rev.commit_hash);
+			path = path.replace(&rev.commit_hash, &hash);
+		}
+
 		let indent_str = if indent == 0 {
 			String::new()
 		} else {
@@ -188,14 +417,40 @@ impl RevisionFilesComponent {
 			symbol::EMPTY_STR
 		};
 
-		let available_width =
-			width.saturating_sub(indent_str.len() + path_arrow.len());
+		let available_width = width.saturating_sub(
+			indent_str.len() + path_arrow.len() + INFO_WIDTH,
+		);
+		let path = strings::ellipsis_trim_start(&path, available_width);
 
 		let path = format!(
 			"{indent_str}{path_arrow}{path:available_width$}"
 		);
+		let path_style = match commit {
+			Some(rev) if rev.refs.is_empty() => {
+				theme.text(false, selected)
+			}
+			Some(_) => theme.commit_hash(selected),
+			None => theme.file_tree_item(is_path, selected),
+		};
 
-		Span::styled(path, theme.file_tree_item(is_path, selected))
+		let (kind, size) = self.item_info(item, revision);
+		let kind_style = if kind == Some(FileKind::Symlink) {
+			theme.text_danger()
+		} else {
+			theme.tags(selected)
+		};
+		let kind = kind.map_or(symbol::EMPTY_STR, strings::file_kind);
+
+		let size = size.map(bytes_to_string).unwrap_or_default();
+
+		Line::from(vec![
+			Span::styled(path, path_style),
+			Span::styled(format!(" {kind:>4} "), kind_style),
+			Span::styled(
+				format!("{size:>8}"),
+				theme.text(true, selected),
+			),
+		])
 	}
 
 	// fn blame(&self) -> bool {
@@ -248,44 +503,38 @@ impl RevisionFilesComponent {
 	// 	}
 	// }
 
-	fn selected_file_path_with_prefix(&self) -> Option<String> {
-		self.tree
-			.selected_file()
-			.map(|file| file.full_path_str().to_string())
+	/// path of the selected file relative to its snapshot
+	pub fn selected_file(&self) -> Option<PathBuf> {
+		let file = self.tree.selected_file()?;
+		self.locate(file.full_path_str())
+			.map(|(_, path)| path.to_path_buf())
 	}
 
-	fn selected_file_path(&self) -> Option<String> {
-		self.tree.selected_file().map(|file| {
-			file.full_path_str()
-				.strip_prefix("./")
-				.unwrap_or_default()
-				.to_string()
-		})
+	fn selection_changed(&mut self) {
+		let path = self
+			.selected_revision()
+			.zip(self.selected_file())
+			.map(|(revision, file)| revision.path.join(file));
+
+		match path {
+			Some(path) => self.current_file.load_file(&path),
+			None => self.current_file.clear(),
+		}
 	}
 
-	// fn selection_changed(&mut self) {
-	// 	//TODO: retrieve TreeFile from tree datastructure
-	// 	if let Some(file) = self.selected_file_path_with_prefix() {
-	// 		if let Some(files) = &self.files {
-	// 			let path = Path::new(&file);
-	// 			if let Some(item) =
-	// 				files.iter().find(|f| f.path == path)
-	// 			{
-	// 				if let Ok(path) = path.strip_prefix("./") {
-	// 					return self.current_file.load_file(
-	// 						path.to_string_lossy().to_string(),
-	// 						item,
-	// 					);
-	// 				}
-	// 			}
-	// 			self.current_file.clear();
-	// 		}
-	// 	}
-	// }
+	fn focus_tree(&mut self) {
+		self.focus = Focus::Tree;
+		self.current_file.focus(false);
+	}
+
+	fn focus_file(&mut self) {
+		self.focus = Focus::File;
+		self.current_file.focus(self.focused);
+	}
 
 	fn draw_tree(&self, f: &mut Frame, area: Rect) -> Result<()> {
 		let tree_height = usize::from(area.height.saturating_sub(2));
-		let tree_width = usize::from(area.width);
+		let tree_width = usize::from(area.width.saturating_sub(2));
 
 		self.tree.visual_selection().map_or_else(
 			|| {
@@ -304,18 +553,21 @@ impl RevisionFilesComponent {
 			.tree
 			.iterate(self.scroll.get_top(), tree_height)
 			.map(|(item, selected)| {
-				Self::tree_item_to_span(
-					item,
-					&self.theme,
-					tree_width,
-					selected,
-				)
+				self.tree_item_to_line(item, tree_width, selected)
 			});
 
-		let is_tree_focused = matches!(self.focus, Focus::Tree);
-
-		// let title = self.title_within(tree_width)?;
-        let title = String::from("demo");
+		let is_tree_focused =
+			self.focused && matches!(self.focus, Focus::Tree);
+
+		let location = self.selected_revision().map(|revision| {
+			let hash = strings::short_hash(&revision.commit_hash);
+			format!("{}@{hash}", self.repo_id)
+		});
+		let title = strings::title_revision_files(
+			location.as_deref().or(
+				Some(self.repo_id.as_str()).filter(|id| !id.is_empty()),
+			),
+		);
 		let block = Block::default()
 			.title(Span::styled(
 				title,
@@ -324,14 +576,14 @@ impl RevisionFilesComponent {
 			.borders(Borders::ALL)
 			.border_style(self.theme.block(is_tree_focused));
 
-		if self.files.is_some() {
+		if self.revisions.is_some() {
 			ui::draw_list_block(f, area, block, items);
 		} else {
 			ui::draw_list_block(
 				f,
 				area,
 				block,
-				vec![Span::styled(
+				vec![Line::styled(
 					Cow::from(strings::loading_text(
 						&self.key_config,
 					)),
@@ -391,43 +643,99 @@ impl RevisionFilesComponent {
 	// }
 }
 
-use ratatui::{
-    backend::CrosstermBackend,
-    widgets::{ Paragraph},
-    style::{Style, Color},
-    Terminal,
-};
 impl DrawableComponent for RevisionFilesComponent {
 	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
-		// if self.is_visible() {
-		// 	let chunks = Layout::default()
-		// 		.direction(Direction::Horizontal)
-		// 		.constraints(
-		// 			[
-		// 				Constraint::Percentage(40),
-		// 				Constraint::Percentage(60),
-		// 			]
-		// 			.as_ref(),
-		// 		)
-		// 		.split(area);
-
-		// 	self.draw_tree(f, chunks[0])?;
-
-		// 	// self.current_file.draw(f, chunks[1])?;
-		// }
-        let block = Block::default()
-                .title("My App")
-                .borders(Borders::ALL);
-        let paragraph = Paragraph::new("Hello, world!")
-                .block(block)
-                .style(Style::default().fg(Color::Yellow));
-
-            f.render_widget(paragraph, area);
+		if self.is_visible() {
+			let chunks = Layout::default()
+				.direction(Direction::Horizontal)
+				.constraints(
+					[
+						Constraint::Percentage(40),
+						Constraint::Percentage(60),
+					]
+					.as_ref(),
+				)
+				.split(area);
+
+			self.draw_tree(f, chunks[0])?;
+			self.current_file.draw(f, chunks[1])?;
+		}
+
 		Ok(())
 	}
 }
 
 impl Component for RevisionFilesComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		let active = self.is_visible() && self.focused;
+		if !active && !force_all {
+			return CommandBlocking::PassingOn;
+		}
+
+		let is_tree_focused = matches!(self.focus, Focus::Tree);
+
+		if is_tree_focused || force_all {
+			tree_nav_cmds(&self.tree, &self.key_config, out);
+			out.push(CommandInfo::new(
+				strings::commands::select_preview(&self.key_config),
+				self.tree.selected_file().is_some(),
+				true,
+			));
+		}
+		if !is_tree_focused || force_all {
+			self.current_file.commands(out, force_all);
+			out.push(CommandInfo::new(
+				strings::commands::select_tree(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if !self.is_visible() || !self.focused {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = event {
+			let is_tree_focused = matches!(self.focus, Focus::Tree);
+			if is_tree_focused {
+				if tree_nav(&mut self.tree, &self.key_config, key) {
+					self.selection_changed();
+					return Ok(EventState::Consumed);
+				} else if key_match(key, self.key_config.keys.move_right)
+					&& self.tree.selected_file().is_some()
+				{
+					self.focus_file();
+					return Ok(EventState::Consumed);
+				}
+			} else if key_match(key, self.key_config.keys.move_left) {
+				self.focus_tree();
+				return Ok(EventState::Consumed);
+			} else {
+				return self.current_file.event(event);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+		self.current_file
+			.focus(focus && matches!(self.focus, Focus::File));
+	}
+
 	// fn commands(
 	// 	&self,
 	// 	out: &mut Vec<CommandInfo>,
@@ -572,20 +880,20 @@ impl Component for RevisionFilesComponent {
 }
 
 //TODO: reuse for other tree usages
-// fn tree_nav_cmds(
-// 	tree: &FileTree,
-// 	key_config: &SharedKeyConfig,
-// 	out: &mut Vec<CommandInfo>,
-// ) {
-// 	out.push(
-// 		CommandInfo::new(
-// 			strings::commands::navigate_tree(key_config),
-// 			!tree.is_empty(),
-// 			true,
-// 		)
-// 		.order(order::NAV),
-// 	);
-// }
+fn tree_nav_cmds(
+	tree: &FileTree,
+	key_config: &SharedKeyConfig,
+	out: &mut Vec<CommandInfo>,
+) {
+	out.push(
+		CommandInfo::new(
+			strings::commands::navigate_tree(key_config),
+			!tree.is_empty(),
+			true,
+		)
+		.order(order::NAV),
+	);
+}
 
 //TODO: reuse for other tree usages
 fn tree_nav(
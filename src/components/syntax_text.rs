@@ -0,0 +1,366 @@
+use super::{
+	text_stream::is_jsonl,
+	utils::{is_binary, read_head, BINARY_PROBE_SIZE},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, HexViewComponent, TextStreamComponent,
+};
+use crate::{
+	app::Environment,
+	asyncjob::AsyncSingleJob,
+	keys::SharedKeyConfig,
+	progress::ProgressPercent,
+	string_utils::tabs_to_spaces,
+	strings::{self, order},
+	ui::{
+		self, common_nav, style::SharedTheme, AsyncSyntaxJob,
+		ParagraphState, ScrollPos, StatefulParagraph,
+	},
+	AsyncAppNotification, SyntaxHighlightProgress,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use filetreelist::MoveSelection;
+use ratatui::{
+	layout::Rect,
+	text::{Span, Text},
+	widgets::{Block, Borders, Wrap},
+	Frame,
+};
+use std::{
+	cell::Cell,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// larger text files are streamed instead of read at once,
+/// datasets must not end up in memory
+const MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// where to move the focused hex or stream view,
+/// see `InternalEvent::ViewJump`
+pub enum ViewJump {
+	/// byte offset in the hex view
+	Offset(u64),
+	/// byte pattern searched in the hex view
+	Bytes(Vec<u8>),
+	/// text searched in the stream view, sent again on every edit
+	Text(String),
+}
+
+enum Content {
+	/// shown until highlighting finished, or if reading failed
+	Plain(String),
+	Highlighted(ui::SyntaxText),
+	/// binary file paged through `SyntaxTextComponent::hex`
+	Hex,
+	/// large or JSONL file paged through `SyntaxTextComponent::stream`
+	Stream,
+}
+
+/// scrollable view of a file on disk with syntax highlighting,
+/// binary files are shown as hex and large text files streamed
+pub struct SyntaxTextComponent {
+	current_file: Option<(PathBuf, Content)>,
+	hex: HexViewComponent,
+	stream: TextStreamComponent,
+	async_highlighting: AsyncSingleJob<AsyncSyntaxJob>,
+	syntax_progress: Option<ProgressPercent>,
+	paragraph_state: Cell<ParagraphState>,
+	focused: bool,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl SyntaxTextComponent {
+	/// creates an empty view
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			current_file: None,
+			hex: HexViewComponent::new(env),
+			stream: TextStreamComponent::new(env),
+			async_highlighting: AsyncSingleJob::new(
+				env.sender_app.clone(),
+			),
+			syntax_progress: None,
+			paragraph_state: Cell::new(ParagraphState::default()),
+			focused: false,
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// picks up progress and results of the highlighting job
+	/// and of the hex and stream view jobs
+	pub fn update(&mut self, ev: AsyncAppNotification) {
+		let AsyncAppNotification::SyntaxHighlighting(progress) = ev else {
+			self.hex.update(ev);
+			self.stream.update(ev);
+			return;
+		};
+
+		match progress {
+			SyntaxHighlightProgress::Progress => {
+				self.syntax_progress =
+					self.async_highlighting.progress();
+			}
+			SyntaxHighlightProgress::Done => {
+				// the notification may be meant for another view
+				if !self.async_highlighting.is_pending() {
+					self.syntax_progress = None;
+				}
+				let syntax = self
+					.async_highlighting
+					.take_last()
+					.and_then(|job| job.result());
+
+				if let (Some((path, content)), Some(syntax)) =
+					(self.current_file.as_mut(), syntax)
+				{
+					if syntax.path() == path {
+						*content = Content::Highlighted(syntax);
+					}
+				}
+			}
+		}
+	}
+
+	/// true while highlighting or a hex or stream view job runs
+	pub fn any_work_pending(&self) -> bool {
+		self.async_highlighting.is_pending()
+			|| self.hex.any_work_pending()
+			|| self.stream.any_work_pending()
+	}
+
+	/// forwards a jump or search to the hex or stream view
+	pub fn view_jump(&mut self, jump: &ViewJump) {
+		match self.current_file {
+			Some((_, Content::Hex)) => self.hex.jump(jump),
+			Some((_, Content::Stream)) => self.stream.jump(jump),
+			_ => {}
+		}
+	}
+
+	/// path of the shown file
+	pub fn path(&self) -> Option<&Path> {
+		self.current_file.as_ref().map(|(path, _)| path.as_path())
+	}
+
+	/// shows nothing
+	pub fn clear(&mut self) {
+		self.current_file = None;
+		self.hex.clear();
+		self.stream.clear();
+		self.syntax_progress = None;
+		self.paragraph_state.set(ParagraphState::default());
+	}
+
+	/// shows the file at `path` and highlights it in the background
+	pub fn load_file(&mut self, path: &Path) {
+		if self.path() == Some(path) {
+			return;
+		}
+
+		self.paragraph_state.set(ParagraphState::default());
+		self.syntax_progress = None;
+		self.hex.clear();
+		self.stream.clear();
+
+		// weights are paged through and large text streamed,
+		// never read as a whole
+		let streamed = is_jsonl(path)
+			|| fs::metadata(path).is_ok_and(|m| m.len() > MAX_FILE_SIZE);
+		let loaded = match read_head(path, BINARY_PROBE_SIZE) {
+			Ok((head, _)) if is_binary(&head) => {
+				self.hex.load_file(path).map(|()| Content::Hex)
+			}
+			Ok(_) if streamed => {
+				self.stream.load_file(path).map(|()| Content::Stream)
+			}
+			Ok(_) => self.highlight(path),
+			Err(e) => Err(e.into()),
+		};
+
+		let content = loaded.unwrap_or_else(|e| {
+			Content::Plain(strings::code_load_error(&e.to_string()))
+		});
+		self.current_file = Some((path.to_path_buf(), content));
+	}
+
+	/// reads the file at `path` and starts highlighting it
+	fn highlight(&mut self, path: &Path) -> Result<Content> {
+		let (content, _) = read_head(path, MAX_FILE_SIZE)?;
+		let content =
+			tabs_to_spaces(String::from_utf8_lossy(&content).into_owned());
+
+		self.syntax_progress = Some(ProgressPercent::default());
+		self.async_highlighting.spawn(AsyncSyntaxJob::new(
+			content.clone(),
+			path.to_string_lossy().into_owned(),
+			self.theme.get_syntax(),
+		));
+
+		Ok(Content::Plain(content))
+	}
+
+	fn scroll(&self, nav: MoveSelection) -> bool {
+		let state = self.paragraph_state.get();
+		let page = state.height().saturating_sub(2);
+
+		let new_scroll_pos = match nav {
+			MoveSelection::Down => state.scroll().y.saturating_add(1),
+			MoveSelection::Up => state.scroll().y.saturating_sub(1),
+			MoveSelection::Top => 0,
+			MoveSelection::End => state.lines().saturating_sub(page),
+			MoveSelection::PageUp => {
+				state.scroll().y.saturating_sub(page)
+			}
+			MoveSelection::PageDown => {
+				state.scroll().y.saturating_add(page)
+			}
+			MoveSelection::Left | MoveSelection::Right => {
+				state.scroll().y
+			}
+		};
+
+		self.set_scroll(new_scroll_pos)
+	}
+
+	fn set_scroll(&self, pos: u16) -> bool {
+		let mut state = self.paragraph_state.get();
+
+		let new_scroll_pos = pos.min(
+			state
+				.lines()
+				.saturating_sub(state.height().saturating_sub(2)),
+		);
+
+		if new_scroll_pos == state.scroll().y {
+			return false;
+		}
+
+		state.set_scroll(ScrollPos {
+			x: 0,
+			y: new_scroll_pos,
+		});
+		self.paragraph_state.set(state);
+
+		true
+	}
+
+	fn title(&self) -> String {
+		let name = self
+			.path()
+			.and_then(Path::file_name)
+			.map_or_else(strings::title_code, |n| {
+				n.to_string_lossy().into_owned()
+			});
+
+		match self.syntax_progress {
+			Some(p) => format!("{name} ({}%)", p.progress),
+			None => name,
+		}
+	}
+}
+
+impl DrawableComponent for SyntaxTextComponent {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		let text = match &self.current_file {
+			Some((_, Content::Hex)) => return self.hex.draw(f, area),
+			Some((_, Content::Stream)) => return self.stream.draw(f, area),
+			Some((_, Content::Highlighted(syntax))) => syntax.into(),
+			Some((_, Content::Plain(text))) => Text::from(text.as_str()),
+			None => Text::from(""),
+		};
+
+		let content = StatefulParagraph::new(text)
+			.wrap(Wrap { trim: false })
+			.block(
+				Block::default()
+					.title(Span::styled(
+						self.title(),
+						self.theme.title(self.focused),
+					))
+					.borders(Borders::ALL)
+					.border_style(self.theme.block(self.focused)),
+			);
+
+		let mut state = self.paragraph_state.get();
+		f.render_stateful_widget(content, area, &mut state);
+		self.paragraph_state.set(state);
+		self.set_scroll(state.scroll().y);
+
+		if self.focused {
+			ui::draw_scrollbar(
+				f,
+				area,
+				&self.theme,
+				usize::from(
+					state.lines().saturating_sub(state.height()),
+				),
+				usize::from(state.scroll().y),
+				ui::Orientation::Vertical,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for SyntaxTextComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		match self.current_file {
+			Some((_, Content::Hex)) => {
+				return self.hex.commands(out, force_all)
+			}
+			Some((_, Content::Stream)) => {
+				return self.stream.commands(out, force_all)
+			}
+			_ => {}
+		}
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				self.current_file.is_some(),
+				self.focused,
+			)
+			.order(order::NAV),
+		);
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.focused {
+			return Ok(EventState::NotConsumed);
+		}
+		match self.current_file {
+			Some((_, Content::Hex)) => return self.hex.event(ev),
+			Some((_, Content::Stream)) => return self.stream.event(ev),
+			_ => {}
+		}
+
+		if let Event::Key(key) = ev {
+			if let Some(nav) = common_nav(key, &self.key_config) {
+				self.scroll(nav);
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+		self.hex.focus(focus);
+		self.stream.focus(focus);
+	}
+}
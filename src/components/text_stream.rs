@@ -0,0 +1,713 @@
+use super::{
+	utils::lines::{
+		read_lines, AsyncLineIndexJob, AsyncLineSearchJob, LineIndex,
+	},
+	CommandBlocking, CommandInfo, Component, DrawableComponent,
+	EventState, ViewJump,
+};
+use crate::{
+	app::Environment,
+	asyncjob::AsyncSingleJob,
+	hub::open_untouched,
+	keys::{key_match, SharedKeyConfig},
+	popups::ViewInputKind,
+	progress::ProgressPercent,
+	queue::{InternalEvent, Queue},
+	strings::{self, order},
+	ui::{
+		self, common_nav, style::SharedTheme, ParagraphState, ScrollPos,
+		StatefulParagraph,
+	},
+	AsyncAppNotification, TextStreamProgress,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use filetreelist::MoveSelection;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span, Text},
+	widgets::{Block, Borders, Wrap},
+	Frame,
+};
+use std::{
+	cell::Cell,
+	fs::File,
+	io,
+	path::{Path, PathBuf},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, RwLock,
+	},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// bytes of a line shown at most, the rest is skipped
+const MAX_LINE_BYTES: u64 = 64 * 1024;
+/// bytes of a JSONL record read at most for the pretty view
+const MAX_RECORD_BYTES: u64 = 64 * 1024 * 1024;
+/// columns moved by one sideways scroll
+const SIDEWAYS_STEP: u16 = 8;
+
+/// true for files holding one JSON record per line
+pub fn is_jsonl(path: &Path) -> bool {
+	path.extension().is_some_and(|ext| {
+		ext.eq_ignore_ascii_case("jsonl")
+			|| ext.eq_ignore_ascii_case("ndjson")
+	})
+}
+
+/// rows `line` takes wrapped to `width`, ignoring word boundaries
+fn wrapped_rows(line: &str, width: usize) -> u64 {
+	(line.width().div_ceil(width.max(1)) as u64).max(1)
+}
+
+/// JSONL record at `line`, pretty printed instead of the lines
+struct Record {
+	line: u64,
+	text: String,
+}
+
+/// pages through a text file on disk line by line, reading only
+/// the shown lines while the lines are counted in the background
+pub struct TextStreamComponent {
+	file: Option<(PathBuf, File)>,
+	jsonl: bool,
+	index: Arc<RwLock<LineIndex>>,
+	index_cancel: Arc<AtomicBool>,
+	indexing: AsyncSingleJob<AsyncLineIndexJob>,
+	index_progress: Option<ProgressPercent>,
+	cursor: u64,
+	/// first shown line, moved on draw to keep the cursor visible
+	top: Cell<u64>,
+	/// lines fitting the pane on the last draw
+	page_lines: Cell<u64>,
+	/// width of the widest shown line on the last draw
+	page_width: Cell<u16>,
+	wrap: bool,
+	/// columns scrolled sideways without wrap
+	column: u16,
+	paragraph_state: Cell<ParagraphState>,
+	record: Option<Record>,
+	record_state: Cell<ParagraphState>,
+	query: String,
+	/// line the search for the query typed so far starts at
+	search_origin: u64,
+	/// id of the search whose result is shown, older ones are dropped
+	search_id: u64,
+	search_cancel: Arc<AtomicBool>,
+	searching: AsyncSingleJob<AsyncLineSearchJob>,
+	search_progress: Option<ProgressPercent>,
+	/// outcome of the last search or record
+	message: Option<String>,
+	focused: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl TextStreamComponent {
+	/// creates an empty view
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			file: None,
+			jsonl: false,
+			index: Arc::default(),
+			index_cancel: Arc::default(),
+			indexing: AsyncSingleJob::new(env.sender_app.clone()),
+			index_progress: None,
+			cursor: 0,
+			top: Cell::new(0),
+			page_lines: Cell::new(0),
+			page_width: Cell::new(0),
+			wrap: true,
+			column: 0,
+			paragraph_state: Cell::new(ParagraphState::default()),
+			record: None,
+			record_state: Cell::new(ParagraphState::default()),
+			query: String::new(),
+			search_origin: 0,
+			search_id: 0,
+			search_cancel: Arc::default(),
+			searching: AsyncSingleJob::new(env.sender_app.clone()),
+			search_progress: None,
+			message: None,
+			focused: false,
+			queue: env.queue.clone(),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// opens the file at `path` and counts its lines in the background
+	pub fn load_file(&mut self, path: &Path) -> Result<()> {
+		self.clear();
+
+		let file = open_untouched(path)?;
+		self.jsonl = is_jsonl(path);
+		self.index = Arc::default();
+		self.index_cancel = Arc::default();
+		self.index_progress = Some(ProgressPercent::default());
+		self.indexing.spawn(AsyncLineIndexJob::new(
+			path,
+			self.index.clone(),
+			self.index_cancel.clone(),
+		));
+		self.file = Some((path.to_path_buf(), file));
+
+		Ok(())
+	}
+
+	/// shows nothing, stopping the jobs of the shown file
+	pub fn clear(&mut self) {
+		self.index_cancel.store(true, Ordering::Relaxed);
+		self.stop_search();
+
+		self.file = None;
+		self.jsonl = false;
+		self.index_progress = None;
+		self.cursor = 0;
+		self.top.set(0);
+		self.column = 0;
+		self.record = None;
+		self.query.clear();
+		self.message = None;
+	}
+
+	/// picks up progress and results of indexing and searching
+	pub fn update(&mut self, ev: AsyncAppNotification) {
+		let AsyncAppNotification::TextStream(progress) = ev else {
+			return;
+		};
+
+		match progress {
+			TextStreamProgress::Indexing => {
+				self.index_progress = self.indexing.progress();
+			}
+			TextStreamProgress::Indexed => {
+				// the notification may be meant for another view
+				if !self.indexing.is_pending() {
+					self.index_progress = None;
+				}
+			}
+			TextStreamProgress::Searching => {
+				self.search_progress = self.searching.progress();
+			}
+			TextStreamProgress::Searched => {
+				if !self.searching.is_pending() {
+					self.search_progress = None;
+				}
+				let Some(job) = self.searching.take_last() else {
+					return;
+				};
+				if job.id() != self.search_id {
+					return;
+				}
+
+				self.message = Some(match job.take_result() {
+					Some(Ok(Some(line))) => {
+						self.cursor = line;
+						self.record = None;
+						strings::text_found(line)
+					}
+					Some(Ok(None)) => strings::text_not_found(),
+					Some(Err(e)) => strings::code_load_error(&e.to_string()),
+					None => return,
+				});
+			}
+		}
+	}
+
+	/// true while lines are counted or searched
+	pub fn any_work_pending(&self) -> bool {
+		self.indexing.is_pending() || self.searching.is_pending()
+	}
+
+	/// searches for the text typed in the input popup,
+	/// if this view has focus
+	pub fn jump(&mut self, jump: &ViewJump) {
+		if !self.focused || self.file.is_none() {
+			return;
+		}
+
+		if let ViewJump::Text(query) = jump {
+			self.query.clone_from(query);
+			if self.query.is_empty() {
+				self.stop_search();
+				self.cursor = self.search_origin;
+				self.message = None;
+			} else {
+				self.search_from(self.search_origin);
+			}
+		}
+	}
+
+	fn path(&self) -> Option<&Path> {
+		self.file.as_ref().map(|(path, _)| path.as_path())
+	}
+
+	/// lines counted so far and whether that is all of them
+	fn line_count(&self) -> (u64, bool) {
+		self.index
+			.read()
+			.map(|index| (index.lines(), index.is_complete()))
+			.unwrap_or_default()
+	}
+
+	fn read(
+		&self,
+		first: u64,
+		count: u64,
+		limit: u64,
+	) -> io::Result<Vec<String>> {
+		let Some((_, file)) = &self.file else {
+			return Ok(Vec::new());
+		};
+		let index = self
+			.index
+			.read()
+			.map_err(|_| io::Error::other("line index poisoned"))?;
+
+		read_lines(
+			file,
+			&index,
+			first,
+			usize::try_from(count).unwrap_or(usize::MAX),
+			limit,
+		)
+	}
+
+	/// cancels the running search and drops its result
+	fn stop_search(&mut self) {
+		self.search_cancel.store(true, Ordering::Relaxed);
+		self.search_id += 1;
+		self.search_progress = None;
+	}
+
+	fn search_from(&mut self, from: u64) {
+		self.stop_search();
+		self.search_cancel = Arc::default();
+
+		let Some(path) = self.path() else {
+			return;
+		};
+		let job = AsyncLineSearchJob::new(
+			self.search_id,
+			path,
+			self.index.clone(),
+			from,
+			self.query.clone(),
+			self.search_cancel.clone(),
+		);
+
+		self.search_progress = Some(ProgressPercent::default());
+		self.message = None;
+		self.searching.spawn(job);
+	}
+
+	fn search_next(&mut self) -> bool {
+		if self.query.is_empty() || self.searching.is_pending() {
+			return false;
+		}
+
+		self.search_from(self.cursor + 1);
+
+		true
+	}
+
+	fn open_search(&mut self) {
+		self.search_origin = self.cursor;
+		self.queue
+			.push(InternalEvent::OpenViewInput(ViewInputKind::Text));
+	}
+
+	fn move_cursor(&mut self, nav: MoveSelection) -> bool {
+		let page = self.page_lines.get().saturating_sub(1).max(1);
+		let last = self.line_count().0.saturating_sub(1);
+		let old = self.cursor;
+
+		self.cursor = match nav {
+			MoveSelection::Down => old.saturating_add(1),
+			MoveSelection::Up => old.saturating_sub(1),
+			MoveSelection::Top => 0,
+			MoveSelection::End => last,
+			MoveSelection::PageUp => old.saturating_sub(page),
+			MoveSelection::PageDown => old.saturating_add(page),
+			MoveSelection::Left | MoveSelection::Right => old,
+		}
+		.min(last);
+
+		old != self.cursor
+	}
+
+	fn scroll_sideways(&mut self, right: bool) -> bool {
+		let old = self.column;
+		self.column = if right {
+			let max = self.page_width.get().saturating_sub(1);
+			old.saturating_add(SIDEWAYS_STEP).min(max).max(old)
+		} else {
+			old.saturating_sub(SIDEWAYS_STEP)
+		};
+
+		old != self.column
+	}
+
+	fn toggle_wrap(&mut self) {
+		self.wrap = !self.wrap;
+		self.column = 0;
+	}
+
+	fn show_record(&mut self) {
+		let line = match self.read(self.cursor, 1, MAX_RECORD_BYTES) {
+			Ok(mut lines) if !lines.is_empty() => lines.swap_remove(0),
+			Ok(_) => return,
+			Err(e) => {
+				self.message =
+					Some(strings::code_load_error(&e.to_string()));
+				return;
+			}
+		};
+
+		let pretty = serde_json::from_str::<serde_json::Value>(&line)
+			.and_then(|value| serde_json::to_string_pretty(&value));
+		match pretty {
+			Ok(text) => {
+				self.record = Some(Record {
+					line: self.cursor,
+					text,
+				});
+				self.record_state.set(ParagraphState::default());
+				self.message = None;
+			}
+			Err(e) => {
+				self.message =
+					Some(strings::record_invalid(&e.to_string()));
+			}
+		}
+	}
+
+	fn scroll_record(&self, nav: MoveSelection) -> bool {
+		let mut state = self.record_state.get();
+		let page = state.height().saturating_sub(2);
+		let max = state.lines().saturating_sub(page);
+		let old = state.scroll().y;
+
+		let new = match nav {
+			MoveSelection::Down => old.saturating_add(1),
+			MoveSelection::Up => old.saturating_sub(1),
+			MoveSelection::Top => 0,
+			MoveSelection::End => max,
+			MoveSelection::PageUp => old.saturating_sub(page),
+			MoveSelection::PageDown => old.saturating_add(page),
+			MoveSelection::Left | MoveSelection::Right => old,
+		}
+		.min(max);
+
+		state.set_scroll(ScrollPos { x: 0, y: new });
+		self.record_state.set(state);
+
+		new != old
+	}
+
+	/// first line to draw so the cursor is within `height` rows
+	/// of `width` columns, wrapped rows are estimated
+	fn top_for_cursor(&self, height: u64, width: usize) -> u64 {
+		let top = self.top.get();
+		if self.cursor < top {
+			return self.cursor;
+		}
+
+		let start = top.max((self.cursor + 1).saturating_sub(height));
+		if !self.wrap {
+			return start;
+		}
+
+		let Ok(lines) =
+			self.read(start, self.cursor - start + 1, MAX_LINE_BYTES)
+		else {
+			return start;
+		};
+
+		// drop lines from the front until the rest up to the cursor fits
+		let mut rows: u64 =
+			lines.iter().map(|line| wrapped_rows(line, width)).sum();
+		let mut first = start;
+		for line in &lines {
+			if rows <= height || first == self.cursor {
+				break;
+			}
+			rows -= wrapped_rows(line, width);
+			first += 1;
+		}
+
+		first
+	}
+
+	fn page_text(&self, height: u64, width: usize) -> Text<'static> {
+		let top = self.top_for_cursor(height, width);
+		self.top.set(top);
+
+		let lines = match self.read(top, height, MAX_LINE_BYTES) {
+			Ok(lines) => lines,
+			Err(e) => {
+				return Text::from(strings::code_load_error(
+					&e.to_string(),
+				));
+			}
+		};
+
+		let mut rows = 0;
+		let mut fitting = 0;
+		for line in &lines {
+			rows += if self.wrap { wrapped_rows(line, width) } else { 1 };
+			if rows > height {
+				break;
+			}
+			fitting += 1;
+		}
+		self.page_lines.set(fitting.max(1));
+		self.page_width.set(
+			lines
+				.iter()
+				.map(|line| u16::try_from(line.width()).unwrap_or(u16::MAX))
+				.max()
+				.unwrap_or_default(),
+		);
+
+		Text::from(
+			lines
+				.into_iter()
+				.enumerate()
+				.map(|(idx, line)| {
+					let selected = top + idx as u64 == self.cursor;
+					Line::from(Span::styled(
+						line,
+						self.theme.text(true, selected && self.focused),
+					))
+				})
+				.collect::<Vec<_>>(),
+		)
+	}
+
+	fn title(&self) -> String {
+		let name = self
+			.path()
+			.and_then(Path::file_name)
+			.map_or_else(strings::title_code, |n| {
+				n.to_string_lossy().into_owned()
+			});
+
+		match &self.record {
+			Some(record) => strings::title_record(&name, record.line),
+			None => name,
+		}
+	}
+
+	fn status(&self) -> String {
+		let (lines, complete) = self.line_count();
+		let mut status =
+			strings::stream_position(self.cursor, lines, complete);
+
+		if !self.wrap && self.column > 0 {
+			status = format!(
+				"{status}  {}",
+				strings::stream_nowrap(self.column)
+			);
+		}
+		if let Some(p) = self.index_progress {
+			status = format!(
+				"{status}  {}",
+				strings::stream_indexing(p.progress)
+			);
+		}
+
+		match (self.search_progress, &self.message) {
+			(Some(p), _) => {
+				format!("{status}  {}", strings::hex_searching(p.progress))
+			}
+			(None, Some(msg)) => format!("{status}  {msg}"),
+			(None, None) => status,
+		}
+	}
+
+	fn block(&self) -> Block<'static> {
+		Block::default()
+			.title(Span::styled(self.title(), self.theme.title(self.focused)))
+			.title_bottom(Span::styled(
+				self.status(),
+				self.theme.text(self.focused, false),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(self.focused))
+	}
+
+	fn draw_record(&self, text: &str, f: &mut Frame, area: Rect) {
+		let content = StatefulParagraph::new(Text::from(text))
+			.wrap(Wrap { trim: false })
+			.block(self.block());
+
+		let mut state = self.record_state.get();
+		f.render_stateful_widget(content, area, &mut state);
+		self.record_state.set(state);
+
+		if self.focused {
+			ui::draw_scrollbar(
+				f,
+				area,
+				&self.theme,
+				usize::from(state.lines().saturating_sub(state.height())),
+				usize::from(state.scroll().y),
+				ui::Orientation::Vertical,
+			);
+		}
+	}
+}
+
+impl DrawableComponent for TextStreamComponent {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if let Some(record) = &self.record {
+			self.draw_record(&record.text, f, area);
+			return Ok(());
+		}
+
+		let height = u64::from(area.height.saturating_sub(2));
+		let width = usize::from(area.width.saturating_sub(2));
+		let text = self.page_text(height, width);
+
+		let content = StatefulParagraph::new(text).block(self.block());
+		let content = if self.wrap {
+			content.wrap(Wrap { trim: false })
+		} else {
+			content
+		};
+
+		let mut state = self.paragraph_state.get();
+		state.set_scroll(ScrollPos {
+			x: if self.wrap { 0 } else { self.column },
+			y: 0,
+		});
+		f.render_stateful_widget(content, area, &mut state);
+		self.paragraph_state.set(state);
+
+		if self.focused {
+			ui::draw_scrollbar(
+				f,
+				area,
+				&self.theme,
+				usize::try_from(self.line_count().0.saturating_sub(1))
+					.unwrap_or(usize::MAX),
+				usize::try_from(self.cursor).unwrap_or(usize::MAX),
+				ui::Orientation::Vertical,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for TextStreamComponent {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		let open = self.file.is_some();
+
+		out.push(
+			CommandInfo::new(
+				strings::commands::scroll(&self.key_config),
+				open,
+				self.focused,
+			)
+			.order(order::NAV),
+		);
+
+		if self.record.is_some() {
+			out.push(CommandInfo::new(
+				strings::commands::hide_record(&self.key_config),
+				true,
+				self.focused,
+			));
+			return CommandBlocking::PassingOn;
+		}
+
+		out.push(CommandInfo::new(
+			strings::commands::search_text(&self.key_config),
+			open,
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::search_next(&self.key_config),
+			open && !self.query.is_empty() && !self.searching.is_pending(),
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::toggle_wrap(&self.key_config),
+			open,
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::scroll_sideways(&self.key_config),
+			open && !self.wrap,
+			self.focused,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::show_record(&self.key_config),
+			open,
+			self.focused && self.jsonl,
+		));
+
+		CommandBlocking::PassingOn
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.focused || self.file.is_none() {
+			return Ok(EventState::NotConsumed);
+		}
+		let Event::Key(key) = ev else {
+			return Ok(EventState::NotConsumed);
+		};
+		let keys = &self.key_config.keys;
+
+		if self.record.is_some() {
+			if let Some(nav) = common_nav(key, &self.key_config) {
+				self.scroll_record(nav);
+				return Ok(EventState::Consumed);
+			} else if key_match(key, keys.enter) {
+				self.record = None;
+				return Ok(EventState::Consumed);
+			}
+
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Some(nav) = common_nav(key, &self.key_config) {
+			self.move_cursor(nav);
+			return Ok(EventState::Consumed);
+		} else if key_match(key, keys.scroll_left) {
+			return Ok(self.scroll_sideways(false).into());
+		} else if key_match(key, keys.scroll_right) {
+			return Ok(self.scroll_sideways(true).into());
+		} else if key_match(key, keys.toggle_wrap) {
+			self.toggle_wrap();
+			return Ok(EventState::Consumed);
+		} else if key_match(key, keys.search) {
+			self.open_search();
+			return Ok(EventState::Consumed);
+		} else if key_match(key, keys.search_next) {
+			return Ok(self.search_next().into());
+		} else if self.jsonl && key_match(key, keys.enter) {
+			self.show_record();
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+
+	fn focus(&mut self, focus: bool) {
+		self.focused = focus;
+	}
+}
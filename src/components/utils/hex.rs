@@ -0,0 +1,340 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	hub::open_untouched,
+	progress::ProgressPercent,
+	AsyncAppNotification, HexSearchProgress,
+};
+use anyhow::{anyhow, Result};
+use std::{
+	fs::File,
+	io::{self, Read, Seek, SeekFrom},
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+/// bytes read at once while searching
+const SEARCH_CHUNK: usize = 1024 * 1024;
+
+/// formats of weight and data files told apart by their first bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobFormat {
+	Safetensors,
+	Gguf,
+	/// zip archive, as written by `torch.save`
+	Zip,
+	/// legacy pickled `torch.save` checkpoint
+	Pickle,
+	/// guessed from a plausible first field, there is no magic number
+	Protobuf,
+	Parquet,
+}
+
+/// decodes a protobuf varint, returning it and the bytes it took
+fn varint(bytes: &[u8]) -> Option<(u64, usize)> {
+	let mut value = 0_u64;
+	for (idx, byte) in bytes.iter().take(10).enumerate() {
+		value |= u64::from(byte & 0x7f) << (7 * idx);
+		if byte & 0x80 == 0 {
+			return Some((value, idx + 1));
+		}
+	}
+
+	None
+}
+
+/// true if `head` starts with a protobuf field that fits in `len` bytes
+fn protobuf_like(head: &[u8], len: u64) -> bool {
+	let Some((&key, rest)) = head.split_first() else {
+		return false;
+	};
+	if !(1..=15).contains(&(key >> 3)) {
+		return false;
+	}
+
+	match key & 0x07 {
+		0 => varint(rest).is_some(),
+		2 => varint(rest).is_some_and(|(size, taken)| {
+			size > 0 && size <= len.saturating_sub(1 + taken as u64)
+		}),
+		_ => false,
+	}
+}
+
+/// format of a file of `len` bytes starting with `head`
+pub fn detect_format(head: &[u8], len: u64) -> Option<BlobFormat> {
+	if head.starts_with(b"GGUF") {
+		return Some(BlobFormat::Gguf);
+	}
+	if head.starts_with(b"PAR1") {
+		return Some(BlobFormat::Parquet);
+	}
+	if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+		return Some(BlobFormat::Zip);
+	}
+	if let [0x80, 2..=5, ..] = head {
+		return Some(BlobFormat::Pickle);
+	}
+
+	// little endian header size followed by the JSON header
+	if let Some((size, rest)) = head.split_first_chunk::<8>() {
+		let size = u64::from_le_bytes(*size);
+		if rest.first() == Some(&b'{')
+			&& size >= 2 && size <= len.saturating_sub(8)
+		{
+			return Some(BlobFormat::Safetensors);
+		}
+	}
+
+	protobuf_like(head, len).then_some(BlobFormat::Protobuf)
+}
+
+/// parses a decimal offset or a hex one prefixed with `0x`,
+/// `_` may separate digits
+pub fn parse_offset(text: &str) -> Option<u64> {
+	let text = text.trim().replace('_', "");
+
+	match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+		Some(hex) => u64::from_str_radix(hex, 16).ok(),
+		None => text.parse().ok(),
+	}
+}
+
+/// parses hex bytes (`de ad be ef`, `deadbeef`) or quoted text (`"GGUF"`)
+pub fn parse_pattern(text: &str) -> Option<Vec<u8>> {
+	let text = text.trim();
+
+	let bytes = match text
+		.strip_prefix('"')
+		.and_then(|quoted| quoted.strip_suffix('"'))
+	{
+		Some(quoted) => quoted.as_bytes().to_vec(),
+		None => {
+			let digits: String =
+				text.chars().filter(|c| !c.is_whitespace()).collect();
+			hex::decode(digits).ok()?
+		}
+	};
+
+	(!bytes.is_empty()).then_some(bytes)
+}
+
+/// reads up to `len` bytes of `file` starting at `offset`
+pub fn read_at(file: &File, offset: u64, len: u64) -> io::Result<Vec<u8>> {
+	let mut reader = file;
+	reader.seek(SeekFrom::Start(offset))?;
+
+	let mut content = Vec::new();
+	reader.take(len).read_to_end(&mut content)?;
+
+	Ok(content)
+}
+
+/// position of the first `pattern` in `reader`, counted from where
+/// the reader stands; `progress` gets the bytes read so far
+pub fn find_pattern(
+	reader: &mut impl Read,
+	pattern: &[u8],
+	mut progress: impl FnMut(u64),
+) -> io::Result<Option<u64>> {
+	if pattern.is_empty() {
+		return Ok(Some(0));
+	}
+
+	// the tail of a chunk a match may continue from
+	let keep = pattern.len() - 1;
+	let mut chunk = vec![0; SEARCH_CHUNK];
+	let mut window = Vec::with_capacity(SEARCH_CHUNK + keep);
+	// offset of `window[0]`
+	let mut start = 0_u64;
+
+	loop {
+		let read = match reader.read(&mut chunk) {
+			Ok(0) => return Ok(None),
+			Ok(read) => read,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		};
+		window.extend_from_slice(&chunk[..read]);
+
+		if let Some(pos) =
+			window.windows(pattern.len()).position(|w| w == pattern)
+		{
+			return Ok(Some(start + pos as u64));
+		}
+
+		let done = window.len().saturating_sub(keep);
+		window.drain(..done);
+		start += done as u64;
+		progress(start + window.len() as u64);
+	}
+}
+
+/// searches a file for a byte pattern in the background
+#[derive(Clone)]
+pub struct AsyncHexSearchJob {
+	path: PathBuf,
+	from: u64,
+	pattern: Vec<u8>,
+	result: Arc<Mutex<Option<io::Result<Option<u64>>>>>,
+}
+
+impl AsyncHexSearchJob {
+	/// job searching `path` for `pattern` starting at offset `from`
+	pub fn new(path: &Path, from: u64, pattern: Vec<u8>) -> Self {
+		Self {
+			path: path.to_path_buf(),
+			from,
+			pattern,
+			result: Arc::default(),
+		}
+	}
+
+	/// file searched
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// offset of the match or `None` if there is none, once the job finished
+	pub fn take_result(&self) -> Option<io::Result<Option<u64>>> {
+		self.result.lock().ok().and_then(|mut r| r.take())
+	}
+
+	fn search(
+		&self,
+		params: &RunParams<AsyncAppNotification, ProgressPercent>,
+	) -> io::Result<Option<u64>> {
+		let mut file = open_untouched(&self.path)?;
+		let total = file.metadata()?.len().saturating_sub(self.from);
+		file.seek(SeekFrom::Start(self.from))?;
+
+		let found = find_pattern(&mut file, &self.pattern, |done| {
+			let progress = ProgressPercent::new(
+				usize::try_from(done).unwrap_or(usize::MAX),
+				usize::try_from(total).unwrap_or(usize::MAX),
+			);
+			if params.set_progress(progress).unwrap_or_default() {
+				if let Err(e) = params.send(
+					AsyncAppNotification::HexSearch(
+						HexSearchProgress::Progress,
+					),
+				) {
+					log::error!("hex search progress: {e}");
+				}
+			}
+		})?;
+
+		Ok(found.map(|pos| pos + self.from))
+	}
+}
+
+impl AsyncJob for AsyncHexSearchJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		params.set_progress(ProgressPercent::default())?;
+
+		let found = self.search(&params);
+		*self
+			.result
+			.lock()
+			.map_err(|_| anyhow!("hex search result poisoned"))? =
+			Some(found);
+
+		Ok(AsyncAppNotification::HexSearch(HexSearchProgress::Done))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use std::io::Cursor;
+
+	#[test]
+	fn test_detect_format() {
+		let mut safetensors = 4_u64.to_le_bytes().to_vec();
+		safetensors.extend_from_slice(b"{}  ");
+
+		assert_eq!(
+			detect_format(&safetensors, 12),
+			Some(BlobFormat::Safetensors)
+		);
+		// header larger than the file
+		assert_eq!(detect_format(&safetensors, 10), None);
+		assert_eq!(
+			detect_format(b"GGUF\x03\x00", 100),
+			Some(BlobFormat::Gguf)
+		);
+		assert_eq!(
+			detect_format(b"PK\x03\x04rest", 100),
+			Some(BlobFormat::Zip)
+		);
+		assert_eq!(
+			detect_format(b"\x80\x02cfoo", 100),
+			Some(BlobFormat::Pickle)
+		);
+		assert_eq!(
+			detect_format(b"PAR1\x15\x04", 100),
+			Some(BlobFormat::Parquet)
+		);
+		// field 1, length delimited, 3 bytes
+		assert_eq!(
+			detect_format(b"\x0a\x03abc", 5),
+			Some(BlobFormat::Protobuf)
+		);
+		assert_eq!(detect_format(b"\x0a\x09abc", 5), None);
+		assert_eq!(detect_format(b"\x00\x00\x00", 3), None);
+		assert_eq!(detect_format(b"", 0), None);
+	}
+
+	#[test]
+	fn test_parse_offset() {
+		assert_eq!(parse_offset("1024"), Some(1024));
+		assert_eq!(parse_offset(" 0x1f "), Some(31));
+		assert_eq!(parse_offset("0X1_000"), Some(4096));
+		assert_eq!(parse_offset("1_000_000"), Some(1_000_000));
+		assert_eq!(parse_offset("0x"), None);
+		assert_eq!(parse_offset("-1"), None);
+		assert_eq!(parse_offset("ff"), None);
+	}
+
+	#[test]
+	fn test_parse_pattern() {
+		assert_eq!(
+			parse_pattern("de ad BE ef"),
+			Some(vec![0xde, 0xad, 0xbe, 0xef])
+		);
+		assert_eq!(parse_pattern("0a0b"), Some(vec![0x0a, 0x0b]));
+		assert_eq!(parse_pattern("\"GGUF\""), Some(b"GGUF".to_vec()));
+		assert_eq!(parse_pattern("abc"), None);
+		assert_eq!(parse_pattern("zz"), None);
+		assert_eq!(parse_pattern("\"\""), None);
+		assert_eq!(parse_pattern(""), None);
+	}
+
+	#[test]
+	fn test_find_pattern() {
+		let mut data = vec![0_u8; SEARCH_CHUNK + 16];
+		// spans the chunk boundary
+		data[SEARCH_CHUNK - 2..SEARCH_CHUNK + 2]
+			.copy_from_slice(b"GGUF");
+
+		let mut read = 0;
+		let found =
+			find_pattern(&mut Cursor::new(&data), b"GGUF", |done| {
+				read = done;
+			})
+			.unwrap();
+		assert_eq!(found, Some(SEARCH_CHUNK as u64 - 2));
+		assert_eq!(read, SEARCH_CHUNK as u64);
+
+		let found =
+			find_pattern(&mut Cursor::new(&data), b"PAR1", |_| {})
+				.unwrap();
+		assert_eq!(found, None);
+	}
+}
@@ -0,0 +1,517 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	hub::open_untouched,
+	progress::ProgressPercent,
+	string_utils::tabs_to_spaces,
+	AsyncAppNotification, TextStreamProgress,
+};
+use anyhow::{anyhow, Result};
+use std::{
+	fs::File,
+	io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+	path::{Path, PathBuf},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex, RwLock,
+	},
+};
+
+/// lines between two checkpoints of a `LineIndex`
+const CHECKPOINT_LINES: u64 = 1024;
+/// bytes read at once while indexing
+const INDEX_CHUNK: usize = 1024 * 1024;
+/// bytes read at once while searching, lines may be far longer
+const SEARCH_CHUNK: usize = 1024 * 1024;
+
+/// sparse offsets of line starts, filled in while a file is indexed
+#[derive(Debug, Default)]
+pub struct LineIndex {
+	/// start of every `CHECKPOINT_LINES`th line, beginning with line 0
+	checkpoints: Vec<u64>,
+	/// lines counted so far
+	lines: u64,
+	/// bytes counted so far
+	indexed: u64,
+	/// start of the line not terminated yet
+	line_start: u64,
+	complete: bool,
+}
+
+impl LineIndex {
+	/// lines counted so far, all of them once complete
+	pub const fn lines(&self) -> u64 {
+		self.lines
+	}
+
+	/// true once the whole file is counted
+	pub const fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// bytes counted so far
+	pub const fn indexed(&self) -> u64 {
+		self.indexed
+	}
+
+	/// counts the lines in `chunk`, read right after the bytes so far
+	pub fn extend(&mut self, chunk: &[u8]) {
+		if self.checkpoints.is_empty() {
+			self.checkpoints.push(0);
+		}
+
+		for (pos, _) in
+			chunk.iter().enumerate().filter(|(_, b)| **b == b'\n')
+		{
+			self.lines += 1;
+			self.line_start = self.indexed + pos as u64 + 1;
+			if self.lines.is_multiple_of(CHECKPOINT_LINES) {
+				self.checkpoints.push(self.line_start);
+			}
+		}
+
+		self.indexed += chunk.len() as u64;
+	}
+
+	/// marks the index complete, counting a last line without newline
+	pub fn finish(&mut self) {
+		if self.checkpoints.is_empty() {
+			self.checkpoints.push(0);
+		}
+		if self.indexed > self.line_start {
+			self.lines += 1;
+		}
+
+		self.complete = true;
+	}
+
+	/// nearest known line start at or before `line`, as line and offset
+	pub fn checkpoint(&self, line: u64) -> (u64, u64) {
+		let idx = usize::try_from(line / CHECKPOINT_LINES)
+			.unwrap_or(usize::MAX)
+			.min(self.checkpoints.len().saturating_sub(1));
+
+		(
+			idx as u64 * CHECKPOINT_LINES,
+			self.checkpoints.get(idx).copied().unwrap_or_default(),
+		)
+	}
+}
+
+/// reads a line of at most `limit` bytes without its line break,
+/// skipping the rest of it; `None` at the end of the file
+fn read_line_capped(
+	reader: &mut impl BufRead,
+	limit: u64,
+) -> io::Result<Option<Vec<u8>>> {
+	let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+	let mut line = Vec::new();
+	let mut any = false;
+
+	loop {
+		let buf = match reader.fill_buf() {
+			Ok(buf) => buf,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		};
+		if buf.is_empty() {
+			return Ok(any.then_some(line));
+		}
+		any = true;
+
+		let (used, end) = match buf.iter().position(|b| *b == b'\n') {
+			Some(pos) => (pos + 1, true),
+			None => (buf.len(), false),
+		};
+		let room = limit.saturating_sub(line.len());
+		line.extend_from_slice(&buf[..used.min(room)]);
+		reader.consume(used);
+
+		if end {
+			if line.last() == Some(&b'\n') {
+				line.pop();
+			}
+			if line.last() == Some(&b'\r') {
+				line.pop();
+			}
+			return Ok(Some(line));
+		}
+	}
+}
+
+/// `file` positioned at the start of `line`, skipping forward
+/// from the nearest checkpoint
+fn seek_line<'a>(
+	file: &'a File,
+	index: &LineIndex,
+	line: u64,
+) -> io::Result<BufReader<&'a File>> {
+	let (mut at, offset) = index.checkpoint(line);
+	let mut reader = BufReader::new(file);
+	reader.seek(SeekFrom::Start(offset))?;
+
+	while at < line && reader.skip_until(b'\n')? > 0 {
+		at += 1;
+	}
+
+	Ok(reader)
+}
+
+/// up to `count` lines starting at `first`, each cut to `limit` bytes
+pub fn read_lines(
+	file: &File,
+	index: &LineIndex,
+	first: u64,
+	count: usize,
+	limit: u64,
+) -> io::Result<Vec<String>> {
+	let mut reader = seek_line(file, index, first)?;
+	let mut lines = Vec::with_capacity(count);
+
+	while lines.len() < count {
+		let Some(line) = read_line_capped(&mut reader, limit)? else {
+			break;
+		};
+		lines.push(tabs_to_spaces(
+			String::from_utf8_lossy(&line).into_owned(),
+		));
+	}
+
+	Ok(lines)
+}
+
+/// lines read from `reader` before the first one containing `needle`,
+/// ignoring ASCII case; `progress` gets the bytes read so far.
+/// reads in chunks like `hex::find_pattern`, never a whole line
+pub fn find_line(
+	reader: &mut impl Read,
+	needle: &str,
+	cancel: &AtomicBool,
+	mut progress: impl FnMut(u64),
+) -> io::Result<Option<u64>> {
+	let needle = needle.to_ascii_lowercase().into_bytes();
+	if needle.is_empty() {
+		return Ok(Some(0));
+	}
+
+	// the tail of a chunk a match may continue from
+	let keep = needle.len() - 1;
+	let mut chunk = vec![0; SEARCH_CHUNK];
+	let mut window = Vec::with_capacity(SEARCH_CHUNK + keep);
+	// line breaks before `window[0]` and bytes up to its end
+	let mut number = 0;
+	let mut read = 0_u64;
+
+	loop {
+		let len = match reader.read(&mut chunk) {
+			Ok(0) => return Ok(None),
+			Ok(len) => len,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		};
+		if cancel.load(Ordering::Relaxed) {
+			return Ok(None);
+		}
+		window.extend(chunk[..len].iter().map(u8::to_ascii_lowercase));
+		read += len as u64;
+
+		if let Some(pos) =
+			window.windows(needle.len()).position(|w| w == needle)
+		{
+			return Ok(Some(number + line_breaks(&window[..pos])));
+		}
+
+		let done = window.len().saturating_sub(keep);
+		number += line_breaks(&window[..done]);
+		window.drain(..done);
+		progress(read);
+	}
+}
+
+fn line_breaks(bytes: &[u8]) -> u64 {
+	bytes.iter().filter(|&&b| b == b'\n').count() as u64
+}
+
+/// sends a stream notification if the progress changed
+fn report(
+	params: &RunParams<AsyncAppNotification, ProgressPercent>,
+	done: u64,
+	total: u64,
+	notification: TextStreamProgress,
+) {
+	let progress = ProgressPercent::new(
+		usize::try_from(done).unwrap_or(usize::MAX),
+		usize::try_from(total).unwrap_or(usize::MAX),
+	);
+	if params.set_progress(progress).unwrap_or_default() {
+		if let Err(e) =
+			params.send(AsyncAppNotification::TextStream(notification))
+		{
+			log::error!("text stream progress: {e}");
+		}
+	}
+}
+
+/// counts the lines of a file in the background, publishing
+/// checkpoints as it goes so the start of the file shows right away
+#[derive(Clone)]
+pub struct AsyncLineIndexJob {
+	path: PathBuf,
+	index: Arc<RwLock<LineIndex>>,
+	cancel: Arc<AtomicBool>,
+}
+
+impl AsyncLineIndexJob {
+	/// job filling `index` with the lines of `path` until `cancel` is set
+	pub fn new(
+		path: &Path,
+		index: Arc<RwLock<LineIndex>>,
+		cancel: Arc<AtomicBool>,
+	) -> Self {
+		Self {
+			path: path.to_path_buf(),
+			index,
+			cancel,
+		}
+	}
+
+	fn index(
+		&self,
+		params: &RunParams<AsyncAppNotification, ProgressPercent>,
+	) -> Result<()> {
+		let mut file = open_untouched(&self.path)?;
+		let total = file.metadata()?.len();
+		let mut chunk = vec![0; INDEX_CHUNK];
+
+		loop {
+			if self.cancel.load(Ordering::Relaxed) {
+				return Ok(());
+			}
+
+			let read = match file.read(&mut chunk) {
+				Ok(read) => read,
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+					continue
+				}
+				Err(e) => return Err(e.into()),
+			};
+
+			let mut index = self
+				.index
+				.write()
+				.map_err(|_| anyhow!("line index poisoned"))?;
+			if read == 0 {
+				index.finish();
+				return Ok(());
+			}
+			index.extend(&chunk[..read]);
+			let done = index.indexed();
+			drop(index);
+
+			report(params, done, total, TextStreamProgress::Indexing);
+		}
+	}
+}
+
+impl AsyncJob for AsyncLineIndexJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		params.set_progress(ProgressPercent::default())?;
+
+		if let Err(e) = self.index(&params) {
+			log::error!("line index of {}: {e}", self.path.display());
+		}
+
+		Ok(AsyncAppNotification::TextStream(TextStreamProgress::Indexed))
+	}
+}
+
+/// searches the lines of a file for a text in the background
+#[derive(Clone)]
+pub struct AsyncLineSearchJob {
+	id: u64,
+	path: PathBuf,
+	index: Arc<RwLock<LineIndex>>,
+	from: u64,
+	needle: String,
+	cancel: Arc<AtomicBool>,
+	result: Arc<Mutex<Option<io::Result<Option<u64>>>>>,
+}
+
+impl AsyncLineSearchJob {
+	/// job `id` looking for the first line from `from` on containing
+	/// `needle`, seeking through `index`, until `cancel` is set
+	pub fn new(
+		id: u64,
+		path: &Path,
+		index: Arc<RwLock<LineIndex>>,
+		from: u64,
+		needle: String,
+		cancel: Arc<AtomicBool>,
+	) -> Self {
+		Self {
+			id,
+			path: path.to_path_buf(),
+			index,
+			from,
+			needle,
+			cancel,
+			result: Arc::default(),
+		}
+	}
+
+	/// id given to `new`
+	pub const fn id(&self) -> u64 {
+		self.id
+	}
+
+	/// matching line or `None` if there is none, once the job finished
+	pub fn take_result(&self) -> Option<io::Result<Option<u64>>> {
+		self.result.lock().ok().and_then(|mut r| r.take())
+	}
+
+	fn search(
+		&self,
+		params: &RunParams<AsyncAppNotification, ProgressPercent>,
+	) -> io::Result<Option<u64>> {
+		let file = open_untouched(&self.path)?;
+		let mut reader = match self.index.read() {
+			Ok(index) => seek_line(&file, &index, self.from)?,
+			Err(_) => return Err(io::Error::other("line index poisoned")),
+		};
+		let start = reader.stream_position()?;
+		let total = file.metadata()?.len().saturating_sub(start);
+
+		let found =
+			find_line(&mut reader, &self.needle, &self.cancel, |done| {
+				report(params, done, total, TextStreamProgress::Searching);
+			})?;
+
+		Ok(found.map(|line| line + self.from))
+	}
+}
+
+impl AsyncJob for AsyncLineSearchJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		params.set_progress(ProgressPercent::default())?;
+
+		let found = self.search(&params);
+		*self
+			.result
+			.lock()
+			.map_err(|_| anyhow!("line search result poisoned"))? =
+			Some(found);
+
+		Ok(AsyncAppNotification::TextStream(TextStreamProgress::Searched))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use std::io::{Cursor, Write};
+	use tempfile::NamedTempFile;
+
+	fn index_of(content: &[u8]) -> LineIndex {
+		let mut index = LineIndex::default();
+		// small chunks to cross chunk boundaries
+		for chunk in content.chunks(7) {
+			index.extend(chunk);
+		}
+		index.finish();
+		index
+	}
+
+	#[test]
+	fn test_line_index() {
+		assert_eq!(index_of(b"").lines(), 0);
+		assert_eq!(index_of(b"a\nb\n").lines(), 2);
+		assert_eq!(index_of(b"a\nb").lines(), 2);
+
+		let content: String =
+			(0..3000).map(|n| format!("line {n}\n")).collect();
+		let index = index_of(content.as_bytes());
+		assert_eq!(index.lines(), 3000);
+		assert_eq!(index.checkpoint(0), (0, 0));
+		assert_eq!(index.checkpoint(1023), (0, 0));
+
+		let (line, offset) = index.checkpoint(2500);
+		assert_eq!(line, 2048);
+		assert!(content[usize::try_from(offset).unwrap()..]
+			.starts_with("line 2048\n"));
+	}
+
+	#[test]
+	fn test_read_lines() {
+		let content: String =
+			(0..3000).map(|n| format!("line\t{n}\r\n")).collect();
+		let mut file = NamedTempFile::new().unwrap();
+		file.write_all(content.as_bytes()).unwrap();
+		file.write_all(b"last without newline").unwrap();
+
+		let index = index_of(&std::fs::read(file.path()).unwrap());
+		let file = File::open(file.path()).unwrap();
+
+		assert_eq!(
+			read_lines(&file, &index, 2047, 2, 100).unwrap(),
+			vec!["line  2047", "line  2048"]
+		);
+		assert_eq!(
+			read_lines(&file, &index, 2999, 5, 6).unwrap(),
+			vec!["line  2", "last w"]
+		);
+		assert!(read_lines(&file, &index, 5000, 5, 100)
+			.unwrap()
+			.is_empty());
+	}
+
+	#[test]
+	fn test_find_line() {
+		let content = b"alpha\nbeta\nGamma delta\n";
+		let cancel = AtomicBool::new(false);
+		let find = |needle| {
+			find_line(&mut Cursor::new(content), needle, &cancel, |_| {})
+				.unwrap()
+		};
+
+		assert_eq!(find("beta"), Some(1));
+		assert_eq!(find("gamma"), Some(2));
+		assert_eq!(find("A D"), Some(2));
+		assert_eq!(find("epsilon"), None);
+		assert_eq!(find(""), Some(0));
+
+		cancel.store(true, Ordering::Relaxed);
+		assert_eq!(find("gamma"), None);
+	}
+
+	#[test]
+	fn test_find_line_across_chunks() {
+		// a single line longer than a chunk, the match straddling it
+		let mut content = b"first\n".to_vec();
+		content.resize(SEARCH_CHUNK - 2, b'x');
+		content.extend_from_slice(b"NEEDLE\nlast\n");
+		let cancel = AtomicBool::new(false);
+
+		let mut read = 0;
+		let found = find_line(
+			&mut Cursor::new(&content),
+			"needle",
+			&cancel,
+			|n| read = n,
+		)
+		.unwrap();
+		assert_eq!(found, Some(1));
+		assert_eq!(read, SEARCH_CHUNK as u64);
+	}
+}
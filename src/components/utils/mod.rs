@@ -1,10 +1,17 @@
 
+use crate::hub::open_untouched;
 use chrono::{DateTime, Local, Utc};
+use std::{
+	io::{self, Read},
+	path::Path,
+};
 use unicode_width::UnicodeWidthStr;
 
 #[cfg(feature = "ghemoji")]
 pub mod emoji;
 // pub mod filetree;
+pub mod hex;
+pub mod lines;
 // pub mod logitems;
 pub mod scroll_horizontal;
 pub mod scroll_vertical;
@@ -46,6 +53,84 @@ pub fn time_to_string(secs: i64, short: bool) -> String {
 	.to_string()
 }
 
+/// how long before `now` the unix time `secs` lies (`3 weeks ago`),
+/// in the largest unit that fits at least once
+pub fn time_to_relative_string(secs: i64, now: i64) -> String {
+	const UNITS: [(i64, &str); 6] = [
+		(60, "minute"),
+		(60 * 60, "hour"),
+		(24 * 60 * 60, "day"),
+		(7 * 24 * 60 * 60, "week"),
+		(30 * 24 * 60 * 60, "month"),
+		(365 * 24 * 60 * 60, "year"),
+	];
+
+	let elapsed = now.saturating_sub(secs);
+	let Some((unit_secs, unit)) =
+		UNITS.iter().rev().find(|(unit_secs, _)| elapsed >= *unit_secs)
+	else {
+		return String::from("just now");
+	};
+
+	let count = elapsed / unit_secs;
+	let plural = if count == 1 { "" } else { "s" };
+	format!("{count} {unit}{plural} ago")
+}
+
+/// current unix time
+pub fn now_secs() -> i64 {
+	Utc::now().timestamp()
+}
+
+/// bytes searched for a nul byte to tell binary files apart
+pub const BINARY_PROBE_SIZE: u64 = 8 * 1024;
+
+/// reads up to `limit` bytes of `path`, telling whether the file is longer
+pub fn read_head(path: &Path, limit: u64) -> io::Result<(Vec<u8>, bool)> {
+	let mut content = Vec::new();
+	open_untouched(path)?.take(limit + 1).read_to_end(&mut content)?;
+
+	let truncated = content.len() as u64 > limit;
+	content.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+
+	Ok((content, truncated))
+}
+
+/// true if `content` looks like the start of a binary file
+pub fn is_binary(content: &[u8]) -> bool {
+	content.iter().take(BINARY_PROBE_SIZE as usize).any(|b| *b == 0)
+}
+
+/// human readable size, signed for deltas (`+1.5 GB`, `-3 B`)
+pub fn bytes_delta_to_string(delta: i64) -> String {
+	let sign = match delta.signum() {
+		1 => "+",
+		-1 => "-",
+		_ => "",
+	};
+
+	format!("{sign}{}", bytes_to_string(delta.unsigned_abs()))
+}
+
+/// human readable size using decimal units (`1.5 GB`)
+pub fn bytes_to_string(bytes: u64) -> String {
+	const UNITS: [&str; 5] = ["kB", "MB", "GB", "TB", "PB"];
+
+	if bytes < 1000 {
+		return format!("{bytes} B");
+	}
+
+	#[allow(clippy::cast_precision_loss)]
+	let mut size = bytes as f64 / 1000.0;
+	let mut unit = 0;
+	while size >= 999.95 && unit + 1 < UNITS.len() {
+		size /= 1000.0;
+		unit += 1;
+	}
+
+	format!("{size:.1} {}", UNITS[unit])
+}
+
 #[inline]
 pub fn string_width_align(s: &str, width: usize) -> String {
 	static POSTFIX: &str = "..";
@@ -68,3 +153,41 @@ pub fn string_width_align(s: &str, width: usize) -> String {
 fn find_truncate_point(s: &str, chars: usize) -> usize {
 	s.chars().take(chars).map(char::len_utf8).sum()
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_bytes_to_string() {
+		assert_eq!(bytes_to_string(0), "0 B");
+		assert_eq!(bytes_to_string(999), "999 B");
+		assert_eq!(bytes_to_string(1_000), "1.0 kB");
+		assert_eq!(bytes_to_string(1_540_000), "1.5 MB");
+		assert_eq!(bytes_to_string(999_990_000), "1.0 GB");
+		assert_eq!(bytes_to_string(7_000_000_000_000), "7.0 TB");
+	}
+
+	#[test]
+	fn test_bytes_delta_to_string() {
+		assert_eq!(bytes_delta_to_string(0), "0 B");
+		assert_eq!(bytes_delta_to_string(1_540_000), "+1.5 MB");
+		assert_eq!(bytes_delta_to_string(-3), "-3 B");
+	}
+
+	#[test]
+	fn test_time_to_relative_string() {
+		let now = 1_000_000_000;
+		let ago = |secs: i64| time_to_relative_string(now - secs, now);
+
+		assert_eq!(ago(0), "just now");
+		assert_eq!(ago(59), "just now");
+		assert_eq!(ago(-60), "just now");
+		assert_eq!(ago(60), "1 minute ago");
+		assert_eq!(ago(2 * 60 * 60 + 5), "2 hours ago");
+		assert_eq!(ago(21 * 24 * 60 * 60), "3 weeks ago");
+		assert_eq!(ago(45 * 24 * 60 * 60), "1 month ago");
+		assert_eq!(ago(800 * 24 * 60 * 60), "2 years ago");
+	}
+}
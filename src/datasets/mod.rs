@@ -0,0 +1,157 @@
+//! model of the `datasets` library cache
+//!
+//! every builder folder (`org___name`, `json`, `parquet`, ...) holds
+//! `<config>/<version>/<hash>` folders with a `dataset_info.json`,
+//! the prepared split files `<builder>-<split>[-NNNNN-of-NNNNN].arrow`
+//! and the `cache-<fingerprint>.arrow` results of `Dataset.map` and friends.
+
+mod job;
+mod scan;
+
+pub use job::AsyncDatasetsJob;
+pub use scan::scan_datasets;
+
+use std::path::PathBuf;
+
+/// separator used in place of `/` inside builder folder names
+pub const NAMESPACE_SEPARATOR: &str = "___";
+
+/// why a `cache-*.arrow` file is considered orphaned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrphanReason {
+	/// the prepared dataset it was computed from is gone
+	MissingBase,
+}
+
+/// one `cache-*.arrow` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrowCacheFile {
+	pub path: PathBuf,
+	pub size: u64,
+	/// `Some` if nothing can load this file anymore
+	pub orphaned: Option<OrphanReason>,
+}
+
+/// one split as described by `dataset_info.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatasetSplit {
+	pub name: String,
+	/// rows according to `dataset_info.json`
+	pub num_examples: Option<u64>,
+	/// number of arrow shards found on disk
+	pub files: usize,
+	/// size of the shards on disk
+	pub size: u64,
+}
+
+/// one prepared `<version>/<hash>` folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetBuild {
+	pub version: String,
+	pub hash: String,
+	pub path: PathBuf,
+	/// splits sorted by name
+	pub splits: Vec<DatasetSplit>,
+	/// map results sorted by path
+	pub cache_files: Vec<ArrowCacheFile>,
+	/// size of all files in the folder
+	pub size: u64,
+	/// unix time of the last modification of the folder
+	pub last_modified: i64,
+}
+
+impl DatasetBuild {
+	/// true if `dataset_info.json` and split files exist
+	pub fn has_base(&self) -> bool {
+		self.splits.iter().any(|s| s.files > 0)
+	}
+
+	/// rows of all splits, `None` if no split knows its row count
+	pub fn num_examples(&self) -> Option<u64> {
+		self.splits
+			.iter()
+			.filter_map(|s| s.num_examples)
+			.reduce(|a, b| a + b)
+	}
+
+	/// size of the orphaned map results
+	pub fn orphaned_size(&self) -> u64 {
+		self.orphans().map(|f| f.size).sum()
+	}
+
+	/// map results nothing can load anymore
+	pub fn orphans(&self) -> impl Iterator<Item = &ArrowCacheFile> {
+		self.cache_files.iter().filter(|f| f.orphaned.is_some())
+	}
+}
+
+/// one config folder of a builder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetConfig {
+	pub name: String,
+	/// builds sorted by version and hash
+	pub builds: Vec<DatasetBuild>,
+}
+
+/// one builder folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedDataset {
+	/// dataset name as used on the hub (`org/name`) or the packaged builder
+	pub name: String,
+	pub path: PathBuf,
+	/// configs sorted by name
+	pub configs: Vec<DatasetConfig>,
+}
+
+impl CachedDataset {
+	/// all builds of all configs
+	pub fn builds(&self) -> impl Iterator<Item = &DatasetBuild> {
+		self.configs.iter().flat_map(|c| c.builds.iter())
+	}
+
+	/// size of all builds
+	pub fn size(&self) -> u64 {
+		self.builds().map(|b| b.size).sum()
+	}
+
+	/// size of the orphaned map results of all builds
+	pub fn orphaned_size(&self) -> u64 {
+		self.builds().map(DatasetBuild::orphaned_size).sum()
+	}
+}
+
+/// result of scanning the datasets cache
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatasetsCache {
+	/// scanned cache folder
+	pub root: PathBuf,
+	/// datasets sorted by name
+	pub datasets: Vec<CachedDataset>,
+}
+
+impl DatasetsCache {
+	/// size of all orphaned map results
+	pub fn orphaned_size(&self) -> u64 {
+		self.datasets.iter().map(CachedDataset::orphaned_size).sum()
+	}
+}
+
+/// `org___name` to `org/name`
+pub fn dataset_name_from_folder(folder: &str) -> String {
+	folder.replace(NAMESPACE_SEPARATOR, "/")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_dataset_name_from_folder() {
+		assert_eq!(
+			dataset_name_from_folder("stanfordnlp___imdb"),
+			"stanfordnlp/imdb"
+		);
+		assert_eq!(dataset_name_from_folder("parquet"), "parquet");
+	}
+}
@@ -0,0 +1,341 @@
+use super::{
+	dataset_name_from_folder, ArrowCacheFile, CachedDataset,
+	DatasetBuild, DatasetConfig, DatasetSplit, DatasetsCache,
+	OrphanReason,
+};
+use crate::hub::modified_secs;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+const INFO_FILE: &str = "dataset_info.json";
+const CACHE_FILE_PREFIX: &str = "cache-";
+const ARROW_EXTENSION: &str = "arrow";
+/// `datasets` keeps downloaded raw files next to the builders
+const DOWNLOADS_FOLDER: &str = "downloads";
+/// suffix of builds still being prepared
+const INCOMPLETE_SUFFIX: &str = ".incomplete";
+
+/// scans all builder folders below `root`, an empty cache if `root` does not exist
+pub fn scan_datasets(root: &Path) -> Result<DatasetsCache> {
+	let mut datasets = Vec::new();
+
+	if root.is_dir() {
+		for folder in sub_folders(root)? {
+			let name = file_name(&folder);
+			if name == DOWNLOADS_FOLDER || name.starts_with('.') {
+				continue;
+			}
+
+			match scan_dataset(&folder) {
+				Ok(dataset) if !dataset.configs.is_empty() => {
+					datasets.push(dataset);
+				}
+				Ok(_) => (),
+				Err(e) => {
+					log::error!("scan_dataset {}: {e}", folder.display());
+				}
+			}
+		}
+	}
+
+	datasets.sort_by(|a, b| a.name.cmp(&b.name));
+
+	Ok(DatasetsCache {
+		root: root.to_path_buf(),
+		datasets,
+	})
+}
+
+fn scan_dataset(path: &Path) -> Result<CachedDataset> {
+	let mut configs = Vec::new();
+
+	for config in sub_folders(path)? {
+		let mut builds = Vec::new();
+
+		for version in sub_folders(&config)? {
+			for build in sub_folders(&version)? {
+				if file_name(&build).ends_with(INCOMPLETE_SUFFIX) {
+					continue;
+				}
+
+				builds.push(scan_build(&build, file_name(&version))?);
+			}
+		}
+
+		if builds.is_empty() {
+			continue;
+		}
+
+		builds.sort_by(|a, b| {
+			(&a.version, &a.hash).cmp(&(&b.version, &b.hash))
+		});
+		mark_orphans(&mut builds);
+
+		configs.push(DatasetConfig {
+			name: file_name(&config),
+			builds,
+		});
+	}
+
+	configs.sort_by(|a, b| a.name.cmp(&b.name));
+
+	Ok(CachedDataset {
+		name: dataset_name_from_folder(&file_name(path)),
+		path: path.to_path_buf(),
+		configs,
+	})
+}
+
+fn scan_build(path: &Path, version: String) -> Result<DatasetBuild> {
+	let mut splits = read_splits(&path.join(INFO_FILE));
+	let mut cache_files = Vec::new();
+	let mut size = 0;
+
+	for entry in fs::read_dir(path)? {
+		let entry = entry?;
+		if !entry.file_type()?.is_file() {
+			continue;
+		}
+
+		let file_size = entry.metadata()?.len();
+		size += file_size;
+
+		let path = entry.path();
+		if path.extension().is_none_or(|e| e != ARROW_EXTENSION) {
+			continue;
+		}
+
+		let name = file_name(&path);
+		if name.starts_with(CACHE_FILE_PREFIX) {
+			cache_files.push(ArrowCacheFile {
+				path,
+				size: file_size,
+				orphaned: None,
+			});
+		} else if let Some(split) = split_of_file(&name, &mut splits) {
+			split.files += 1;
+			split.size += file_size;
+		}
+	}
+
+	cache_files.sort_by(|a, b| a.path.cmp(&b.path));
+	splits.sort_by(|a, b| a.name.cmp(&b.name));
+
+	Ok(DatasetBuild {
+		version,
+		hash: file_name(path),
+		path: path.to_path_buf(),
+		splits,
+		cache_files,
+		size,
+		last_modified: modified_secs(path),
+	})
+}
+
+/// splits listed in `dataset_info.json`, none if it is missing or unreadable
+fn read_splits(info: &Path) -> Vec<DatasetSplit> {
+	let Ok(content) = fs::read_to_string(info) else {
+		return Vec::new();
+	};
+
+	let info: Value = match serde_json::from_str(&content) {
+		Ok(info) => info,
+		Err(e) => {
+			log::error!("{}: {e}", info.display());
+			return Vec::new();
+		}
+	};
+
+	info.get("splits")
+		.and_then(Value::as_object)
+		.map(|splits| {
+			splits
+				.iter()
+				.map(|(name, split)| DatasetSplit {
+					name: name.clone(),
+					num_examples: split
+						.get("num_examples")
+						.and_then(Value::as_u64),
+					..DatasetSplit::default()
+				})
+				.collect()
+		})
+		.unwrap_or_default()
+}
+
+/// split a prepared `<builder>-<split>[-NNNNN-of-NNNNN].arrow` file belongs to
+fn split_of_file<'a>(
+	file: &str,
+	splits: &'a mut [DatasetSplit],
+) -> Option<&'a mut DatasetSplit> {
+	let stem = file.strip_suffix(".arrow")?;
+	let stem = strip_shard_suffix(stem).unwrap_or(stem);
+
+	splits
+		.iter_mut()
+		.filter(|s| stem.ends_with(&format!("-{}", s.name)))
+		// `train` must not steal the files of `sub-train`
+		.max_by_key(|s| s.name.len())
+}
+
+/// strips `-00000-of-00003`
+fn strip_shard_suffix(stem: &str) -> Option<&str> {
+	let (rest, total) = stem.rsplit_once("-of-")?;
+	let (rest, index) = rest.rsplit_once('-')?;
+	let is_number = |s: &str| {
+		!s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+	};
+
+	(is_number(total) && is_number(index)).then_some(rest)
+}
+
+/// flags the map results of builds without base data.
+/// builds of the same version differ in their config fingerprint,
+/// a newer one does not make an older one unloadable
+fn mark_orphans(builds: &mut [DatasetBuild]) {
+	for build in builds {
+		let reason =
+			(!build.has_base()).then_some(OrphanReason::MissingBase);
+
+		for file in &mut build.cache_files {
+			file.orphaned = reason;
+		}
+	}
+}
+
+fn sub_folders(path: &Path) -> Result<Vec<PathBuf>> {
+	let mut folders = Vec::new();
+
+	for entry in fs::read_dir(path)
+		.with_context(|| format!("failed to read {}", path.display()))?
+	{
+		let entry = entry?;
+		if entry.file_type()?.is_dir() {
+			folders.push(entry.path());
+		}
+	}
+
+	Ok(folders)
+}
+
+fn file_name(path: &Path) -> String {
+	path.file_name()
+		.map(|n| n.to_string_lossy().to_string())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use filetime::{set_file_mtime, FileTime};
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	fn add_build(
+		root: &Path,
+		build: &str,
+		info: Option<&str>,
+		files: &[(&str, &str)],
+	) -> PathBuf {
+		let path = root.join(build);
+		fs::create_dir_all(&path).unwrap();
+
+		if let Some(info) = info {
+			fs::write(path.join(INFO_FILE), info).unwrap();
+		}
+		for (name, content) in files {
+			fs::write(path.join(name), content).unwrap();
+		}
+
+		path
+	}
+
+	#[test]
+	fn test_split_of_file() {
+		let mut splits = vec![
+			DatasetSplit {
+				name: "train".to_string(),
+				..DatasetSplit::default()
+			},
+			DatasetSplit {
+				name: "sub-train".to_string(),
+				..DatasetSplit::default()
+			},
+		];
+
+		let split = |file, splits: &mut [DatasetSplit]| {
+			split_of_file(file, splits).map(|s| s.name.clone())
+		};
+
+		assert_eq!(
+			split("imdb-train-00001-of-00002.arrow", &mut splits),
+			Some("train".to_string())
+		);
+		assert_eq!(
+			split("imdb-sub-train.arrow", &mut splits),
+			Some("sub-train".to_string())
+		);
+		assert_eq!(split("imdb-test.arrow", &mut splits), None);
+	}
+
+	#[test]
+	fn test_scan_datasets() {
+		let root = TempDir::new().unwrap();
+		let info = r#"{"splits": {
+			"train": {"name": "train", "num_examples": 3},
+			"test": {"name": "test", "num_examples": 2}
+		}}"#;
+
+		// an older build of other data files stays loadable
+		let old = add_build(
+			root.path(),
+			"org___data/default/1.0.0/aaa",
+			Some(info),
+			&[("data-train.arrow", "1234"), ("cache-x.arrow", "12")],
+		);
+		set_file_mtime(&old, FileTime::from_unix_time(1_000, 0)).unwrap();
+		add_build(
+			root.path(),
+			"org___data/default/1.0.0/bbb",
+			Some(info),
+			&[
+				("data-train-00000-of-00002.arrow", "12"),
+				("data-train-00001-of-00002.arrow", "12"),
+				("data-test.arrow", "1"),
+				("cache-y.arrow", "123"),
+			],
+		);
+		add_build(
+			root.path(),
+			"json/default-123/0.0.0/ccc",
+			None,
+			&[("cache-z.arrow", "1")],
+		);
+		fs::create_dir_all(root.path().join("downloads/extracted"))
+			.unwrap();
+
+		let cache = scan_datasets(root.path()).unwrap();
+		let names: Vec<_> =
+			cache.datasets.iter().map(|d| d.name.as_str()).collect();
+		assert_eq!(names, vec!["json", "org/data"]);
+
+		let builds = &cache.datasets[1].configs[0].builds;
+		assert_eq!(builds[1].hash, "bbb");
+		assert_eq!(builds[1].num_examples(), Some(5));
+		assert_eq!(builds[1].splits[1].files, 2);
+		assert_eq!(builds[1].splits[1].size, 4);
+		assert_eq!(builds[1].cache_files[0].orphaned, None);
+		assert_eq!(builds[0].cache_files[0].orphaned, None);
+
+		let json = &cache.datasets[0].configs[0].builds[0];
+		assert_eq!(
+			json.cache_files[0].orphaned,
+			Some(OrphanReason::MissingBase)
+		);
+		assert_eq!(cache.orphaned_size(), 1);
+	}
+}
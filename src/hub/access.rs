@@ -0,0 +1,162 @@
+//! last access of repos
+//!
+//! the scanner reads the access times of snapshot files where the
+//! mount records them. mounted with `noatime` they never change, so a
+//! journal remembers the last time every repo was seen in use instead:
+//! any file read where atimes are kept, any download or update that
+//! modified the repo folders everywhere. the journal outlives the
+//! scan index and repos moved to a `noatime` mount keep their history.
+
+use super::{stats::statvfs, CachedRepo};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	fs::{self, File},
+	io,
+	path::{Path, PathBuf},
+};
+
+const JOURNAL_FILENAME: &str = "access_journal.ron";
+
+/// false if the file system of `path` is mounted without access times
+pub fn atime_supported(path: &Path) -> bool {
+	#[cfg(target_os = "linux")]
+	{
+		statvfs(path)
+			.is_none_or(|stat| stat.f_flag & libc::ST_NOATIME == 0)
+	}
+	#[cfg(not(target_os = "linux"))]
+	{
+		statvfs(path).is_some()
+	}
+}
+
+/// opens `path` for reading without moving its access time, so that
+/// hashing and previewing files does not count as using the repo.
+/// `O_NOATIME` is only allowed on own files, others are opened
+/// normally, restoring their atime would need ownership just as well
+pub fn open_untouched(path: &Path) -> io::Result<File> {
+	#[cfg(target_os = "linux")]
+	{
+		use std::os::unix::fs::OpenOptionsExt;
+
+		let untouched = fs::OpenOptions::new()
+			.read(true)
+			.custom_flags(libc::O_NOATIME)
+			.open(path);
+		match untouched {
+			Err(e) if e.raw_os_error() == Some(libc::EPERM) => {
+				File::open(path)
+			}
+			opened => opened,
+		}
+	}
+	#[cfg(not(target_os = "linux"))]
+	{
+		File::open(path)
+	}
+}
+
+/// location of the journal in the app data directory
+pub fn journal_path() -> Result<PathBuf> {
+	Ok(crate::args::get_app_data_path()?.join(JOURNAL_FILENAME))
+}
+
+/// newest known access of every repo folder, kept between runs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessJournal {
+	/// unix time of the last access by repo folder
+	repos: BTreeMap<PathBuf, i64>,
+}
+
+impl AccessJournal {
+	/// reads the journal at `path`, empty if there is none
+	pub fn load(path: &Path) -> Self {
+		let Ok(content) = fs::read_to_string(path) else {
+			return Self::default();
+		};
+
+		ron::from_str(&content).unwrap_or_else(|e| {
+			log::error!("access journal {}: {e}", path.display());
+			Self::default()
+		})
+	}
+
+	/// writes the journal to `path`, replacing it atomically
+	pub fn save(&self, path: &Path) -> Result<()> {
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+
+		let tmp = path.with_extension("ron.tmp");
+		fs::write(&tmp, ron::to_string(self)?)?;
+		fs::rename(&tmp, path)?;
+
+		Ok(())
+	}
+
+	/// records the accesses seen by the scan of `repos` and sets their
+	/// `last_accessed` to the newest one known, true if the journal changed
+	pub fn update(&mut self, repos: &mut [CachedRepo]) -> bool {
+		let mut changed = false;
+
+		for repo in repos {
+			let written = repo.stamps.iter().map(|s| s.mtime).max();
+			let known = self.repos.get(&repo.path).copied();
+			let newest = known.max(repo.last_accessed).max(written);
+
+			if let Some(secs) = newest.filter(|_| newest != known) {
+				self.repos.insert(repo.path.clone(), secs);
+				changed = true;
+			}
+
+			repo.last_accessed = newest;
+		}
+
+		changed
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::scan::{scan_repo, tests::add_file};
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_journal_update() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+		add_file(&path, "aaaa", "config.json", "c1", "{}");
+
+		let mut repo = scan_repo(&path, 0).unwrap();
+		let written = repo.stamps.iter().map(|s| s.mtime).max();
+		repo.last_accessed = None;
+
+		// without access times the last modification counts
+		let mut journal = AccessJournal::default();
+		let mut repos = vec![repo.clone()];
+		assert!(journal.update(&mut repos));
+		assert_eq!(repos[0].last_accessed, written);
+		assert!(!journal.update(&mut repos));
+
+		// newer accesses replace older ones, never the other way round
+		let later = written.unwrap() + 100;
+		repos[0].last_accessed = Some(later);
+		assert!(journal.update(&mut repos));
+
+		repos[0].last_accessed = None;
+		assert!(!journal.update(&mut repos));
+		assert_eq!(repos[0].last_accessed, Some(later));
+
+		let file = root.path().join("journal.ron");
+		journal.save(&file).unwrap();
+		assert_eq!(AccessJournal::load(&file), journal);
+		assert_eq!(
+			AccessJournal::load(&root.path().join("missing.ron")),
+			AccessJournal::default()
+		);
+	}
+}
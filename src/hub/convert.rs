@@ -0,0 +1,371 @@
+use super::{
+	access::open_untouched, verify::blob_names, AsyncHubNotification,
+	CachedRepo, BLOBS_FOLDER,
+};
+use crate::{
+	args::APP_NAME,
+	asyncjob::{AsyncJob, RunParams},
+	progress::ProgressPercent,
+};
+use anyhow::{anyhow, bail, Result};
+use std::{
+	fs,
+	io::Read,
+	os::unix::fs::symlink,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+/// the hub refuses files above this size in git, they always live in LFS
+const GIT_FILE_LIMIT: u64 = 10 * 1000 * 1000;
+/// suffix of the link created to probe for symlink support
+const PROBE_SUFFIX: &str = "-symlink-probe";
+/// lists the patterns of files stored in LFS, in the snapshot root
+const GITATTRIBUTES: &str = ".gitattributes";
+
+/// outcome of converting a repo to the symlinked layout
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConvertResult {
+	/// snapshot files replaced by a link
+	pub linked: usize,
+	/// bytes freed by dropping duplicate copies
+	pub reclaimed: u64,
+	/// small copies left alone, without blob or `.gitattributes`
+	/// it is unknown whether the hub names them by sha256 or git sha1
+	pub kept: usize,
+	/// files that could not be converted
+	pub errors: Vec<(PathBuf, String)>,
+}
+
+/// name of the link created to probe for symlink support
+fn probe_name() -> String {
+	format!(".{APP_NAME}{PROBE_SUFFIX}")
+}
+
+/// true if links can be created in `dir`, the probe is removed again
+pub fn symlinks_supported(dir: &Path) -> bool {
+	let probe = dir.join(probe_name());
+	let _ = fs::remove_file(&probe);
+
+	let supported = symlink(BLOBS_FOLDER, &probe).is_ok();
+	let _ = fs::remove_file(&probe);
+
+	supported
+}
+
+/// moves the copied snapshot files of `repo` into `blobs/` and links them back,
+/// copies whose blob already exists are dropped
+pub fn convert_repo(
+	repo: &CachedRepo,
+	mut on_read: impl FnMut(u64),
+) -> Result<ConvertResult> {
+	if !symlinks_supported(&repo.path) {
+		bail!("{} does not support symlinks", repo.path.display());
+	}
+
+	let blobs = repo.path.join(BLOBS_FOLDER);
+	fs::create_dir_all(&blobs)?;
+
+	let mut result = ConvertResult::default();
+	for rev in &repo.revisions {
+		let patterns = lfs_patterns(&rev.path);
+
+		for file in rev.files.iter().filter(|f| f.symlink_target.is_none())
+		{
+			let path = rev.path.join(&file.path);
+			let lfs = patterns
+				.as_ref()
+				.map(|patterns| is_lfs(patterns, &file.path));
+
+			match link_file(&blobs, &path, &file.path, lfs, &mut on_read) {
+				Ok(Some(reclaimed)) => {
+					result.linked += 1;
+					result.reclaimed += reclaimed;
+				}
+				Ok(None) => result.kept += 1,
+				Err(e) => result.errors.push((path, e.to_string())),
+			}
+		}
+	}
+
+	Ok(result)
+}
+
+/// replaces the copy at `path` with a link to its blob,
+/// returns the bytes freed if the blob existed already.
+/// `lfs` tells whether `.gitattributes` puts the file in LFS, the
+/// blob name must match for the hub to find it on the next download,
+/// so small files are kept as copies (`None`) if that is unknown
+fn link_file(
+	blobs: &Path,
+	path: &Path,
+	relative: &Path,
+	lfs: Option<bool>,
+	on_read: impl FnMut(u64),
+) -> Result<Option<u64>> {
+	let size = fs::metadata(path)?.len();
+	let (sha256, sha1) = blob_names(path, on_read)?;
+
+	let name = if blobs.join(&sha256).exists() {
+		sha256
+	} else if blobs.join(&sha1).exists() {
+		sha1
+	} else {
+		match lfs {
+			Some(true) => sha256,
+			_ if size > GIT_FILE_LIMIT => sha256,
+			Some(false) => sha1,
+			None => return Ok(None),
+		}
+	};
+
+	let blob = blobs.join(&name);
+	let depth = relative.components().count();
+	let target = PathBuf::from("../".repeat(depth + 1))
+		.join(BLOBS_FOLDER)
+		.join(&name);
+
+	if blob.exists() {
+		if fs::metadata(&blob)?.len() != size {
+			bail!("blob {name} differs in size");
+		}
+
+		let link = path.with_file_name(probe_name());
+		symlink(&target, &link)?;
+		fs::rename(&link, path).inspect_err(|_| {
+			let _ = fs::remove_file(&link);
+		})?;
+
+		return Ok(Some(size));
+	}
+
+	fs::rename(path, &blob)?;
+	if let Err(e) = symlink(&target, path) {
+		restore(&blob, path);
+		return Err(e.into());
+	}
+
+	Ok(Some(0))
+}
+
+/// `(pattern, lfs)` of every line of the `.gitattributes` in
+/// `snapshot` setting a filter, `None` without such a file
+fn lfs_patterns(snapshot: &Path) -> Option<Vec<(String, bool)>> {
+	let mut content = String::new();
+	open_untouched(&snapshot.join(GITATTRIBUTES))
+		.and_then(|mut file| file.read_to_string(&mut content))
+		.ok()?;
+
+	Some(
+		content
+			.lines()
+			.filter_map(|line| {
+				let mut parts = line.split_whitespace();
+				let pattern = parts.next().filter(|p| !p.starts_with('#'))?;
+				let filter = parts.rfind(|attr| {
+					attr.trim_start_matches(['-', '!']).split('=').next()
+						== Some("filter")
+				})?;
+
+				Some((pattern.to_string(), filter == "filter=lfs"))
+			})
+			.collect(),
+	)
+}
+
+/// true if the last of `patterns` matching `path` puts it in LFS,
+/// patterns without a slash match the file name in any folder
+fn is_lfs(patterns: &[(String, bool)], path: &Path) -> bool {
+	let path = path.to_string_lossy();
+	let name = path.rsplit('/').next().unwrap_or(&path);
+
+	patterns
+		.iter()
+		.rev()
+		.find(|(pattern, _)| match pattern.strip_prefix('/') {
+			Some(pattern) => glob_match(pattern.as_bytes(), path.as_bytes()),
+			None if pattern.contains('/') => {
+				glob_match(pattern.as_bytes(), path.as_bytes())
+			}
+			None => glob_match(pattern.as_bytes(), name.as_bytes()),
+		})
+		.is_some_and(|(_, lfs)| *lfs)
+}
+
+/// gitattributes glob, `*` and `?` stay within a folder,
+/// `**` spans folders
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+	match pattern {
+		[] => text.is_empty(),
+		[b'*', b'*', b'/', rest @ ..] => {
+			glob_match(rest, text)
+				|| text.iter().enumerate().any(|(idx, &c)| {
+					c == b'/' && glob_match(rest, &text[idx + 1..])
+				})
+		}
+		[b'*', b'*', rest @ ..] => {
+			(0..=text.len()).any(|idx| glob_match(rest, &text[idx..]))
+		}
+		[b'*', rest @ ..] => (0..=text.len())
+			.take_while(|&idx| idx == 0 || text[idx - 1] != b'/')
+			.any(|idx| glob_match(rest, &text[idx..])),
+		[b'?', rest @ ..] => {
+			text.first().is_some_and(|&c| c != b'/')
+				&& glob_match(rest, &text[1..])
+		}
+		[c, rest @ ..] => {
+			text.first() == Some(c) && glob_match(rest, &text[1..])
+		}
+	}
+}
+
+/// moves a blob back to the snapshot after linking failed
+fn restore(blob: &Path, path: &Path) {
+	if let Err(e) = fs::rename(blob, path) {
+		log::error!("restore {}: {e}", path.display());
+	}
+}
+
+/// converts a repo on a worker thread
+#[derive(Clone)]
+pub struct AsyncConvertJob {
+	repo: Arc<CachedRepo>,
+	result: Arc<Mutex<Option<Result<ConvertResult>>>>,
+}
+
+impl AsyncConvertJob {
+	/// job converting the copied files of `repo`
+	pub fn new(repo: CachedRepo) -> Self {
+		Self {
+			repo: Arc::new(repo),
+			result: Arc::default(),
+		}
+	}
+
+	/// the result, once the job finished
+	pub fn take_result(&self) -> Option<Result<ConvertResult>> {
+		self.result.lock().ok().and_then(|mut r| r.take())
+	}
+}
+
+impl AsyncJob for AsyncConvertJob {
+	type Notification = AsyncHubNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let total = self.repo.copied_size();
+		let mut done = 0_u64;
+
+		params.set_progress(ProgressPercent::default())?;
+
+		let result = convert_repo(&self.repo, |read| {
+			done += read;
+
+			let progress = ProgressPercent::new(
+				usize::try_from(done).unwrap_or(usize::MAX),
+				usize::try_from(total).unwrap_or(usize::MAX),
+			);
+			if params.set_progress(progress).unwrap_or_default() {
+				if let Err(e) =
+					params.send(AsyncHubNotification::ConvertProgress)
+				{
+					log::error!("convert progress: {e}");
+				}
+			}
+		});
+
+		*self
+			.result
+			.lock()
+			.map_err(|_| anyhow!("convert result poisoned"))? = Some(result);
+
+		Ok(AsyncHubNotification::Convert)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::scan::{
+		scan_repo,
+		tests::{add_copy, add_file},
+	};
+	use super::*;
+	use crate::hub::FileKind;
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	// `git hash-object` of "hello\n"
+	const HELLO_SHA1: &str = "ce013625030ba8dba906f756967f9e9ca394464a";
+	const ATTRIBUTES: &str = "\
+*.model filter=lfs diff=lfs merge=lfs -text
+# comment
+/saved_model/**/* filter=lfs diff=lfs merge=lfs -text
+small.model -filter -diff -merge text
+";
+
+	#[test]
+	fn test_convert_repo() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		// without `.gitattributes` only `sub/a.txt` can be linked,
+		// its blob already exists once `aaaa` is converted
+		add_copy(&path, "aaaa", ".gitattributes", ATTRIBUTES);
+		add_copy(&path, "aaaa", "a.txt", "hello\n");
+		add_copy(&path, "aaaa", "tokenizer.model", "spm");
+		add_copy(&path, "bbbb", "sub/a.txt", "hello\n");
+		add_copy(&path, "bbbb", "c.txt", "other");
+		add_file(&path, "bbbb", "b.txt", "b1", "linked");
+
+		let repo = scan_repo(&path, 0).unwrap();
+		assert_eq!(repo.copied_files(), 5);
+
+		let mut read = 0;
+		let result = convert_repo(&repo, |n| read += n).unwrap();
+		assert_eq!(
+			result,
+			ConvertResult {
+				linked: 4,
+				reclaimed: 6,
+				kept: 1,
+				errors: Vec::new(),
+			}
+		);
+		assert_eq!(read, ATTRIBUTES.len() as u64 + 20);
+
+		let repo = scan_repo(&path, 0).unwrap();
+		assert_eq!(repo.copied_files(), 1);
+
+		let aaaa = repo.revision("aaaa").unwrap();
+		assert_eq!(aaaa.files[1].blob_hash.as_deref(), Some(HELLO_SHA1));
+		assert_eq!(aaaa.files[2].path, Path::new("tokenizer.model"));
+		assert_eq!(aaaa.files[2].kind(), FileKind::Lfs);
+
+		let file = &repo.revision("bbbb").unwrap().files[2];
+		assert_eq!(file.path, Path::new("sub/a.txt"));
+		assert_eq!(file.blob_hash.as_deref(), Some(HELLO_SHA1));
+
+		let copy = aaaa.path.join("a.txt");
+		assert_eq!(fs::read_to_string(copy).unwrap(), "hello\n");
+	}
+
+	#[test]
+	fn test_is_lfs() {
+		let root = TempDir::new().unwrap();
+		fs::write(root.path().join(GITATTRIBUTES), ATTRIBUTES).unwrap();
+		let patterns = lfs_patterns(root.path()).unwrap();
+		let lfs = |path| is_lfs(&patterns, Path::new(path));
+
+		assert!(lfs("tokenizer.model"));
+		assert!(lfs("sub/tokenizer.model"));
+		assert!(lfs("saved_model/variables/data"));
+		assert!(lfs("saved_model/data"));
+		assert!(!lfs("small.model"));
+		assert!(!lfs("other/saved_model/data"));
+		assert!(!lfs("config.json"));
+		assert_eq!(lfs_patterns(&root.path().join("missing")), None);
+	}
+}
@@ -0,0 +1,176 @@
+use super::{
+	verify::{hash_file, HashKind},
+	CachedFile, CachedRevision,
+};
+use std::{cmp::Ordering, path::PathBuf};
+
+/// how a file differs between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+	Added,
+	Removed,
+	Modified,
+}
+
+/// a file that differs between two snapshots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+	/// path relative to the snapshot folders
+	pub path: PathBuf,
+	pub kind: ChangeKind,
+	/// size in the old snapshot, 0 if added
+	pub old_size: u64,
+	/// size in the new snapshot, 0 if removed
+	pub new_size: u64,
+}
+
+impl FileChange {
+	/// bytes the file grew by, negative if it shrank
+	pub fn size_delta(&self) -> i64 {
+		let size = |size| i64::try_from(size).unwrap_or(i64::MAX);
+
+		size(self.new_size).saturating_sub(size(self.old_size))
+	}
+}
+
+/// true if both files resolve to different data,
+/// files without blob (no symlinks) of the same size are hashed,
+/// unreadable ones count as modified
+fn modified(
+	old_rev: &CachedRevision,
+	old: &CachedFile,
+	new_rev: &CachedRevision,
+	new: &CachedFile,
+) -> bool {
+	match (&old.blob_hash, &new.blob_hash) {
+		(Some(old_hash), Some(new_hash)) => old_hash != new_hash,
+		_ if old.size != new.size => true,
+		_ => {
+			let hash = |rev: &CachedRevision, file: &CachedFile| {
+				let path = rev.path.join(&file.path);
+				hash_file(&path, HashKind::Sha256, |_| ()).ok()
+			};
+			match (hash(old_rev, old), hash(new_rev, new)) {
+				(Some(old_hash), Some(new_hash)) => old_hash != new_hash,
+				_ => true,
+			}
+		}
+	}
+}
+
+/// files added, removed or modified from `old` to `new`, sorted by path
+pub fn diff_revisions(
+	old: &CachedRevision,
+	new: &CachedRevision,
+) -> Vec<FileChange> {
+	let change = |file: &CachedFile, kind, old_size, new_size| {
+		FileChange {
+			path: file.path.clone(),
+			kind,
+			old_size,
+			new_size,
+		}
+	};
+
+	let mut changes = Vec::new();
+	let mut old_files = old.files.iter().peekable();
+	let mut new_files = new.files.iter().peekable();
+
+	loop {
+		let order = match (old_files.peek(), new_files.peek()) {
+			(Some(o), Some(n)) => o.path.cmp(&n.path),
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(None, None) => break,
+		};
+
+		match order {
+			Ordering::Less => {
+				if let Some(o) = old_files.next() {
+					changes.push(change(
+						o,
+						ChangeKind::Removed,
+						o.size,
+						0,
+					));
+				}
+			}
+			Ordering::Greater => {
+				if let Some(n) = new_files.next() {
+					changes.push(change(
+						n,
+						ChangeKind::Added,
+						0,
+						n.size,
+					));
+				}
+			}
+			Ordering::Equal => {
+				if let (Some(o), Some(n)) =
+					(old_files.next(), new_files.next())
+				{
+					if modified(old, o, new, n) {
+						changes.push(change(
+							n,
+							ChangeKind::Modified,
+							o.size,
+							n.size,
+						));
+					}
+				}
+			}
+		}
+	}
+
+	changes
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::scan::{
+		scan_repo,
+		tests::{add_copy, add_file},
+	};
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_diff_revisions() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		add_file(&path, "aaaa", "config.json", "c1", "{}");
+		add_file(&path, "aaaa", "model.bin", "w", "0123456789");
+		add_file(&path, "aaaa", "old.txt", "o", "old");
+		add_copy(&path, "aaaa", "README.md", "# model");
+		add_copy(&path, "aaaa", "LICENSE", "MIT");
+		add_file(&path, "bbbb", "config.json", "c2", "{ }");
+		add_file(&path, "bbbb", "model.bin", "w", "0123456789");
+		add_file(&path, "bbbb", "new.txt", "n", "new file");
+		add_copy(&path, "bbbb", "README.md", "# Model");
+		add_copy(&path, "bbbb", "LICENSE", "MIT");
+
+		let repo = scan_repo(&path, 0).unwrap();
+		let changes = diff_revisions(
+			repo.revision("aaaa").unwrap(),
+			repo.revision("bbbb").unwrap(),
+		);
+
+		let found: Vec<_> = changes
+			.iter()
+			.map(|c| {
+				(c.path.to_str().unwrap(), c.kind, c.size_delta())
+			})
+			.collect();
+		assert_eq!(
+			found,
+			vec![
+				("README.md", ChangeKind::Modified, 0),
+				("config.json", ChangeKind::Modified, 1),
+				("new.txt", ChangeKind::Added, 8),
+				("old.txt", ChangeKind::Removed, -3),
+			]
+		);
+	}
+}
@@ -0,0 +1,259 @@
+//! scan results persisted between runs
+//!
+//! every repo carries stamps of its folders, a repo whose stamps still
+//! match the disk is taken from the index instead of being rescanned.
+
+use super::{CachedRepo, HubCache, BLOBS_FOLDER};
+use crate::args::CacheRoot;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+	fs,
+	os::unix::fs::MetadataExt,
+	path::{Path, PathBuf},
+};
+
+/// bumped whenever the stored model changes, older indices are ignored
+const INDEX_VERSION: u32 = 2;
+const INDEX_FILENAME: &str = "scan_index.ron";
+const LOCKS_FOLDER: &str = ".locks";
+
+/// identity and modification time of a folder or ref file,
+/// any entry added, removed or renamed in a folder changes its mtime
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirStamp {
+	/// path relative to the repo folder
+	pub path: PathBuf,
+	pub inode: u64,
+	pub mtime: i64,
+	pub mtime_nsec: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexedRepo {
+	/// cache root the repo was found in
+	root: PathBuf,
+	repo: CachedRepo,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScanIndex {
+	version: u32,
+	repos: Vec<IndexedRepo>,
+}
+
+/// location of the index file in the app cache directory
+pub fn index_path() -> Result<PathBuf> {
+	Ok(crate::args::get_app_cache_path()?.join(INDEX_FILENAME))
+}
+
+/// stamps of all folders of the repo at `path`, its ref files
+/// and its lock folder, sorted by path
+pub fn repo_stamps(path: &Path) -> Vec<DirStamp> {
+	let mut stamps = Vec::new();
+	add_stamps(path, Path::new(""), &mut stamps);
+
+	if let Some(locks) = path
+		.parent()
+		.zip(path.file_name())
+		.map(|(root, name)| root.join(LOCKS_FOLDER).join(name))
+	{
+		if let Ok(meta) = fs::symlink_metadata(&locks) {
+			stamps.push(stamp(Path::new(LOCKS_FOLDER), &meta));
+		}
+	}
+
+	stamps.sort_by(|a, b| a.path.cmp(&b.path));
+	stamps
+}
+
+fn add_stamps(base: &Path, relative: &Path, out: &mut Vec<DirStamp>) {
+	let path = base.join(relative);
+	let Ok(meta) = fs::symlink_metadata(&path) else {
+		return;
+	};
+	out.push(stamp(relative, &meta));
+
+	// blobs never hold folders, refs are rewritten in place
+	if !meta.is_dir() || relative == Path::new(BLOBS_FOLDER) {
+		return;
+	}
+
+	let Ok(entries) = fs::read_dir(&path) else {
+		return;
+	};
+	for entry in entries.flatten() {
+		let Ok(file_type) = entry.file_type() else {
+			continue;
+		};
+		let child = relative.join(entry.file_name());
+
+		if file_type.is_dir() || child.starts_with("refs") {
+			add_stamps(base, &child, out);
+		}
+	}
+}
+
+fn stamp(path: &Path, meta: &fs::Metadata) -> DirStamp {
+	DirStamp {
+		path: path.to_path_buf(),
+		inode: meta.ino(),
+		mtime: meta.mtime(),
+		mtime_nsec: meta.mtime_nsec(),
+	}
+}
+
+/// repos of `roots` stored in the index at `path`,
+/// `None` if there is no usable index
+pub fn load_index(path: &Path, roots: &[CacheRoot]) -> Option<HubCache> {
+	let content = fs::read_to_string(path).ok()?;
+	let index: ScanIndex = match ron::from_str(&content) {
+		Ok(index) => index,
+		Err(e) => {
+			log::error!("scan index {}: {e}", path.display());
+			return None;
+		}
+	};
+
+	if index.version != INDEX_VERSION {
+		return None;
+	}
+
+	let mut cache = HubCache::new(roots.to_vec());
+	cache.merge(
+		index
+			.repos
+			.into_iter()
+			.filter_map(|indexed| {
+				let root = roots.iter().position(|r| r.path == indexed.root)?;
+				Some(CachedRepo {
+					root,
+					..indexed.repo
+				})
+			})
+			.collect(),
+	);
+
+	Some(cache)
+}
+
+/// writes `cache` to the index at `path`, replacing it atomically
+pub fn save_index(path: &Path, cache: &HubCache) -> Result<()> {
+	let index = ScanIndex {
+		version: INDEX_VERSION,
+		repos: cache
+			.repos
+			.iter()
+			.filter_map(|repo| {
+				Some(IndexedRepo {
+					root: cache.root_of(repo)?.path.clone(),
+					repo: repo.clone(),
+				})
+			})
+			.collect(),
+	};
+
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)?;
+	}
+
+	let tmp = path.with_extension("ron.tmp");
+	fs::write(&tmp, ron::to_string(&index)?)?;
+	fs::rename(&tmp, path)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::scan::{
+		scan_cache, scan_repo,
+		tests::{add_file, add_ref},
+	};
+	use super::*;
+	use crate::args::CacheRootSource;
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	fn cache_root(path: &Path) -> CacheRoot {
+		CacheRoot {
+			path: path.to_path_buf(),
+			source: CacheRootSource::Argument,
+			label: String::from("local"),
+			writable: true,
+		}
+	}
+
+	#[test]
+	fn test_repo_stamps() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		add_file(&path, "aaaa", "sub/config.json", "c1", "{}");
+		add_ref(&path, "main", "aaaa");
+
+		let paths: Vec<_> = repo_stamps(&path)
+			.into_iter()
+			.map(|s| s.path.to_string_lossy().to_string())
+			.collect();
+		assert_eq!(
+			paths,
+			vec![
+				"",
+				"blobs",
+				"refs",
+				"refs/main",
+				"snapshots",
+				"snapshots/aaaa",
+				"snapshots/aaaa/sub",
+			]
+		);
+
+		let before = repo_stamps(&path);
+		add_file(&path, "bbbb", "config.json", "c2", "{ }");
+		assert_ne!(before, repo_stamps(&path));
+	}
+
+	#[test]
+	fn test_save_load() {
+		let root = TempDir::new().unwrap();
+		let index = TempDir::new().unwrap();
+		let index = index.path().join("index.ron");
+
+		add_file(
+			&root.path().join("models--org--model"),
+			"aaaa",
+			"config.json",
+			"c1",
+			"{}",
+		);
+
+		let roots = vec![cache_root(root.path())];
+		let mut cache = HubCache::new(roots.clone());
+		cache.merge(scan_cache(root.path(), 0, &[], |_, _, _| ()).unwrap());
+
+		save_index(&index, &cache).unwrap();
+		assert_eq!(load_index(&index, &roots), Some(cache.clone()));
+
+		// repos of roots no longer scanned are dropped
+		let other = TempDir::new().unwrap();
+		let roots = vec![cache_root(other.path())];
+		assert_eq!(
+			load_index(&index, &roots).map(|c| c.repos.len()),
+			Some(0)
+		);
+
+		// unchanged repos are taken from the index
+		let mut known = cache.repos.clone();
+		known[0].sizes.apparent = 42;
+		let repos = scan_cache(root.path(), 0, &known, |_, _, _| ()).unwrap();
+		assert_eq!(repos[0].sizes.apparent, 42);
+
+		add_ref(&root.path().join("models--org--model"), "main", "aaaa");
+		let repos = scan_cache(root.path(), 0, &known, |_, _, _| ()).unwrap();
+		assert_eq!(
+			repos[0],
+			scan_repo(&root.path().join("models--org--model"), 0).unwrap()
+		);
+	}
+}
@@ -0,0 +1,249 @@
+use super::{CachedRepo, BLOBS_FOLDER};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashSet,
+	fs::{self, File, TryLockError},
+	io,
+	path::{Path, PathBuf},
+};
+
+const LOCKS_FOLDER: &str = ".locks";
+const INCOMPLETE_SUFFIX: &str = ".incomplete";
+const LOCK_SUFFIX: &str = ".lock";
+
+/// kinds of damage the integrity check detects
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Hash,
+	Serialize,
+	Deserialize,
+)]
+pub enum ProblemKind {
+	/// snapshot symlink whose blob is missing
+	DanglingSymlink,
+	/// blob no snapshot links to
+	OrphanBlob,
+	/// leftover `*.incomplete` of an interrupted download
+	IncompleteDownload,
+	/// ref pointing at a commit without snapshot folder
+	DanglingRef,
+	/// lock file in `.locks` not held by any download,
+	/// whose blob was never completed
+	StaleLock,
+	/// snapshot holding copies instead of links, not deduplicated
+	CopiedFiles,
+}
+
+/// a single problem found in a repo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Problem {
+	pub kind: ProblemKind,
+	/// absolute path of the offending file
+	pub path: PathBuf,
+}
+
+/// classifies all problems of `repo`, sorted by kind and path
+pub fn check_repo(repo: &CachedRepo) -> Result<Vec<Problem>> {
+	let mut problems = Vec::new();
+	let mut referenced = HashSet::new();
+
+	for rev in &repo.revisions {
+		if rev.files.iter().any(|f| f.symlink_target.is_none()) {
+			problems.push(Problem {
+				kind: ProblemKind::CopiedFiles,
+				path: rev.path.clone(),
+			});
+		}
+
+		for file in &rev.files {
+			if let Some(hash) = &file.blob_hash {
+				referenced.insert(hash.as_str());
+			}
+
+			let path = rev.path.join(&file.path);
+			if file.symlink_target.is_some() && fs::metadata(&path).is_err()
+			{
+				problems.push(Problem {
+					kind: ProblemKind::DanglingSymlink,
+					path,
+				});
+			}
+		}
+	}
+
+	for r in &repo.refs {
+		if repo.revision(&r.commit_hash).is_none() {
+			problems.push(Problem {
+				kind: ProblemKind::DanglingRef,
+				path: repo.path.join("refs").join(&r.name),
+			});
+		}
+	}
+
+	let locks = locks_folder(&repo.path);
+	// a download in progress holds the lock of its blob
+	let downloading = |blob: &str| {
+		locks.as_ref().is_some_and(|locks| {
+			let lock = locks.join(format!("{blob}{LOCK_SUFFIX}"));
+			lock.exists() && is_held(&lock)
+		})
+	};
+
+	let blobs = repo.path.join(BLOBS_FOLDER);
+	for entry in read_dir_if_exists(&blobs)? {
+		let entry = entry?;
+		let name = entry.file_name().to_string_lossy().to_string();
+
+		let kind = if let Some(blob) = name.strip_suffix(INCOMPLETE_SUFFIX)
+		{
+			if downloading(blob) {
+				continue;
+			}
+			ProblemKind::IncompleteDownload
+		} else if !referenced.contains(name.as_str()) {
+			ProblemKind::OrphanBlob
+		} else {
+			continue;
+		};
+
+		problems.push(Problem {
+			kind,
+			path: entry.path(),
+		});
+	}
+
+	if let Some(locks) = &locks {
+		for entry in read_dir_if_exists(locks)? {
+			let entry = entry?;
+			let path = entry.path();
+			let name = entry.file_name().to_string_lossy().to_string();
+
+			// huggingface_hub leaves the locks of finished downloads
+			if let Some(blob) = name.strip_suffix(LOCK_SUFFIX) {
+				if !blobs.join(blob).exists() && !is_held(&path) {
+					problems.push(Problem {
+						kind: ProblemKind::StaleLock,
+						path,
+					});
+				}
+			}
+		}
+	}
+
+	problems.sort_by(|a, b| {
+		a.kind.cmp(&b.kind).then_with(|| a.path.cmp(&b.path))
+	});
+
+	Ok(problems)
+}
+
+/// `<root>/.locks/<repo folder>`
+fn locks_folder(repo: &Path) -> Option<PathBuf> {
+	Some(repo.parent()?.join(LOCKS_FOLDER).join(repo.file_name()?))
+}
+
+fn read_dir_if_exists(
+	path: &Path,
+) -> Result<impl Iterator<Item = io::Result<fs::DirEntry>>> {
+	let entries = match fs::read_dir(path) {
+		Ok(entries) => Some(entries),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+		Err(e) => return Err(e.into()),
+	};
+
+	Ok(entries.into_iter().flatten())
+}
+
+/// whether a download holds the lock at `path`, found out by trying
+/// to take it without blocking, if that fails for any other reason
+/// the lock is assumed held
+fn is_held(path: &Path) -> bool {
+	let Ok(file) = File::open(path) else {
+		return true;
+	};
+
+	match file.try_lock() {
+		Ok(()) => false,
+		Err(TryLockError::WouldBlock | TryLockError::Error(_)) => true,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::scan::{
+		scan_repo,
+		tests::{add_file, add_ref},
+	};
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_check_repo() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		add_file(&path, "aaaa", "config.json", "c1", "{}");
+		add_file(&path, "aaaa", "model.bin", "w1", "0123");
+		add_ref(&path, "main", "aaaa");
+		add_ref(&path, "v1", "bbbb");
+		fs::remove_file(path.join("blobs/w1")).unwrap();
+		fs::write(path.join("blobs/orphan"), "").unwrap();
+		fs::write(path.join("blobs/w2.incomplete"), "01").unwrap();
+		fs::write(path.join("blobs/w3.incomplete"), "01").unwrap();
+
+		// `w2` is still downloading, `c1` finished
+		// and `w3` and `old` were given up
+		let locks = root.path().join(".locks/models--org--model");
+		fs::create_dir_all(&locks).unwrap();
+		fs::write(locks.join("w2.lock"), "").unwrap();
+		fs::write(locks.join("c1.lock"), "").unwrap();
+		fs::write(locks.join("old.lock"), "").unwrap();
+		let download = File::open(locks.join("w2.lock")).unwrap();
+		download.lock().unwrap();
+
+		let repo = scan_repo(&path, 0).unwrap();
+		let problems: Vec<_> = repo
+			.problems
+			.iter()
+			.map(|p| {
+				(p.kind, p.path.strip_prefix(root.path()).unwrap())
+			})
+			.collect();
+
+		assert_eq!(
+			problems,
+			vec![
+				(
+					ProblemKind::DanglingSymlink,
+					Path::new(
+						"models--org--model/snapshots/aaaa/model.bin"
+					)
+				),
+				(
+					ProblemKind::OrphanBlob,
+					Path::new("models--org--model/blobs/orphan")
+				),
+				(
+					ProblemKind::IncompleteDownload,
+					Path::new("models--org--model/blobs/w3.incomplete")
+				),
+				(
+					ProblemKind::DanglingRef,
+					Path::new("models--org--model/refs/v1")
+				),
+				(
+					ProblemKind::StaleLock,
+					Path::new(".locks/models--org--model/old.lock")
+				),
+			]
+		);
+	}
+}
@@ -0,0 +1,190 @@
+use super::{
+	scan::scan_repo, scan_cache, AsyncHubNotification, CachedRepo,
+	HubCache,
+};
+use crate::{
+	args::CacheRoot,
+	asyncjob::{AsyncJob, RunParams},
+	progress::ProgressPercent,
+};
+use anyhow::{anyhow, Result};
+use std::{
+	mem,
+	path::PathBuf,
+	sync::{Arc, Mutex},
+};
+
+/// outcome of an `AsyncScanJob`
+pub enum ScanResult {
+	/// all cache roots were scanned
+	Cache(HubCache),
+	/// only some repo folders were rescanned
+	Repos {
+		updated: Vec<CachedRepo>,
+		/// folders that are gone or no longer hold a valid repo
+		removed: Vec<PathBuf>,
+	},
+}
+
+#[derive(Clone)]
+enum ScanTarget {
+	Cache {
+		roots: Vec<CacheRoot>,
+		/// repos of a previous scan, reused if unchanged
+		known: Arc<Vec<CachedRepo>>,
+	},
+	/// repo folders with the index of their root
+	Repos(Vec<(usize, PathBuf)>),
+}
+
+#[derive(Default)]
+struct ScanState {
+	/// repos scanned since the last `take_partial`
+	partial: Vec<CachedRepo>,
+	result: Option<Result<ScanResult>>,
+}
+
+/// scans cache roots on a worker thread,
+/// streaming every finished repo before the full result is ready
+#[derive(Clone)]
+pub struct AsyncScanJob {
+	target: ScanTarget,
+	state: Arc<Mutex<ScanState>>,
+}
+
+impl AsyncScanJob {
+	/// job scanning all of `roots`, rescanning only the repos of `known` that changed
+	pub fn new(roots: Vec<CacheRoot>, known: Vec<CachedRepo>) -> Self {
+		Self {
+			target: ScanTarget::Cache {
+				roots,
+				known: Arc::new(known),
+			},
+			state: Arc::default(),
+		}
+	}
+
+	/// job rescanning just the given repo `folders`, each with its root index
+	pub fn repos(folders: Vec<(usize, PathBuf)>) -> Self {
+		Self {
+			target: ScanTarget::Repos(folders),
+			state: Arc::default(),
+		}
+	}
+
+	/// repos scanned since the last call
+	pub fn take_partial(&self) -> Vec<CachedRepo> {
+		self.state
+			.lock()
+			.map(|mut state| mem::take(&mut state.partial))
+			.unwrap_or_default()
+	}
+
+	/// the final scan result, once the job finished
+	pub fn take_result(&self) -> Option<Result<ScanResult>> {
+		self.state.lock().ok().and_then(|mut state| state.result.take())
+	}
+
+	fn scan_cache(
+		&self,
+		roots: &[CacheRoot],
+		known: &[CachedRepo],
+		params: &RunParams<AsyncHubNotification, ProgressPercent>,
+	) -> Result<ScanResult> {
+		let mut cache = HubCache::new(roots.to_vec());
+
+		for (root_idx, root) in roots.iter().enumerate() {
+			// one unreachable root must not hide the others
+			let scanned = scan_cache(
+				&root.path,
+				root_idx,
+				known,
+				|repo, done, total| {
+					if let Ok(mut state) = self.state.lock() {
+						state.partial.push(repo.clone());
+					}
+
+					Self::progress(
+						params,
+						root_idx * total + done,
+						roots.len() * total,
+					);
+				},
+			);
+
+			match scanned {
+				Ok(repos) => cache.merge(repos),
+				Err(e) if roots.len() > 1 => {
+					log::error!("scan {}: {e}", root.label);
+				}
+				Err(e) => return Err(e),
+			}
+		}
+
+		Ok(ScanResult::Cache(cache))
+	}
+
+	fn scan_repos(
+		folders: &[(usize, PathBuf)],
+		params: &RunParams<AsyncHubNotification, ProgressPercent>,
+	) -> ScanResult {
+		let mut updated = Vec::new();
+		let mut removed = Vec::new();
+
+		for (idx, (root, folder)) in folders.iter().enumerate() {
+			match scan_repo(folder, *root) {
+				Ok(repo) if folder.is_dir() => updated.push(repo),
+				Ok(_) => removed.push(folder.clone()),
+				Err(e) => {
+					log::debug!("rescan {}: {e}", folder.display());
+					removed.push(folder.clone());
+				}
+			}
+
+			Self::progress(params, idx + 1, folders.len());
+		}
+
+		ScanResult::Repos { updated, removed }
+	}
+
+	fn progress(
+		params: &RunParams<AsyncHubNotification, ProgressPercent>,
+		done: usize,
+		total: usize,
+	) {
+		if let Err(e) =
+			params.set_progress(ProgressPercent::new(done, total))
+		{
+			log::error!("scan progress: {e}");
+		}
+		if let Err(e) = params.send(AsyncHubNotification::ScanProgress) {
+			log::error!("scan progress: {e}");
+		}
+	}
+}
+
+impl AsyncJob for AsyncScanJob {
+	type Notification = AsyncHubNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let result = match &self.target {
+			ScanTarget::Cache { roots, known } => {
+				self.scan_cache(roots, known, &params)
+			}
+			ScanTarget::Repos(folders) => {
+				Ok(Self::scan_repos(folders, &params))
+			}
+		};
+
+		self.state
+			.lock()
+			.map_err(|_| anyhow!("scan state poisoned"))?
+			.result = Some(result);
+
+		Ok(AsyncHubNotification::Scan)
+	}
+}
@@ -0,0 +1,575 @@
+//! model of the Hugging Face hub cache
+//!
+//! the hub cache holds one folder per repository
+//! (`models--org--name`, `datasets--org--name`, `spaces--org--name`),
+//! each with the content addressed `blobs`,
+//! the `refs` pointing at commits and one `snapshots/<commit>` tree
+//! per cached revision whose files link into `blobs`.
+//!
+//! on filesystems without symlinks the hub copies files into the
+//! snapshots instead, such repos lose the deduplication of `blobs`.
+//!
+//! several cache roots can be scanned into one `HubCache`,
+//! every repo remembers the root it was found in.
+//! scan results are kept in an index file so that only changed repos
+//! are rescanned on the next start, a journal keeps the last access
+//! of every repo.
+
+mod access;
+mod convert;
+mod diff;
+mod index;
+mod integrity;
+mod job;
+mod scan;
+mod sizes;
+mod stats;
+mod verify;
+
+pub use access::{journal_path, open_untouched, AccessJournal};
+pub use convert::{symlinks_supported, AsyncConvertJob, ConvertResult};
+pub use diff::{diff_revisions, ChangeKind, FileChange};
+pub use index::{index_path, load_index, save_index, DirStamp};
+pub use integrity::{Problem, ProblemKind};
+pub use job::{AsyncScanJob, ScanResult};
+pub use scan::{modified_secs, scan_cache};
+#[cfg(test)]
+pub use scan::tests::add_file;
+pub use sizes::{folder_usage, SizeInfo};
+pub use stats::{
+	free_space, newest_revision, CacheStats, LARGEST_REPOS,
+};
+pub use verify::{
+	verify_items, AsyncVerifyJob, VerifyItem, VerifyResult,
+};
+
+use crate::args::CacheRoot;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// separator used in place of `/` inside repo folder names
+pub const REPO_ID_SEPARATOR: &str = "--";
+/// content addressed file storage inside a repo folder
+const BLOBS_FOLDER: &str = "blobs";
+
+/// kind of a hub repository, told apart by the folder name prefix
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Default,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Hash,
+	Serialize,
+	Deserialize,
+)]
+pub enum RepoType {
+	#[default]
+	Model,
+	Dataset,
+	Space,
+}
+
+impl RepoType {
+	/// all types in display order
+	pub const ALL: [Self; 3] = [Self::Model, Self::Dataset, Self::Space];
+
+	/// folder name prefix of repos of this type
+	pub const fn folder_prefix(self) -> &'static str {
+		match self {
+			Self::Model => "models--",
+			Self::Dataset => "datasets--",
+			Self::Space => "spaces--",
+		}
+	}
+}
+
+/// notifications sent by hub jobs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsyncHubNotification {
+	/// a running scan finished another repo
+	ScanProgress,
+	/// a scan finished
+	Scan,
+	/// a running verification hashed more data
+	VerifyProgress,
+	/// a verification finished
+	Verify,
+	/// a scan of the datasets cache finished
+	Datasets,
+	/// a running layout conversion hashed more data
+	ConvertProgress,
+	/// a layout conversion finished
+	Convert,
+	/// a scan of the `transformers` module cache finished
+	Modules,
+}
+
+/// how a snapshot file is stored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileKind {
+	/// link to a blob named by its sha256, an LFS file
+	Lfs,
+	/// link to a blob named by its git blob sha1
+	Git,
+	/// link to something other than a blob
+	Symlink,
+	/// regular file copied into the snapshot
+	Copy,
+}
+
+/// a single file inside a cached snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedFile {
+	/// path relative to the snapshot folder
+	pub path: PathBuf,
+	/// name of the blob in `blobs/` this file resolves to
+	pub blob_hash: Option<String>,
+	/// size in bytes of the resolved file
+	pub size: u64,
+	/// raw link target if the snapshot entry is a symlink
+	pub symlink_target: Option<PathBuf>,
+}
+
+impl CachedFile {
+	/// storage of the file, told apart by the name of its blob
+	pub fn kind(&self) -> FileKind {
+		if self.symlink_target.is_none() {
+			return FileKind::Copy;
+		}
+
+		match self
+			.blob_hash
+			.as_deref()
+			.and_then(verify::HashKind::from_blob_name)
+		{
+			Some(verify::HashKind::Sha256) => FileKind::Lfs,
+			Some(verify::HashKind::GitSha1) => FileKind::Git,
+			None => FileKind::Symlink,
+		}
+	}
+}
+
+/// a named ref (`main`, `refs/pr/3`, tags) stored under `refs/`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedRef {
+	/// ref name relative to the `refs` folder
+	pub name: String,
+	/// commit hash the ref points to
+	pub commit_hash: String,
+}
+
+/// a cached revision as reached through one of its refs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevisionRef {
+	/// ref name, `None` for a detached snapshot no ref points to
+	pub name: Option<String>,
+	/// commit hash of the snapshot
+	pub commit_hash: String,
+}
+
+/// one `snapshots/<commit>` folder
+#[derive(
+	Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct CachedRevision {
+	/// full commit hash, also the snapshot folder name
+	pub commit_hash: String,
+	/// absolute path of the snapshot folder
+	pub path: PathBuf,
+	/// names of all refs pointing at this commit
+	pub refs: Vec<String>,
+	/// files sorted by path
+	pub files: Vec<CachedFile>,
+	/// unix time of the last modification of the snapshot folder
+	pub last_modified: i64,
+	/// disk usage compared to the other revisions of the repo
+	pub sizes: SizeInfo,
+}
+
+/// one repository folder of the cache
+#[derive(
+	Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct CachedRepo {
+	/// repo id as used on the hub (`org/name`)
+	pub repo_id: String,
+	/// model, dataset or space, from the folder prefix
+	pub repo_type: RepoType,
+	/// index of the cache root holding the repo folder
+	pub root: usize,
+	/// absolute path of the repo folder
+	pub path: PathBuf,
+	/// revisions sorted by commit hash
+	pub revisions: Vec<CachedRevision>,
+	/// refs sorted by name
+	pub refs: Vec<CachedRef>,
+	/// disk usage of all revisions together
+	pub sizes: SizeInfo,
+	/// integrity problems, empty for a healthy repo
+	pub problems: Vec<Problem>,
+	/// unix time the repo was last seen in use, `None` if unknown
+	pub last_accessed: Option<i64>,
+	/// folder stamps taken before scanning, see `index`
+	pub stamps: Vec<DirStamp>,
+}
+
+impl CachedRepo {
+	/// revision with the given commit hash
+	pub fn revision(&self, commit_hash: &str) -> Option<&CachedRevision> {
+		self.revisions.iter().find(|r| r.commit_hash == commit_hash)
+	}
+
+	/// revision `main` points to or the most recently modified one
+	pub fn default_revision(&self) -> Option<&CachedRevision> {
+		self.refs
+			.iter()
+			.find(|r| r.name == "main")
+			.and_then(|r| self.revision(&r.commit_hash))
+			.or_else(|| {
+				self.revisions.iter().max_by_key(|r| r.last_modified)
+			})
+	}
+
+	/// every ref with a cached snapshot sorted by name,
+	/// followed by the detached snapshots
+	pub fn revision_refs(&self) -> Vec<RevisionRef> {
+		let refs = self
+			.refs
+			.iter()
+			.filter(|r| self.revision(&r.commit_hash).is_some())
+			.map(|r| RevisionRef {
+				name: Some(r.name.clone()),
+				commit_hash: r.commit_hash.clone(),
+			});
+		let detached = self
+			.revisions
+			.iter()
+			.filter(|rev| rev.refs.is_empty())
+			.map(|rev| RevisionRef {
+				name: None,
+				commit_hash: rev.commit_hash.clone(),
+			});
+
+		refs.chain(detached).collect()
+	}
+
+	/// order of repos in `HubCache::repos`
+	fn sort_key(&self) -> (&str, RepoType, usize) {
+		(&self.repo_id, self.repo_type, self.root)
+	}
+
+	/// identifies the same repo across roots
+	fn type_key(&self) -> (&str, RepoType) {
+		(&self.repo_id, self.repo_type)
+	}
+
+	/// number of snapshot files stored as copies instead of links to a blob
+	pub fn copied_files(&self) -> usize {
+		self.copies().count()
+	}
+
+	/// bytes held by copied snapshot files
+	pub fn copied_size(&self) -> u64 {
+		self.copies().map(|f| f.size).sum()
+	}
+
+	fn copies(&self) -> impl Iterator<Item = &CachedFile> {
+		self.revisions
+			.iter()
+			.flat_map(|r| r.files.iter())
+			.filter(|f| f.symlink_target.is_none())
+	}
+
+	/// total number of files over all revisions
+	pub fn file_count(&self) -> usize {
+		self.revisions.iter().map(|r| r.files.len()).sum()
+	}
+}
+
+/// result of scanning all cache roots
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HubCache {
+	/// scanned cache roots
+	pub roots: Vec<CacheRoot>,
+	/// repos sorted by repo id, type and root
+	pub repos: Vec<CachedRepo>,
+}
+
+impl HubCache {
+	/// empty cache of `roots`, filled by `merge`
+	pub const fn new(roots: Vec<CacheRoot>) -> Self {
+		Self {
+			roots,
+			repos: Vec::new(),
+		}
+	}
+
+	/// adds `repos`, replacing already known ones with the same id, type and root
+	pub fn merge(&mut self, repos: Vec<CachedRepo>) {
+		for repo in repos {
+			match self
+				.repos
+				.binary_search_by(|r| r.sort_key().cmp(&repo.sort_key()))
+			{
+				Ok(idx) => self.repos[idx] = repo,
+				Err(idx) => self.repos.insert(idx, repo),
+			}
+		}
+	}
+
+	/// cache root holding `repo`
+	pub fn root_of(&self, repo: &CachedRepo) -> Option<&CacheRoot> {
+		self.roots.get(repo.root)
+	}
+
+	/// the other repos with the same id and type as `repos[idx]`, found in other roots
+	pub fn duplicates(
+		&self,
+		idx: usize,
+	) -> impl Iterator<Item = &CachedRepo> {
+		let key = self.repos.get(idx).map(CachedRepo::type_key);
+		let start = self
+			.repos
+			.partition_point(|r| Some(r.type_key()) < key);
+
+		self.repos[start..]
+			.iter()
+			.enumerate()
+			.take_while(move |(_, r)| Some(r.type_key()) == key)
+			.filter(move |(offset, _)| start + offset != idx)
+			.map(|(_, r)| r)
+	}
+
+	/// number of roots caching revision `rev` of `repos[idx]`
+	pub fn revision_copies(&self, idx: usize, rev: usize) -> usize {
+		let Some(rev) =
+			self.repos.get(idx).and_then(|r| r.revisions.get(rev))
+		else {
+			return 0;
+		};
+
+		1 + self
+			.duplicates(idx)
+			.filter(|repo| repo.revision(&rev.commit_hash).is_some())
+			.count()
+	}
+
+	/// drops the repo stored in `folder`
+	pub fn remove(&mut self, folder: &Path) {
+		self.repos.retain(|r| r.path != folder);
+	}
+}
+
+/// repo folder below `root` containing `path`, `None` for paths outside of repos
+pub fn repo_folder_of(root: &Path, path: &Path) -> Option<PathBuf> {
+	let folder = path.strip_prefix(root).ok()?.components().next()?;
+	let folder = folder.as_os_str().to_str()?;
+
+	repo_id_from_folder(folder)?;
+
+	Some(root.join(folder))
+}
+
+/// `models--org--name` to `org/name` and its type, `None` for non repo folders
+pub fn repo_id_from_folder(folder: &str) -> Option<(RepoType, String)> {
+	let (repo_type, name) = RepoType::ALL.into_iter().find_map(|t| {
+		folder.strip_prefix(t.folder_prefix()).map(|name| (t, name))
+	})?;
+
+	if name.is_empty() {
+		return None;
+	}
+
+	Some((
+		repo_type,
+		name.split(REPO_ID_SEPARATOR).collect::<Vec<_>>().join("/"),
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_merge() {
+		let repo = |id: &str, path: &str| CachedRepo {
+			repo_id: id.to_string(),
+			path: PathBuf::from(path),
+			..Default::default()
+		};
+
+		let mut cache = HubCache::default();
+		cache.merge(vec![repo("b/b", "old"), repo("c/c", "")]);
+		cache.merge(vec![repo("a/a", ""), repo("b/b", "new")]);
+
+		let ids: Vec<_> =
+			cache.repos.iter().map(|r| r.repo_id.as_str()).collect();
+		assert_eq!(ids, vec!["a/a", "b/b", "c/c"]);
+		assert_eq!(cache.repos[1].path, PathBuf::from("new"));
+	}
+
+	#[test]
+	fn test_duplicates() {
+		let repo = |id: &str, root: usize, commit: &str| CachedRepo {
+			repo_id: id.to_string(),
+			root,
+			revisions: vec![CachedRevision {
+				commit_hash: commit.to_string(),
+				..Default::default()
+			}],
+			..Default::default()
+		};
+
+		let mut cache = HubCache::default();
+		cache.merge(vec![
+			repo("b/b", 1, "c1"),
+			repo("a/a", 0, "c0"),
+			repo("b/b", 0, "c1"),
+			repo("c/c", 1, "c2"),
+			repo("b/b", 2, "c3"),
+		]);
+
+		let found: Vec<_> = cache
+			.repos
+			.iter()
+			.map(|r| (r.repo_id.as_str(), r.root))
+			.collect();
+		assert_eq!(
+			found,
+			vec![
+				("a/a", 0),
+				("b/b", 0),
+				("b/b", 1),
+				("b/b", 2),
+				("c/c", 1)
+			]
+		);
+
+		let roots = |idx| {
+			cache.duplicates(idx).map(|r| r.root).collect::<Vec<_>>()
+		};
+		assert_eq!(roots(0), Vec::<usize>::new());
+		assert_eq!(roots(2), vec![0, 2]);
+		assert_eq!(roots(4), Vec::<usize>::new());
+
+		assert_eq!(cache.revision_copies(1, 0), 2);
+		assert_eq!(cache.revision_copies(3, 0), 1);
+		assert_eq!(cache.revision_copies(0, 0), 1);
+	}
+
+	#[test]
+	fn test_file_kind() {
+		let file = |blob: Option<&str>, link: bool| CachedFile {
+			path: PathBuf::from("model.bin"),
+			blob_hash: blob.map(String::from),
+			size: 0,
+			symlink_target: link.then(|| PathBuf::from("../../blobs/x")),
+		};
+
+		assert_eq!(file(Some(&"a".repeat(64)), true).kind(), FileKind::Lfs);
+		assert_eq!(file(Some(&"a".repeat(40)), true).kind(), FileKind::Git);
+		assert_eq!(file(Some("model.bin"), true).kind(), FileKind::Symlink);
+		assert_eq!(file(None, false).kind(), FileKind::Copy);
+	}
+
+	#[test]
+	fn test_revision_refs() {
+		let revision = |commit: &str, refs: &[&str]| CachedRevision {
+			commit_hash: commit.to_string(),
+			refs: refs.iter().map(ToString::to_string).collect(),
+			..Default::default()
+		};
+		let cached_ref = |name: &str, commit: &str| CachedRef {
+			name: name.to_string(),
+			commit_hash: commit.to_string(),
+		};
+		let repo = CachedRepo {
+			repo_id: "org/model".to_string(),
+			revisions: vec![
+				revision("c1", &["main", "v1.0"]),
+				revision("c2", &[]),
+				revision("c3", &["pr/3"]),
+			],
+			refs: vec![
+				cached_ref("gone", "c9"),
+				cached_ref("main", "c1"),
+				cached_ref("pr/3", "c3"),
+				cached_ref("v1.0", "c1"),
+			],
+			..Default::default()
+		};
+
+		let found: Vec<_> = repo
+			.revision_refs()
+			.into_iter()
+			.map(|r| (r.name, r.commit_hash))
+			.collect();
+		let expected = [
+			(Some("main"), "c1"),
+			(Some("pr/3"), "c3"),
+			(Some("v1.0"), "c1"),
+			(None, "c2"),
+		];
+
+		assert_eq!(
+			found,
+			expected
+				.iter()
+				.map(|(name, commit)| (
+					name.map(String::from),
+					commit.to_string()
+				))
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn test_repo_folder_of() {
+		let root = Path::new("/cache");
+
+		assert_eq!(
+			repo_folder_of(
+				root,
+				Path::new("/cache/models--org--name/refs/main")
+			),
+			Some(PathBuf::from("/cache/models--org--name"))
+		);
+		assert_eq!(
+			repo_folder_of(root, Path::new("/cache/models--a")),
+			Some(PathBuf::from("/cache/models--a"))
+		);
+		assert_eq!(
+			repo_folder_of(root, Path::new("/cache/.locks/x.lock")),
+			None
+		);
+		assert_eq!(repo_folder_of(root, Path::new("/cache")), None);
+		assert_eq!(repo_folder_of(root, Path::new("/other")), None);
+	}
+
+	#[test]
+	fn test_repo_id_from_folder() {
+		assert_eq!(
+			repo_id_from_folder("models--openai--whisper-tiny"),
+			Some((RepoType::Model, String::from("openai/whisper-tiny")))
+		);
+		assert_eq!(
+			repo_id_from_folder("models--gpt2"),
+			Some((RepoType::Model, String::from("gpt2")))
+		);
+		assert_eq!(
+			repo_id_from_folder("datasets--org--data"),
+			Some((RepoType::Dataset, String::from("org/data")))
+		);
+		assert_eq!(
+			repo_id_from_folder("spaces--org--demo"),
+			Some((RepoType::Space, String::from("org/demo")))
+		);
+		assert_eq!(repo_id_from_folder("models--"), None);
+		assert_eq!(repo_id_from_folder(".locks"), None);
+	}
+}
@@ -0,0 +1,337 @@
+use super::{
+	access::atime_supported, index::repo_stamps,
+	integrity::check_repo, repo_id_from_folder, sizes::update_sizes,
+	CachedFile, CachedRef, CachedRepo, CachedRevision, DirStamp,
+	SizeInfo,
+};
+use anyhow::{Context, Result};
+use std::{
+	collections::HashMap,
+	fs,
+	path::Path,
+	time::UNIX_EPOCH,
+};
+
+const REFS_FOLDER: &str = "refs";
+const SNAPSHOTS_FOLDER: &str = "snapshots";
+
+/// scans all repos found directly below `root`, the cache root with index `root_idx`,
+/// repos of `known` whose folders did not change are reused as they are,
+/// `on_repo` sees every scanned repo together with the repos done so far and the total
+pub fn scan_cache(
+	root: &Path,
+	root_idx: usize,
+	known: &[CachedRepo],
+	mut on_repo: impl FnMut(&CachedRepo, usize, usize),
+) -> Result<Vec<CachedRepo>> {
+	let known: HashMap<&Path, &CachedRepo> =
+		known.iter().map(|r| (r.path.as_path(), r)).collect();
+
+	let entries = fs::read_dir(root).with_context(|| {
+		format!("failed to read cache dir {}", root.display())
+	})?;
+
+	let mut folders = Vec::new();
+	for entry in entries {
+		let entry = entry?;
+		let name = entry.file_name();
+
+		if repo_id_from_folder(&name.to_string_lossy()).is_some()
+			&& entry.file_type()?.is_dir()
+		{
+			folders.push(entry.path());
+		}
+	}
+	folders.sort();
+
+	let mut repos = Vec::with_capacity(folders.len());
+	for (idx, folder) in folders.iter().enumerate() {
+		let stamps = repo_stamps(folder);
+		let scanned = match known.get(folder.as_path()) {
+			// reading files changes no stamps, access times are refreshed
+			Some(repo) if repo.stamps == stamps => Ok(CachedRepo {
+				root: root_idx,
+				last_accessed: last_accessed(folder, &repo.revisions),
+				..(*repo).clone()
+			}),
+			_ => scan_stamped_repo(folder, root_idx, stamps),
+		};
+
+		match scanned {
+			Ok(repo) => {
+				on_repo(&repo, idx + 1, folders.len());
+				repos.push(repo);
+			}
+			Err(e) => {
+				log::error!("scan_repo {}: {e}", folder.display());
+			}
+		}
+	}
+
+	repos.sort_by(|a, b| a.repo_id.cmp(&b.repo_id));
+
+	Ok(repos)
+}
+
+/// scans a single `<type>s--org--name` folder of the cache root with index `root`
+pub fn scan_repo(path: &Path, root: usize) -> Result<CachedRepo> {
+	scan_stamped_repo(path, root, repo_stamps(path))
+}
+
+fn scan_stamped_repo(
+	path: &Path,
+	root: usize,
+	stamps: Vec<DirStamp>,
+) -> Result<CachedRepo> {
+	let folder = path
+		.file_name()
+		.map(|n| n.to_string_lossy().to_string())
+		.unwrap_or_default();
+	let (repo_type, repo_id) = repo_id_from_folder(&folder)
+		.with_context(|| {
+			format!("not a repo folder: {}", path.display())
+		})?;
+
+	let mut refs = Vec::new();
+	let refs_path = path.join(REFS_FOLDER);
+	if refs_path.is_dir() {
+		read_refs(&refs_path, &refs_path, &mut refs)?;
+	}
+	refs.sort_by(|a, b| a.name.cmp(&b.name));
+
+	let mut revisions = Vec::new();
+	let snapshots_path = path.join(SNAPSHOTS_FOLDER);
+	if snapshots_path.is_dir() {
+		for entry in fs::read_dir(&snapshots_path)? {
+			let entry = entry?;
+			if !entry.file_type()?.is_dir() {
+				continue;
+			}
+
+			let commit_hash =
+				entry.file_name().to_string_lossy().to_string();
+			let snapshot_path = entry.path();
+
+			let mut files = Vec::new();
+			read_snapshot_files(
+				&snapshot_path,
+				Path::new(""),
+				&mut files,
+			)?;
+			files.sort_by(|a, b| a.path.cmp(&b.path));
+
+			revisions.push(CachedRevision {
+				refs: refs
+					.iter()
+					.filter(|r| r.commit_hash == commit_hash)
+					.map(|r| r.name.clone())
+					.collect(),
+				last_modified: modified_secs(&snapshot_path),
+				commit_hash,
+				path: snapshot_path,
+				files,
+				sizes: SizeInfo::default(),
+			});
+		}
+	}
+	revisions.sort_by(|a, b| a.commit_hash.cmp(&b.commit_hash));
+
+	let mut repo = CachedRepo {
+		repo_id,
+		repo_type,
+		root,
+		path: path.to_path_buf(),
+		last_accessed: last_accessed(path, &revisions),
+		revisions,
+		refs,
+		sizes: SizeInfo::default(),
+		problems: Vec::new(),
+		stamps,
+	};
+	update_sizes(&mut repo);
+	repo.problems = check_repo(&repo).unwrap_or_else(|e| {
+		log::error!("check_repo {}: {e}", repo.path.display());
+		Vec::new()
+	});
+
+	Ok(repo)
+}
+
+fn read_refs(
+	base: &Path,
+	dir: &Path,
+	out: &mut Vec<CachedRef>,
+) -> Result<()> {
+	for entry in fs::read_dir(dir)? {
+		let entry = entry?;
+		let path = entry.path();
+
+		if entry.file_type()?.is_dir() {
+			read_refs(base, &path, out)?;
+			continue;
+		}
+
+		let name = path
+			.strip_prefix(base)?
+			.components()
+			.map(|c| c.as_os_str().to_string_lossy())
+			.collect::<Vec<_>>()
+			.join("/");
+
+		out.push(CachedRef {
+			name,
+			commit_hash: fs::read_to_string(&path)?.trim().to_string(),
+		});
+	}
+
+	Ok(())
+}
+
+fn read_snapshot_files(
+	snapshot: &Path,
+	relative: &Path,
+	out: &mut Vec<CachedFile>,
+) -> Result<()> {
+	for entry in fs::read_dir(snapshot.join(relative))? {
+		let entry = entry?;
+		let path = entry.path();
+		let relative = relative.join(entry.file_name());
+		let file_type = entry.file_type()?;
+
+		if file_type.is_dir() {
+			read_snapshot_files(snapshot, &relative, out)?;
+		} else if file_type.is_symlink() {
+			let target = fs::read_link(&path)?;
+
+			out.push(CachedFile {
+				path: relative,
+				blob_hash: target
+					.file_name()
+					.map(|n| n.to_string_lossy().to_string()),
+				// follows the link, dangling links count as empty
+				size: fs::metadata(&path).map_or(0, |m| m.len()),
+				symlink_target: Some(target),
+			});
+		} else {
+			out.push(CachedFile {
+				path: relative,
+				blob_hash: None,
+				size: entry.metadata()?.len(),
+				symlink_target: None,
+			});
+		}
+	}
+
+	Ok(())
+}
+
+/// newest access time of the files of `revisions` of the repo at `path`,
+/// links are followed to the blobs they point to.
+/// `None` on mounts without access times, see `access`
+fn last_accessed(
+	path: &Path,
+	revisions: &[CachedRevision],
+) -> Option<i64> {
+	if !atime_supported(path) {
+		return None;
+	}
+
+	revisions
+		.iter()
+		.flat_map(|rev| rev.files.iter().map(|f| rev.path.join(&f.path)))
+		.filter_map(|path| {
+			let accessed = fs::metadata(path).and_then(|m| m.accessed());
+			let secs = accessed.ok()?.duration_since(UNIX_EPOCH).ok()?;
+			i64::try_from(secs.as_secs()).ok()
+		})
+		.max()
+}
+
+/// unix time of the last modification of `path`, 0 if unknown
+pub fn modified_secs(path: &Path) -> i64 {
+	fs::metadata(path)
+		.and_then(|m| m.modified())
+		.ok()
+		.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+		.and_then(|d| i64::try_from(d.as_secs()).ok())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use std::os::unix::fs::symlink;
+	use tempfile::TempDir;
+
+	/// writes `content` as blob `hash` and links it into `snapshot`
+	pub fn add_file(
+		repo: &Path,
+		commit: &str,
+		file: &str,
+		hash: &str,
+		content: &str,
+	) {
+		let blobs = repo.join("blobs");
+		fs::create_dir_all(&blobs).unwrap();
+		fs::write(blobs.join(hash), content).unwrap();
+
+		let link = repo.join(SNAPSHOTS_FOLDER).join(commit).join(file);
+		fs::create_dir_all(link.parent().unwrap()).unwrap();
+
+		let depth = Path::new(file).components().count();
+		let target = format!("{}blobs/{hash}", "../".repeat(depth + 1));
+		symlink(target, link).unwrap();
+	}
+
+	/// writes `content` straight into `snapshot` like the hub does without symlinks
+	pub fn add_copy(repo: &Path, commit: &str, file: &str, content: &str) {
+		let path = repo.join(SNAPSHOTS_FOLDER).join(commit).join(file);
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(path, content).unwrap();
+	}
+
+	pub fn add_ref(repo: &Path, name: &str, commit: &str) {
+		let path = repo.join(REFS_FOLDER).join(name);
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(path, commit).unwrap();
+	}
+
+	#[test]
+	fn test_scan_repo() {
+		let root = TempDir::new().unwrap();
+		let repo = root.path().join("models--org--model");
+
+		add_file(&repo, "aaaa", "config.json", "b1", "{}");
+		add_file(&repo, "aaaa", "onnx/model.onnx", "b2", "12345");
+		add_file(&repo, "bbbb", "config.json", "b3", "{ }");
+		add_ref(&repo, "main", "bbbb");
+		add_ref(&repo, "refs/pr/1", "aaaa");
+		fs::create_dir_all(root.path().join(".locks")).unwrap();
+
+		let repos =
+			scan_cache(root.path(), 1, &[], |_, _, _| ()).unwrap();
+		assert_eq!(repos.len(), 1);
+
+		let repo = &repos[0];
+		assert_eq!(repo.repo_id, "org/model");
+		assert_eq!(repo.root, 1);
+		assert_eq!(repo.refs.len(), 2);
+		assert_eq!(repo.refs[1].name, "refs/pr/1");
+		assert_eq!(repo.revisions.len(), 2);
+		assert_eq!(repo.file_count(), 3);
+		assert_eq!(
+			repo.default_revision().unwrap().commit_hash,
+			"bbbb"
+		);
+
+		let rev = repo.revision("aaaa").unwrap();
+		assert_eq!(rev.refs, vec![String::from("refs/pr/1")]);
+
+		let file = &rev.files[1];
+		assert_eq!(file.path, Path::new("onnx/model.onnx"));
+		assert_eq!(file.blob_hash.as_deref(), Some("b2"));
+		assert_eq!(file.size, 5);
+		assert!(file.symlink_target.is_some());
+	}
+}
@@ -0,0 +1,305 @@
+use super::{CachedFile, CachedRepo};
+use serde::{Deserialize, Serialize};
+use std::{
+	cmp::Reverse,
+	collections::{BTreeMap, HashMap},
+	path::Path,
+};
+
+/// disk usage of a revision or a whole repo
+#[derive(
+	Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct SizeInfo {
+	/// sum of all file sizes as seen through the snapshot links
+	pub apparent: u64,
+	/// size of the distinct blobs referenced
+	pub unique: u64,
+	/// part of `unique` also referenced by other revisions
+	pub shared: u64,
+	/// space freed by deleting it
+	pub reclaimable: u64,
+}
+
+/// disk usage of one entry of a snapshot folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderEntry {
+	/// file or folder name
+	pub name: String,
+	pub is_folder: bool,
+	/// size of the distinct blobs referenced below the entry
+	pub size: u64,
+	/// part of `size` referenced nowhere else in the repo
+	pub frees: u64,
+}
+
+/// identifies the on-disk data of a file,
+/// files without blob (no symlinks) own their data
+#[derive(Hash, PartialEq, Eq)]
+enum BlobKey<'a> {
+	Blob(&'a str),
+	Own(usize, &'a Path),
+}
+
+impl<'a> BlobKey<'a> {
+	/// key of `file` of the revision with index `rev_idx`
+	fn of(rev_idx: usize, file: &'a CachedFile) -> Self {
+		file.blob_hash
+			.as_deref()
+			.map_or(Self::Own(rev_idx, &file.path), Self::Blob)
+	}
+}
+
+/// reference count and size of every blob
+type BlobRefs<'a> = HashMap<BlobKey<'a>, (usize, u64)>;
+
+/// fills the `sizes` of `repo` and all its revisions
+/// based on how many revisions reference each blob
+pub fn update_sizes(repo: &mut CachedRepo) {
+	let keys: Vec<HashMap<BlobKey, u64>> = repo
+		.revisions
+		.iter()
+		.enumerate()
+		.map(|(rev_idx, rev)| {
+			rev.files
+				.iter()
+				.map(|file| (BlobKey::of(rev_idx, file), file.size))
+				.collect()
+		})
+		.collect();
+
+	let mut refcounts: HashMap<&BlobKey, (usize, u64)> = HashMap::new();
+	for blobs in &keys {
+		for (key, size) in blobs {
+			refcounts.entry(key).or_insert((0, *size)).0 += 1;
+		}
+	}
+
+	let rev_sizes: Vec<SizeInfo> = repo
+		.revisions
+		.iter()
+		.zip(&keys)
+		.map(|(rev, blobs)| {
+			let unique = blobs.values().sum();
+			let shared = blobs
+				.keys()
+				.filter(|key| refcounts[key].0 > 1)
+				.map(|key| blobs[key])
+				.sum();
+
+			SizeInfo {
+				apparent: rev.files.iter().map(|f| f.size).sum(),
+				unique,
+				shared,
+				reclaimable: unique - shared,
+			}
+		})
+		.collect();
+
+	let unique = refcounts.values().map(|(_, size)| size).sum();
+	let repo_sizes = SizeInfo {
+		apparent: rev_sizes.iter().map(|s| s.apparent).sum(),
+		unique,
+		shared: refcounts
+			.values()
+			.filter(|(count, _)| *count > 1)
+			.map(|(_, size)| size)
+			.sum(),
+		reclaimable: unique,
+	};
+
+	for (rev, sizes) in repo.revisions.iter_mut().zip(rev_sizes) {
+		rev.sizes = sizes;
+	}
+	repo.sizes = repo_sizes;
+}
+
+/// usage of the entries directly inside `folder`, relative to the
+/// snapshot, of the revision with index `rev` of `repo`, largest first
+pub fn folder_usage(
+	repo: &CachedRepo,
+	rev: usize,
+	folder: &Path,
+) -> Vec<FolderEntry> {
+	let Some(revision) = repo.revisions.get(rev) else {
+		return Vec::new();
+	};
+
+	let mut refcounts: HashMap<BlobKey, usize> = HashMap::new();
+	for (rev_idx, revision) in repo.revisions.iter().enumerate() {
+		for file in &revision.files {
+			*refcounts
+				.entry(BlobKey::of(rev_idx, file))
+				.or_default() += 1;
+		}
+	}
+
+	// folder flag, references and size of every blob by entry name
+	let mut entries: BTreeMap<String, (bool, BlobRefs)> = BTreeMap::new();
+	for file in &revision.files {
+		let Ok(rest) = file.path.strip_prefix(folder) else {
+			continue;
+		};
+		let mut components = rest.components();
+		let Some(name) = components.next() else {
+			continue;
+		};
+
+		let (_, blobs) = entries
+			.entry(name.as_os_str().to_string_lossy().to_string())
+			.or_insert_with(|| {
+				(components.next().is_some(), HashMap::new())
+			});
+		blobs
+			.entry(BlobKey::of(rev, file))
+			.or_insert((0, file.size))
+			.0 += 1;
+	}
+
+	let mut usage: Vec<FolderEntry> = entries
+		.into_iter()
+		.map(|(name, (is_folder, blobs))| FolderEntry {
+			name,
+			is_folder,
+			size: blobs.values().map(|(_, size)| size).sum(),
+			frees: blobs
+				.iter()
+				.filter(|(key, (count, _))| refcounts[*key] == *count)
+				.map(|(_, (_, size))| size)
+				.sum(),
+		})
+		.collect();
+	usage.sort_by_key(|e| Reverse(e.size));
+
+	usage
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::scan::{
+		scan_repo,
+		tests::{add_copy, add_file},
+	};
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_shared_blobs() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		// `aaaa` and `bbbb` share the weights, `bbbb` links them twice
+		add_file(&path, "aaaa", "model.bin", "w", "0123456789");
+		add_file(&path, "aaaa", "config.json", "c1", "{}");
+		add_file(&path, "bbbb", "model.bin", "w", "0123456789");
+		add_file(&path, "bbbb", "copy/model.bin", "w", "0123456789");
+		add_file(&path, "bbbb", "config.json", "c2", "{ }");
+
+		let repo = scan_repo(&path, 0).unwrap();
+
+		assert_eq!(
+			repo.revision("aaaa").unwrap().sizes,
+			SizeInfo {
+				apparent: 12,
+				unique: 12,
+				shared: 10,
+				reclaimable: 2,
+			}
+		);
+		assert_eq!(
+			repo.revision("bbbb").unwrap().sizes,
+			SizeInfo {
+				apparent: 23,
+				unique: 13,
+				shared: 10,
+				reclaimable: 3,
+			}
+		);
+		assert_eq!(
+			repo.sizes,
+			SizeInfo {
+				apparent: 35,
+				unique: 15,
+				shared: 10,
+				reclaimable: 15,
+			}
+		);
+	}
+
+	#[test]
+	fn test_folder_usage() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		add_file(&path, "aaaa", "model.bin", "w", "0123456789");
+		add_file(&path, "bbbb", "model.bin", "w", "0123456789");
+		add_file(&path, "bbbb", "sub/model.bin", "w", "0123456789");
+		add_file(&path, "bbbb", "sub/extra.bin", "e", "01234");
+		add_file(&path, "bbbb", "config.json", "c", "{}");
+
+		let repo = scan_repo(&path, 0).unwrap();
+		let entry = |name: &str, is_folder, size, frees| FolderEntry {
+			name: name.to_string(),
+			is_folder,
+			size,
+			frees,
+		};
+
+		// the weights are also linked by `aaaa`, nothing of them is freed
+		assert_eq!(
+			folder_usage(&repo, 1, Path::new("")),
+			vec![
+				entry("sub", true, 15, 5),
+				entry("model.bin", false, 10, 0),
+				entry("config.json", false, 2, 2),
+			]
+		);
+		assert_eq!(
+			folder_usage(&repo, 1, Path::new("sub")),
+			vec![
+				entry("model.bin", false, 10, 0),
+				entry("extra.bin", false, 5, 5),
+			]
+		);
+		assert_eq!(
+			folder_usage(&repo, 0, Path::new("")),
+			vec![entry("model.bin", false, 10, 0)]
+		);
+		assert_eq!(folder_usage(&repo, 2, Path::new("")), Vec::new());
+	}
+
+	#[test]
+	fn test_copied_files() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		// without symlinks every revision holds its own copy
+		add_copy(&path, "aaaa", "model.bin", "0123456789");
+		add_copy(&path, "bbbb", "model.bin", "0123456789");
+		add_file(&path, "bbbb", "config.json", "c1", "{}");
+
+		let repo = scan_repo(&path, 0).unwrap();
+
+		assert_eq!(repo.copied_files(), 2);
+		assert_eq!(repo.copied_size(), 20);
+		assert_eq!(
+			repo.revision("bbbb").unwrap().sizes,
+			SizeInfo {
+				apparent: 12,
+				unique: 12,
+				shared: 0,
+				reclaimable: 12,
+			}
+		);
+		assert_eq!(
+			repo.sizes,
+			SizeInfo {
+				apparent: 22,
+				unique: 22,
+				shared: 0,
+				reclaimable: 22,
+			}
+		);
+	}
+}
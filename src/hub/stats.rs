@@ -0,0 +1,160 @@
+use super::{CachedRepo, HubCache, RepoType};
+use std::{
+	ffi::CString,
+	mem::MaybeUninit,
+	os::unix::ffi::OsStrExt,
+	path::Path,
+};
+
+/// number of repos listed as largest
+pub const LARGEST_REPOS: usize = 10;
+/// number of repos listed as recently added
+const RECENT_REPOS: usize = 5;
+
+/// figures of the whole cache shown on the dashboard
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+	/// sum of all file sizes as seen through the snapshots
+	pub apparent: u64,
+	/// size of all distinct blobs on disk
+	pub unique: u64,
+	/// repo count of every type, in the order of `RepoType::ALL`
+	pub repo_counts: [usize; 3],
+	/// indices of the largest repos by unique size, largest first
+	pub largest: Vec<usize>,
+	/// indices of the repos with the newest revisions, newest first
+	pub recent: Vec<usize>,
+	/// problems found by the integrity checks
+	pub problems: usize,
+}
+
+impl CacheStats {
+	/// collects the figures of `cache`, without touching the disk
+	pub fn new(cache: &HubCache) -> Self {
+		let repos = &cache.repos;
+
+		let mut repo_counts = [0; 3];
+		for repo in repos {
+			if let Some(idx) =
+				RepoType::ALL.iter().position(|t| *t == repo.repo_type)
+			{
+				repo_counts[idx] += 1;
+			}
+		}
+
+		let mut largest: Vec<usize> = (0..repos.len()).collect();
+		largest.sort_by_key(|idx| {
+			std::cmp::Reverse(repos[*idx].sizes.unique)
+		});
+		largest.truncate(LARGEST_REPOS);
+
+		let mut recent: Vec<usize> = (0..repos.len())
+			.filter(|idx| newest_revision(&repos[*idx]).is_some())
+			.collect();
+		recent.sort_by_key(|idx| {
+			std::cmp::Reverse(newest_revision(&repos[*idx]))
+		});
+		recent.truncate(RECENT_REPOS);
+
+		Self {
+			apparent: repos.iter().map(|r| r.sizes.apparent).sum(),
+			unique: repos.iter().map(|r| r.sizes.unique).sum(),
+			repo_counts,
+			largest,
+			recent,
+			problems: repos.iter().map(|r| r.problems.len()).sum(),
+		}
+	}
+
+	/// number of repos of `repo_type`
+	pub fn repo_count(&self, repo_type: RepoType) -> usize {
+		RepoType::ALL
+			.iter()
+			.position(|t| *t == repo_type)
+			.map_or(0, |idx| self.repo_counts[idx])
+	}
+}
+
+/// unix time the newest revision of `repo` was written
+pub fn newest_revision(repo: &CachedRepo) -> Option<i64> {
+	repo.revisions.iter().map(|r| r.last_modified).max()
+}
+
+/// space available to unprivileged users on the file system of `path`
+pub fn free_space(path: &Path) -> Option<u64> {
+	let stat = statvfs(path)?;
+
+	#[allow(clippy::unnecessary_cast)]
+	Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// information about the file system of `path`
+pub(super) fn statvfs(path: &Path) -> Option<libc::statvfs> {
+	let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+	let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+
+	// SAFETY: `path` is nul terminated and `stat` is only read
+	// after `statvfs` reported to have filled it
+	unsafe {
+		if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+			return None;
+		}
+		Some(stat.assume_init())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::scan::{scan_cache, tests::add_file};
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_cache_stats() {
+		let root = TempDir::new().unwrap();
+
+		add_file(
+			&root.path().join("models--org--small"),
+			"aaaa",
+			"config.json",
+			"c1",
+			"{}",
+		);
+		add_file(
+			&root.path().join("models--org--large"),
+			"bbbb",
+			"model.bin",
+			"b1",
+			"0123456789",
+		);
+		add_file(
+			&root.path().join("datasets--org--data"),
+			"cccc",
+			"data.csv",
+			"d1",
+			"a,b",
+		);
+
+		let mut cache = HubCache::default();
+		cache.merge(scan_cache(root.path(), 0, &[], |_, _, _| ()).unwrap());
+		let stats = CacheStats::new(&cache);
+
+		let ids = |idx: &[usize]| -> Vec<String> {
+			idx.iter().map(|i| cache.repos[*i].repo_id.clone()).collect()
+		};
+
+		assert_eq!(stats.repo_count(RepoType::Model), 2);
+		assert_eq!(stats.repo_count(RepoType::Dataset), 1);
+		assert_eq!(stats.repo_count(RepoType::Space), 0);
+		assert_eq!(stats.unique, 15);
+		assert_eq!(
+			ids(&stats.largest),
+			vec!["org/large", "org/data", "org/small"]
+		);
+		assert_eq!(stats.recent.len(), 3);
+
+		assert!(free_space(root.path()).is_some());
+		assert_eq!(free_space(&root.path().join("missing")), None);
+	}
+}
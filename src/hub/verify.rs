@@ -0,0 +1,357 @@
+use super::{
+	access::open_untouched, AsyncHubNotification, CachedRepo,
+	BLOBS_FOLDER,
+};
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	progress::ProgressPercent,
+};
+use anyhow::{anyhow, Result};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::{
+	collections::BTreeMap,
+	io::Read,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// hash a blob name was derived from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashKind {
+	/// LFS files, named by the sha256 of their content
+	Sha256,
+	/// regular files, named by their git blob sha1
+	GitSha1,
+}
+
+impl HashKind {
+	/// guesses the hash from the length of a blob name
+	pub fn from_blob_name(name: &str) -> Option<Self> {
+		if !name.bytes().all(|b| b.is_ascii_hexdigit()) {
+			return None;
+		}
+
+		match name.len() {
+			64 => Some(Self::Sha256),
+			40 => Some(Self::GitSha1),
+			_ => None,
+		}
+	}
+}
+
+/// a blob to verify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyItem {
+	/// absolute path of the blob
+	pub path: PathBuf,
+	/// blob name, the expected hash
+	pub expected: String,
+	pub kind: HashKind,
+	pub size: u64,
+}
+
+/// a blob whose content does not match its name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+	pub path: PathBuf,
+	pub expected: String,
+	pub actual: String,
+}
+
+/// outcome of verifying a set of blobs
+#[derive(Debug, Clone, Default)]
+pub struct VerifyResult {
+	/// blobs whose content matched
+	pub ok: usize,
+	pub mismatches: Vec<Mismatch>,
+	/// blobs that could not be read
+	pub errors: Vec<(PathBuf, String)>,
+	/// files skipped because their name is no known hash
+	pub skipped: usize,
+}
+
+/// distinct blobs of `repo` to verify,
+/// limited to one revision and optionally one file in it
+pub fn verify_items(
+	repo: &CachedRepo,
+	revision: Option<&str>,
+	file: Option<&Path>,
+) -> (Vec<VerifyItem>, usize) {
+	let mut items = BTreeMap::new();
+	let mut skipped = 0;
+
+	let files = repo
+		.revisions
+		.iter()
+		.filter(|rev| revision.is_none_or(|hash| rev.commit_hash == hash))
+		.flat_map(|rev| rev.files.iter())
+		.filter(|f| file.is_none_or(|path| f.path == path));
+
+	for file in files {
+		let kind =
+			file.blob_hash.as_deref().and_then(HashKind::from_blob_name);
+
+		match (&file.blob_hash, kind) {
+			(Some(hash), Some(kind)) => {
+				items.entry(hash.clone()).or_insert_with(|| VerifyItem {
+					path: repo.path.join(BLOBS_FOLDER).join(hash),
+					expected: hash.clone(),
+					kind,
+					size: file.size,
+				});
+			}
+			_ => skipped += 1,
+		}
+	}
+
+	(items.into_values().collect(), skipped)
+}
+
+/// hashes the file at `path`, reporting every chunk read to `on_read`
+pub fn hash_file(
+	path: &Path,
+	kind: HashKind,
+	on_read: impl FnMut(u64),
+) -> Result<String> {
+	let (sha256, sha1) = hash_file_as(path, &[kind], on_read)?;
+
+	Ok(match kind {
+		HashKind::Sha256 => sha256,
+		HashKind::GitSha1 => sha1,
+	})
+}
+
+/// both possible blob names `(sha256, git sha1)` of the file at `path`, read once
+pub fn blob_names(
+	path: &Path,
+	on_read: impl FnMut(u64),
+) -> Result<(String, String)> {
+	hash_file_as(path, &[HashKind::Sha256, HashKind::GitSha1], on_read)
+}
+
+/// hashes with every hash of `kinds`, the others come back empty
+fn hash_file_as(
+	path: &Path,
+	kinds: &[HashKind],
+	mut on_read: impl FnMut(u64),
+) -> Result<(String, String)> {
+	let mut file = open_untouched(path)?;
+	let size = file.metadata()?.len();
+	let mut buffer = vec![0; READ_BUFFER_SIZE];
+
+	let use_sha256 = kinds.contains(&HashKind::Sha256);
+	let use_sha1 = kinds.contains(&HashKind::GitSha1);
+
+	let mut sha256 = Sha256::new();
+	let mut sha1 = Sha1::new();
+	if use_sha1 {
+		sha1.update(format!("blob {size}\0"));
+	}
+
+	loop {
+		let read = file.read(&mut buffer)?;
+		if read == 0 {
+			break;
+		}
+
+		if use_sha256 {
+			sha256.update(&buffer[..read]);
+		}
+		if use_sha1 {
+			sha1.update(&buffer[..read]);
+		}
+
+		on_read(read as u64);
+	}
+
+	Ok((
+		if use_sha256 {
+			hex::encode(sha256.finalize())
+		} else {
+			String::new()
+		},
+		if use_sha1 {
+			hex::encode(sha1.finalize())
+		} else {
+			String::new()
+		},
+	))
+}
+
+/// rehashes blobs on a worker thread
+#[derive(Clone)]
+pub struct AsyncVerifyJob {
+	items: Arc<Vec<VerifyItem>>,
+	result: Arc<Mutex<Option<VerifyResult>>>,
+	skipped: usize,
+}
+
+impl AsyncVerifyJob {
+	/// job verifying `items`, `skipped` counts files that cannot be verified
+	pub fn new(items: Vec<VerifyItem>, skipped: usize) -> Self {
+		Self {
+			items: Arc::new(items),
+			result: Arc::default(),
+			skipped,
+		}
+	}
+
+	/// the result, once the job finished
+	pub fn take_result(&self) -> Option<VerifyResult> {
+		self.result.lock().ok().and_then(|mut r| r.take())
+	}
+}
+
+impl AsyncJob for AsyncVerifyJob {
+	type Notification = AsyncHubNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let total: u64 = self.items.iter().map(|i| i.size).sum();
+		let mut done = 0_u64;
+		let mut result = VerifyResult {
+			skipped: self.skipped,
+			..VerifyResult::default()
+		};
+
+		params.set_progress(ProgressPercent::default())?;
+
+		for item in self.items.iter() {
+			let hashed = hash_file(&item.path, item.kind, |read| {
+				done += read;
+
+				let progress = ProgressPercent::new(
+					usize::try_from(done).unwrap_or(usize::MAX),
+					usize::try_from(total).unwrap_or(usize::MAX),
+				);
+				if params.set_progress(progress).unwrap_or_default() {
+					if let Err(e) =
+						params.send(AsyncHubNotification::VerifyProgress)
+					{
+						log::error!("verify progress: {e}");
+					}
+				}
+			});
+
+			match hashed {
+				Ok(actual) if actual == item.expected => result.ok += 1,
+				Ok(actual) => result.mismatches.push(Mismatch {
+					path: item.path.clone(),
+					expected: item.expected.clone(),
+					actual,
+				}),
+				Err(e) => {
+					result.errors.push((item.path.clone(), e.to_string()));
+				}
+			}
+		}
+
+		*self
+			.result
+			.lock()
+			.map_err(|_| anyhow!("verify result poisoned"))? =
+			Some(result);
+
+		Ok(AsyncHubNotification::Verify)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::{
+		access::atime_supported,
+		scan::{scan_repo, tests::add_file},
+	};
+	use super::*;
+	use filetime::{set_file_atime, FileTime};
+	use pretty_assertions::assert_eq;
+	use std::fs;
+	use tempfile::TempDir;
+
+	// `git hash-object` and `sha256sum` of "hello\n"
+	const HELLO_SHA1: &str = "ce013625030ba8dba906f756967f9e9ca394464a";
+	const HELLO_SHA256: &str =
+		"5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03";
+
+	#[test]
+	fn test_hash_file() {
+		let dir = TempDir::new().unwrap();
+		let path = dir.path().join("hello");
+		fs::write(&path, "hello\n").unwrap();
+
+		let mut read = 0;
+		assert_eq!(
+			hash_file(&path, HashKind::GitSha1, |n| read += n).unwrap(),
+			HELLO_SHA1
+		);
+		assert_eq!(read, 6);
+		assert_eq!(
+			hash_file(&path, HashKind::Sha256, |_| ()).unwrap(),
+			HELLO_SHA256
+		);
+	}
+
+	#[test]
+	fn test_verify_items() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		add_file(&path, "aaaa", "a.txt", HELLO_SHA1, "hello\n");
+		add_file(&path, "aaaa", "b.bin", HELLO_SHA256, "corrupt");
+		add_file(&path, "bbbb", "a.txt", HELLO_SHA1, "hello\n");
+		add_file(&path, "bbbb", "c.txt", "not-a-hash", "");
+
+		let repo = scan_repo(&path, 0).unwrap();
+
+		let (items, skipped) = verify_items(&repo, None, None);
+		assert_eq!(items.len(), 2);
+		assert_eq!(skipped, 1);
+
+		let (items, skipped) =
+			verify_items(&repo, Some("aaaa"), Some(Path::new("a.txt")));
+		assert_eq!(skipped, 0);
+		assert_eq!(
+			items,
+			vec![VerifyItem {
+				path: path.join("blobs").join(HELLO_SHA1),
+				expected: HELLO_SHA1.to_string(),
+				kind: HashKind::GitSha1,
+				size: 6,
+			}]
+		);
+	}
+
+	#[test]
+	fn test_verify_keeps_last_accessed() {
+		let root = TempDir::new().unwrap();
+		let path = root.path().join("models--org--model");
+
+		add_file(&path, "aaaa", "a.txt", HELLO_SHA1, "hello\n");
+		add_file(&path, "aaaa", "b.bin", HELLO_SHA256, "hello\n");
+
+		// older than a day, so that `relatime` mounts update it on read
+		let repo = scan_repo(&path, 0).unwrap();
+		let (items, _) = verify_items(&repo, None, None);
+		for item in &items {
+			set_file_atime(&item.path, FileTime::from_unix_time(86_400, 0))
+				.unwrap();
+		}
+		let before = scan_repo(&path, 0).unwrap().last_accessed;
+		assert_eq!(before.is_some(), atime_supported(&path));
+
+		for item in &items {
+			assert_eq!(
+				hash_file(&item.path, item.kind, |_| ()).unwrap(),
+				item.expected
+			);
+		}
+
+		assert_eq!(scan_repo(&path, 0).unwrap().last_accessed, before);
+	}
+}
@@ -40,6 +40,8 @@ pub struct KeysList {
     pub tab_status: GituiKeyEvent,
     pub tab_log: GituiKeyEvent,
     pub tab_files: GituiKeyEvent,
+    pub tab_datasets: GituiKeyEvent,
+    pub tab_modules: GituiKeyEvent,
     pub tab_stashing: GituiKeyEvent,
     pub tab_stashes: GituiKeyEvent,
     pub tab_toggle: GituiKeyEvent,
@@ -52,6 +54,20 @@ pub struct KeysList {
     pub open_commit_editor: GituiKeyEvent,
     pub open_help: GituiKeyEvent,
     pub open_options: GituiKeyEvent,
+    pub open_problems: GituiKeyEvent,
+    pub verify: GituiKeyEvent,
+    pub verify_revision: GituiKeyEvent,
+    pub repo_type_filter: GituiKeyEvent,
+    pub convert_layout: GituiKeyEvent,
+    pub sort_column: GituiKeyEvent,
+    pub sort_reverse: GituiKeyEvent,
+    pub select_revision: GituiKeyEvent,
+    pub goto_offset: GituiKeyEvent,
+    pub search: GituiKeyEvent,
+    pub search_next: GituiKeyEvent,
+    pub toggle_wrap: GituiKeyEvent,
+    pub scroll_left: GituiKeyEvent,
+    pub scroll_right: GituiKeyEvent,
     pub move_left: GituiKeyEvent,
     pub move_right: GituiKeyEvent,
     pub move_up: GituiKeyEvent,
@@ -137,6 +153,8 @@ impl Default for KeysList {
 			tab_status: GituiKeyEvent::new(KeyCode::Char('1'), KeyModifiers::empty()),
 			tab_log: GituiKeyEvent::new(KeyCode::Char('2'),  KeyModifiers::empty()),
 			tab_files: GituiKeyEvent::new(KeyCode::Char('3'),  KeyModifiers::empty()),
+			tab_datasets: GituiKeyEvent::new(KeyCode::Char('4'),  KeyModifiers::empty()),
+			tab_modules: GituiKeyEvent::new(KeyCode::Char('5'),  KeyModifiers::empty()),
 			tab_stashing: GituiKeyEvent::new(KeyCode::Char('4'),  KeyModifiers::empty()),
 			tab_stashes: GituiKeyEvent::new(KeyCode::Char('5'),  KeyModifiers::empty()),
 			tab_toggle: GituiKeyEvent::new(KeyCode::Tab,  KeyModifiers::empty()),
@@ -149,6 +167,20 @@ impl Default for KeysList {
 			open_commit_editor: GituiKeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL),
 			open_help: GituiKeyEvent::new(KeyCode::Char('h'),  KeyModifiers::empty()),
 			open_options: GituiKeyEvent::new(KeyCode::Char('o'),  KeyModifiers::empty()),
+			open_problems: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::empty()),
+			verify: GituiKeyEvent::new(KeyCode::Char('v'),  KeyModifiers::empty()),
+			verify_revision: GituiKeyEvent::new(KeyCode::Char('V'),  KeyModifiers::SHIFT),
+			repo_type_filter: GituiKeyEvent::new(KeyCode::Char('t'),  KeyModifiers::empty()),
+			convert_layout: GituiKeyEvent::new(KeyCode::Char('L'),  KeyModifiers::SHIFT),
+			sort_column: GituiKeyEvent::new(KeyCode::Char('s'),  KeyModifiers::empty()),
+			sort_reverse: GituiKeyEvent::new(KeyCode::Char('S'),  KeyModifiers::SHIFT),
+			select_revision: GituiKeyEvent::new(KeyCode::Char('b'),  KeyModifiers::empty()),
+			goto_offset: GituiKeyEvent::new(KeyCode::Char('g'),  KeyModifiers::empty()),
+			search: GituiKeyEvent::new(KeyCode::Char('/'),  KeyModifiers::empty()),
+			search_next: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::empty()),
+			toggle_wrap: GituiKeyEvent::new(KeyCode::Char('W'),  KeyModifiers::SHIFT),
+			scroll_left: GituiKeyEvent::new(KeyCode::Left,  KeyModifiers::SHIFT),
+			scroll_right: GituiKeyEvent::new(KeyCode::Right,  KeyModifiers::SHIFT),
 			move_left: GituiKeyEvent::new(KeyCode::Left,  KeyModifiers::empty()),
 			move_right: GituiKeyEvent::new(KeyCode::Right,  KeyModifiers::empty()),
 			tree_collapse_recursive: GituiKeyEvent::new(KeyCode::Left,  KeyModifiers::SHIFT),
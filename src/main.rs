@@ -9,15 +9,30 @@ mod queue;
 mod string_utils;
 mod strings;
 mod notify_mutex;
+mod options;
+mod popups;
 
+mod asyncjob;
 mod cmdbar;
-use crate::{app::App, args::process_cmdline};
+mod datasets;
+mod hub;
+mod modules;
+mod progress;
+mod spinner;
+mod watcher;
+use crate::{
+    app::App,
+    args::{process_cmdline, CacheRoot},
+    hub::AsyncHubNotification,
+    spinner::Spinner,
+    watcher::CacheWatcher,
+};
 use anyhow::{anyhow, bail, Result};
 use std::{
     cell::RefCell,
     io::{self, Stdout},
     panic,
-    path::Path,
+    path::{Path, PathBuf},
     process,
     time::{Duration, Instant},
 };
@@ -29,6 +44,7 @@ use crossterm::{
 use input::{Input, InputEvent, InputState};
 use app::QuitState;
 use keys::KeyConfig;
+use options::Options;
 use ratatui::backend::CrosstermBackend;
 use scopeguard::defer;
 use ui::style::Theme;
@@ -44,8 +60,10 @@ pub enum QueueEvent {
     Tick,
     Notify,
     SpinnerUpdate,
-    // AsyncEvent(AsyncNotification),
+    AsyncEvent(AsyncNotification),
     InputEvent(InputEvent),
+    /// paths below the cache root changed
+    FileChanges(Vec<PathBuf>),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -54,19 +72,37 @@ pub enum SyntaxHighlightProgress {
     Done,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexSearchProgress {
+    Progress,
+    Done,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextStreamProgress {
+    Indexing,
+    Indexed,
+    Searching,
+    Searched,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AsyncAppNotification {
-    ///
+    /// a syntax highlighting job made progress or finished
     SyntaxHighlighting(SyntaxHighlightProgress),
+    /// a byte pattern search in the hex view made progress or finished
+    HexSearch(HexSearchProgress),
+    /// a line index or text search of the stream view made progress or finished
+    TextStream(TextStreamProgress),
 }
 
-// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-// pub enum AsyncNotification {
-//     ///
-//     App(AsyncAppNotification),
-//     ///
-//     Git(AsyncGitNotification),
-// }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsyncNotification {
+    /// app level jobs
+    App(AsyncAppNotification),
+    /// hub cache jobs
+    Hub(AsyncHubNotification),
+}
 
 #[derive(Clone, Copy, PartialEq)]
 enum Updater {
@@ -83,6 +119,9 @@ fn main() -> Result<()> {
         .map_err(|e| eprintln!("KeyConfig loading error: {e}"))
         .unwrap_or_default();
     let theme = Theme::init(&cliargs.theme);
+    let options = Options::init()
+        .map_err(|e| eprintln!("Options loading error: {e}"))
+        .unwrap_or_default();
 
     setup_terminal()?;
     defer! {
@@ -92,19 +131,21 @@ fn main() -> Result<()> {
     let mut terminal = start_terminal(io::stdout())?;
     let input = Input::new();
 
-    // let updater = if cliargs.notify_watcher {
-    //     Updater::NotifyWatcher
-    // } else {
-    //     Updater::Ticker
-    // };
-    let updater = Updater::Ticker;
+    let updater = if cliargs.notify_watcher {
+        Updater::NotifyWatcher
+    } else {
+        Updater::Ticker
+    };
 
     loop {
         let quit_state = run_app(
             app_start,
-            // repo_path.clone(),
+            cliargs.cache_roots.clone(),
+            cliargs.datasets_root.clone(),
+            cliargs.modules_root.clone(),
             theme.clone(),
             key_config.clone(),
+            options.clone(),
             &input,
             updater,
             &mut terminal,
@@ -147,41 +188,56 @@ fn start_terminal(buf: Stdout) -> Result<Terminal> {
 }
 
 
+#[allow(clippy::too_many_arguments)]
 fn run_app(
     app_start: Instant,
+    cache_roots: Vec<CacheRoot>,
+    datasets_root: PathBuf,
+    modules_root: PathBuf,
     theme: Theme,
     key_config: KeyConfig,
+    options: Options,
     input: &Input,
     updater: Updater,
     terminal: &mut Terminal,
 ) -> Result<QuitState, anyhow::Error> {
-    // let (tx_git, rx_git) = unbounded();
-    // let (tx_app, rx_app) = unbounded();
+    let (tx_hub, rx_hub) = unbounded();
+    let (tx_app, rx_app) = unbounded();
 
     let rx_input = input.receiver();
 
-    // let (rx_ticker, rx_watcher) = match updater {
-    //     Updater::NotifyWatcher => {
-    //         let repo_watcher = RepoWatcher::new(repo_work_dir(&repo)?.as_str());
+    let (rx_ticker, rx_watcher) = match updater {
+        Updater::NotifyWatcher => {
+            let mut paths: Vec<PathBuf> = cache_roots.iter().map(|r| r.path.clone()).collect();
+            if datasets_root.is_dir() {
+                paths.push(datasets_root.clone());
+            }
+            if modules_root.is_dir() {
+                paths.push(modules_root.clone());
+            }
+            let cache_watcher = CacheWatcher::new(&paths);
 
-    //         (never(), repo_watcher.receiver())
-    //     }
-    //     Updater::Ticker => (tick(TICK_INTERVAL), never()),
-    // };
-    let (rx_ticker, rx_watcher) = (tick(TICK_INTERVAL), never());
+            (never(), cache_watcher.receiver())
+        }
+        Updater::Ticker => (tick(TICK_INTERVAL), never()),
+    };
 
-    // let spinner_ticker = tick(SPINNER_INTERVAL);
+    let spinner_ticker = tick(SPINNER_INTERVAL);
 
     let mut app = App::new(
         // RefCell::new(repo),
-        // tx_git,
-        // tx_app,
+        tx_hub,
+        tx_app,
+        cache_roots,
+        datasets_root,
+        modules_root,
         input.clone(),
         theme,
         key_config,
+        options,
     )?;
 
-    // let mut spinner = Spinner::default();
+    let mut spinner = Spinner::default();
     let mut first_update = true;
 
     log::trace!("app start: {} ms", app_start.elapsed().as_millis());
@@ -193,20 +249,20 @@ fn run_app(
         } else {
             select_event(
                 &rx_input,
-                // &rx_git,
-                // &rx_app,
+                &rx_hub,
+                &rx_app,
                 &rx_ticker,
                 &rx_watcher,
-                // &spinner_ticker,
+                &spinner_ticker,
             )?
         };
 
         {
-            // if matches!(event, QueueEvent::SpinnerUpdate) {
-            //     spinner.update();
-            //     spinner.draw(terminal)?;
-            //     continue;
-            // }
+            if matches!(event, QueueEvent::SpinnerUpdate) {
+                spinner.update();
+                spinner.draw(terminal)?;
+                continue;
+            }
 
             // scope_time!("loop");
 
@@ -219,24 +275,21 @@ fn run_app(
                     app.event(ev)?;
                 }
                 QueueEvent::Tick | QueueEvent::Notify => {
-                    // app.update()?;
+                    app.update()?;
+                }
+                QueueEvent::FileChanges(paths) => {
+                    app.update_paths(paths)?;
+                }
+                QueueEvent::AsyncEvent(ev) => {
+                    app.update_async(ev)?;
                 }
-                // QueueEvent::AsyncEvent(ev) => {
-                //     if !matches!(
-                //         ev,
-                //         AsyncNotification::Git(AsyncGitNotification::FinishUnchanged)
-                //     ) {
-                //         app.update_async(ev)?;
-                //     }
-                // }
-                // QueueEvent::SpinnerUpdate => unreachable!(),
-                _ => bail!("dame!"),
+                QueueEvent::SpinnerUpdate => unreachable!(),
             }
 
             draw(terminal, &app)?;
 
-            // spinner.set_state(app.any_work_pending());
-            // spinner.draw(terminal)?;
+            spinner.set_state(app.any_work_pending());
+            spinner.draw(terminal)?;
 
             if app.is_quit() {
                 break;
@@ -248,40 +301,36 @@ fn run_app(
 }
 fn select_event(
     rx_input: &Receiver<InputEvent>,
-    // rx_git: &Receiver<AsyncGitNotification>,
-    // rx_app: &Receiver<AsyncAppNotification>,
+    rx_hub: &Receiver<AsyncHubNotification>,
+    rx_app: &Receiver<AsyncAppNotification>,
     rx_ticker: &Receiver<Instant>,
-    rx_notify: &Receiver<()>,
-    // rx_spinner: &Receiver<Instant>,
+    rx_notify: &Receiver<Vec<PathBuf>>,
+    rx_spinner: &Receiver<Instant>,
 ) -> Result<QueueEvent> {
     let mut sel = Select::new();
 
     sel.recv(rx_input);
-    // sel.recv(rx_git);
-    // sel.recv(rx_app);
+    sel.recv(rx_hub);
+    sel.recv(rx_app);
     sel.recv(rx_ticker);
     sel.recv(rx_notify);
-    // sel.recv(rx_spinner);
+    sel.recv(rx_spinner);
 
     let oper = sel.select();
     let index = oper.index();
 
     let ev = match index {
         0 => oper.recv(rx_input).map(QueueEvent::InputEvent),
-        1 => oper.recv(rx_ticker).map(|_| QueueEvent::Notify),
-        2 => oper.recv(rx_notify).map(|()| QueueEvent::Notify),
+        1 => oper
+            .recv(rx_hub)
+            .map(|e| QueueEvent::AsyncEvent(AsyncNotification::Hub(e))),
+        2 => oper
+            .recv(rx_app)
+            .map(|e| QueueEvent::AsyncEvent(AsyncNotification::App(e))),
+        3 => oper.recv(rx_ticker).map(|_| QueueEvent::Notify),
+        4 => oper.recv(rx_notify).map(QueueEvent::FileChanges),
+        5 => oper.recv(rx_spinner).map(|_| QueueEvent::SpinnerUpdate),
         _ => bail!("unknown select source"),
-        // 0 => oper.recv(rx_input).map(QueueEvent::InputEvent),
-        // 1 => oper
-        //     .recv(rx_git)
-        //     .map(|e| QueueEvent::AsyncEvent(AsyncNotification::Git(e))),
-        // 2 => oper
-        //     .recv(rx_app)
-        //     .map(|e| QueueEvent::AsyncEvent(AsyncNotification::App(e))),
-        // 3 => oper.recv(rx_ticker).map(|_| QueueEvent::Notify),
-        // 4 => oper.recv(rx_notify).map(|()| QueueEvent::Notify),
-        // 5 => oper.recv(rx_spinner).map(|_| QueueEvent::SpinnerUpdate),
-        // _ => bail!("unknown select source"),
     }?;
 
     Ok(ev)
@@ -0,0 +1,53 @@
+use super::{scan_modules, ModulesCache};
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	hub::AsyncHubNotification,
+	progress::ProgressPercent,
+};
+use anyhow::{anyhow, Result};
+use std::{
+	path::PathBuf,
+	sync::{Arc, Mutex},
+};
+
+/// scans the `transformers_modules` folder on a worker thread
+#[derive(Clone)]
+pub struct AsyncModulesJob {
+	root: PathBuf,
+	result: Arc<Mutex<Option<Result<ModulesCache>>>>,
+}
+
+impl AsyncModulesJob {
+	/// job scanning all of `root`
+	pub fn new(root: PathBuf) -> Self {
+		Self {
+			root,
+			result: Arc::default(),
+		}
+	}
+
+	/// the scan result, once the job finished
+	pub fn take_result(&self) -> Option<Result<ModulesCache>> {
+		self.result.lock().ok().and_then(|mut result| result.take())
+	}
+}
+
+impl AsyncJob for AsyncModulesJob {
+	type Notification = AsyncHubNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let result = scan_modules(&self.root);
+
+		*self
+			.result
+			.lock()
+			.map_err(|_| anyhow!("modules scan state poisoned"))? =
+			Some(result);
+
+		Ok(AsyncHubNotification::Modules)
+	}
+}
@@ -0,0 +1,173 @@
+//! model of the dynamic module cache of `transformers`
+//!
+//! before importing the code of a `trust_remote_code` repo,
+//! `transformers` copies its python files to
+//! `transformers_modules/<org>/<name>/<commit>/`. newer versions escape
+//! `.` and `-` of the repo name as `_dot_` and `_hyphen_`.
+//! modules of local model folders live in `transformers_modules/<folder>/`.
+
+mod job;
+mod scan;
+
+pub use job::AsyncModulesJob;
+pub use scan::scan_modules;
+
+use crate::hub::{HubCache, RepoType};
+use std::path::PathBuf;
+
+/// folder of the `transformers` modules below the modules root
+pub const TRANSFORMERS_MODULES: &str = "transformers_modules";
+
+/// how a module revision relates to the hub cache
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModuleLink {
+	/// the revision it was copied from is cached
+	Cached,
+	/// the repo is cached but no longer this revision
+	RevisionGone,
+	/// the repo is not cached at all
+	RepoGone,
+	/// copied from a local model folder
+	Local,
+}
+
+impl ModuleLink {
+	/// true for copies of code the hub cache no longer holds
+	pub const fn is_stale(self) -> bool {
+		matches!(self, Self::RevisionGone | Self::RepoGone)
+	}
+}
+
+/// one file of a module revision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleFile {
+	/// path relative to the revision folder
+	pub path: PathBuf,
+	pub size: u64,
+}
+
+/// one `<commit>` folder, or the folder of a local module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleRevision {
+	/// `None` for local modules
+	pub commit_hash: Option<String>,
+	pub path: PathBuf,
+	/// files sorted by path
+	pub files: Vec<ModuleFile>,
+	/// unix time of the last modification of the folder
+	pub last_modified: i64,
+}
+
+impl ModuleRevision {
+	pub fn size(&self) -> u64 {
+		self.files.iter().map(|f| f.size).sum()
+	}
+}
+
+/// the module copies of one repo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedModule {
+	/// repo id as used on the hub, the folder name for local modules
+	pub repo_id: String,
+	/// revisions sorted by commit hash
+	pub revisions: Vec<ModuleRevision>,
+}
+
+impl CachedModule {
+	pub fn size(&self) -> u64 {
+		self.revisions.iter().map(ModuleRevision::size).sum()
+	}
+}
+
+/// result of scanning the modules root
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModulesCache {
+	/// the `transformers_modules` folder
+	pub root: PathBuf,
+	/// modules sorted by repo id
+	pub modules: Vec<CachedModule>,
+}
+
+/// links `revision` of the module of `repo_id` to the model repos of `hub`
+pub fn module_link(
+	hub: &HubCache,
+	repo_id: &str,
+	revision: &ModuleRevision,
+) -> ModuleLink {
+	let Some(commit) = &revision.commit_hash else {
+		return ModuleLink::Local;
+	};
+
+	let mut repos = hub
+		.repos
+		.iter()
+		.filter(|r| {
+			r.repo_type == RepoType::Model && r.repo_id == repo_id
+		})
+		.peekable();
+
+	if repos.peek().is_none() {
+		ModuleLink::RepoGone
+	} else if repos.any(|r| r.revision(commit).is_some()) {
+		ModuleLink::Cached
+	} else {
+		ModuleLink::RevisionGone
+	}
+}
+
+/// undoes the escaping of module folder names
+pub fn unescape_module_name(name: &str) -> String {
+	name.replace("_hyphen_", "-").replace("_dot_", ".")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::hub::{add_file, scan_cache};
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	fn revision(commit_hash: Option<&str>) -> ModuleRevision {
+		ModuleRevision {
+			commit_hash: commit_hash.map(String::from),
+			path: PathBuf::new(),
+			files: Vec::new(),
+			last_modified: 0,
+		}
+	}
+
+	#[test]
+	fn test_module_link() {
+		let root = TempDir::new().unwrap();
+		add_file(
+			&root.path().join("models--org--model"),
+			"aaaa",
+			"config.json",
+			"c1",
+			"{}",
+		);
+
+		let mut hub = HubCache::default();
+		hub.merge(scan_cache(root.path(), 0, &[], |_, _, _| ()).unwrap());
+
+		let link = |repo_id, commit| {
+			module_link(&hub, repo_id, &revision(commit))
+		};
+		assert_eq!(link("org/model", Some("aaaa")), ModuleLink::Cached);
+		assert_eq!(
+			link("org/model", Some("bbbb")),
+			ModuleLink::RevisionGone
+		);
+		assert_eq!(link("org/other", Some("aaaa")), ModuleLink::RepoGone);
+		assert_eq!(link("my_model", None), ModuleLink::Local);
+	}
+
+	#[test]
+	fn test_unescape_module_name() {
+		assert_eq!(
+			unescape_module_name("Qwen2_dot_5_hyphen_7B"),
+			"Qwen2.5-7B"
+		);
+		assert_eq!(unescape_module_name("phi_2"), "phi_2");
+	}
+}
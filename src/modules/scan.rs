@@ -0,0 +1,211 @@
+use super::{
+	unescape_module_name, CachedModule, ModuleFile, ModuleRevision,
+	ModulesCache,
+};
+use crate::hub::modified_secs;
+use anyhow::{Context, Result};
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+const PYCACHE_FOLDER: &str = "__pycache__";
+const INIT_FILE: &str = "__init__.py";
+
+/// scans all module copies below the `transformers_modules` folder `root`,
+/// a missing folder is an empty cache
+pub fn scan_modules(root: &Path) -> Result<ModulesCache> {
+	let mut revisions = BTreeMap::new();
+
+	if root.is_dir() {
+		scan_folder(root, Path::new(""), &mut revisions).with_context(
+			|| format!("failed to read modules dir {}", root.display()),
+		)?;
+	}
+
+	let mut modules: BTreeMap<String, Vec<ModuleRevision>> =
+		BTreeMap::new();
+	for (repo_id, revision) in revisions.into_values() {
+		modules.entry(repo_id).or_default().push(revision);
+	}
+
+	Ok(ModulesCache {
+		root: root.to_path_buf(),
+		modules: modules
+			.into_iter()
+			.map(|(repo_id, revisions)| CachedModule {
+				repo_id,
+				revisions,
+			})
+			.collect(),
+	})
+}
+
+/// finds commit folders and local modules below `root/relative`,
+/// keyed by their path to keep them sorted
+fn scan_folder(
+	root: &Path,
+	relative: &Path,
+	out: &mut BTreeMap<PathBuf, (String, ModuleRevision)>,
+) -> Result<()> {
+	let path = root.join(relative);
+	let mut folders = Vec::new();
+	let mut has_code = false;
+
+	for entry in fs::read_dir(&path)? {
+		let entry = entry?;
+		let name = entry.file_name().to_string_lossy().to_string();
+
+		if entry.file_type()?.is_dir() {
+			if name != PYCACHE_FOLDER && !name.starts_with('.') {
+				folders.push(name);
+			}
+		} else if name.ends_with(".py") && name != INIT_FILE {
+			has_code = true;
+		}
+	}
+
+	// every level holds an `__init__.py`, only local modules hold code
+	if has_code && relative != Path::new("") {
+		out.insert(
+			relative.to_path_buf(),
+			(repo_id(relative), read_revision(&path, None)?),
+		);
+		return Ok(());
+	}
+
+	for name in folders {
+		let child = relative.join(&name);
+
+		if is_commit_hash(&name) && relative != Path::new("") {
+			out.insert(
+				child.clone(),
+				(
+					repo_id(relative),
+					read_revision(&root.join(&child), Some(name))?,
+				),
+			);
+		} else {
+			scan_folder(root, &child, out)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn read_revision(
+	path: &Path,
+	commit_hash: Option<String>,
+) -> Result<ModuleRevision> {
+	let mut files = Vec::new();
+	read_files(path, Path::new(""), &mut files)?;
+	files.sort_by(|a, b| a.path.cmp(&b.path));
+
+	Ok(ModuleRevision {
+		commit_hash,
+		path: path.to_path_buf(),
+		files,
+		last_modified: modified_secs(path),
+	})
+}
+
+fn read_files(
+	base: &Path,
+	relative: &Path,
+	out: &mut Vec<ModuleFile>,
+) -> Result<()> {
+	for entry in fs::read_dir(base.join(relative))? {
+		let entry = entry?;
+		let relative = relative.join(entry.file_name());
+		let file_type = entry.file_type()?;
+
+		if file_type.is_dir() {
+			if entry.file_name() != PYCACHE_FOLDER {
+				read_files(base, &relative, out)?;
+			}
+		} else {
+			out.push(ModuleFile {
+				path: relative,
+				size: entry.metadata()?.len(),
+			});
+		}
+	}
+
+	Ok(())
+}
+
+/// `org/name` of the module folder `relative`
+fn repo_id(relative: &Path) -> String {
+	relative
+		.components()
+		.map(|c| unescape_module_name(&c.as_os_str().to_string_lossy()))
+		.collect::<Vec<_>>()
+		.join("/")
+}
+
+fn is_commit_hash(name: &str) -> bool {
+	name.len() == 40 && name.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use tempfile::TempDir;
+
+	const COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+
+	fn write(root: &Path, path: &str, content: &str) {
+		let path = root.join(path);
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(path, content).unwrap();
+	}
+
+	#[test]
+	fn test_scan_modules() {
+		let root = TempDir::new().unwrap();
+		let root = root.path();
+
+		write(root, "__init__.py", "");
+		write(root, "org/__init__.py", "");
+		write(root, "org/Qwen2_dot_5/__init__.py", "");
+		write(
+			root,
+			&format!("org/Qwen2_dot_5/{COMMIT}/modeling_qwen.py"),
+			"import torch\n",
+		);
+		write(
+			root,
+			&format!("org/Qwen2_dot_5/{COMMIT}/__pycache__/x.pyc"),
+			"",
+		);
+		write(root, "my_model/__init__.py", "");
+		write(root, "my_model/configuration.py", "x = 1\n");
+
+		let cache = scan_modules(root).unwrap();
+		let found: Vec<_> = cache
+			.modules
+			.iter()
+			.map(|m| {
+				(
+					m.repo_id.as_str(),
+					m.revisions[0].commit_hash.as_deref(),
+					m.revisions[0].files.len(),
+				)
+			})
+			.collect();
+
+		assert_eq!(
+			found,
+			vec![
+				("my_model", None, 2),
+				("org/Qwen2.5", Some(COMMIT), 1),
+			]
+		);
+		assert_eq!(cache.modules[1].size(), 13);
+
+		let missing = scan_modules(&root.join("missing")).unwrap();
+		assert!(missing.modules.is_empty());
+	}
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::{fs::File, path::PathBuf, rc::Rc};
+use struct_patch::traits::Patch as PatchTrait;
+use struct_patch::Patch;
+
+use crate::args::get_app_config_path;
+
+pub type SharedOptions = Rc<Options>;
+const OPTIONS_FILENAME: &str = "options.ron";
+
+/// user settings, every field can be overridden in `options.ron`
+#[derive(Debug, Clone, Patch)]
+#[patch(attribute(derive(Deserialize, Debug)))]
+pub struct Options {
+    /// repos not accessed for this many days are highlighted as stale
+    pub stale_after_days: u32,
+    /// size in GB the cache should stay below, shown as a gauge
+    pub cache_budget_gb: Option<u64>,
+    /// free space in GB below which a warning is shown at startup
+    pub min_free_gb: Option<u64>,
+}
+
+#[rustfmt::skip]
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            stale_after_days: 90,
+            cache_budget_gb: None,
+            min_free_gb: None,
+        }
+    }
+}
+
+impl Options {
+    fn get_config_file() -> Result<PathBuf> {
+        Ok(get_app_config_path()?.join(OPTIONS_FILENAME))
+    }
+
+    pub fn init() -> Result<Self> {
+        let mut options = Self::default();
+        if let Ok(f) = File::open(Self::get_config_file()?) {
+            match ron::de::from_reader(f) {
+                Ok(patch) => options.apply(patch),
+                Err(e) => {
+                    log::error!("Options parse error: {e}");
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    /// seconds without access after which a repo is stale
+    pub fn stale_after_secs(&self) -> i64 {
+        i64::from(self.stale_after_days) * 24 * 60 * 60
+    }
+
+    /// cache budget in bytes
+    pub fn cache_budget(&self) -> Option<u64> {
+        self.cache_budget_gb.map(gb_to_bytes)
+    }
+
+    /// free space floor in bytes
+    pub fn min_free_space(&self) -> Option<u64> {
+        self.min_free_gb.map(gb_to_bytes)
+    }
+}
+
+/// decimal units as in `bytes_to_string`
+const fn gb_to_bytes(gb: u64) -> u64 {
+    gb.saturating_mul(1_000_000_000)
+}
@@ -0,0 +1,257 @@
+use crate::{
+	app::Environment,
+	asyncjob::AsyncSingleJob,
+	components::{
+		bytes_to_string, visibility_blocking, CommandBlocking,
+		CommandInfo, Component, DrawableComponent, EventState,
+	},
+	hub::{
+		symlinks_supported, AsyncConvertJob, AsyncHubNotification,
+		CachedRepo, ConvertResult,
+	},
+	keys::{key_match, SharedKeyConfig},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Gauge, Paragraph, Wrap},
+	Frame,
+};
+use std::path::PathBuf;
+
+/// repo to convert, see `InternalEvent::OpenConvert`
+pub struct ConvertOpen {
+	pub repo: CachedRepo,
+	/// false for repos of a read-only cache root
+	pub writable: bool,
+}
+
+enum State {
+	/// the repo cannot be converted, with the reason
+	Refused(String),
+	Confirm,
+	Running,
+	Done(Result<ConvertResult>),
+}
+
+/// replaces the copied snapshot files of a repo with links into `blobs/`
+/// after asking for confirmation
+pub struct ConvertPopup {
+	visible: bool,
+	repo: Option<CachedRepo>,
+	state: State,
+	job: AsyncSingleJob<AsyncConvertJob>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl ConvertPopup {
+	/// creates the hidden popup
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			repo: None,
+			state: State::Confirm,
+			job: AsyncSingleJob::new(env.sender_hub.clone()),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// asks to convert `open.repo`, unless its root cannot hold links
+	pub fn open(&mut self, open: ConvertOpen) -> Result<()> {
+		self.state = if !open.writable {
+			State::Refused(strings::convert_read_only())
+		} else if !symlinks_supported(&open.repo.path) {
+			State::Refused(strings::convert_no_symlinks())
+		} else {
+			State::Confirm
+		};
+		self.repo = Some(open.repo);
+
+		self.show()
+	}
+
+	/// true while files are converted
+	pub fn any_work_pending(&self) -> bool {
+		self.job.is_pending()
+	}
+
+	/// picks up the result of a finished job,
+	/// returns the root index and folder of the converted repo
+	pub fn update_hub(
+		&mut self,
+		ev: AsyncHubNotification,
+	) -> Option<(usize, PathBuf)> {
+		if ev != AsyncHubNotification::Convert {
+			return None;
+		}
+
+		let result = self.job.take_last()?.take_result()?;
+		self.state = State::Done(result);
+
+		self.repo.as_ref().map(|r| (r.root, r.path.clone()))
+	}
+
+	fn convert(&mut self) {
+		if let Some(repo) = &self.repo {
+			self.job.spawn(AsyncConvertJob::new(repo.clone()));
+			self.state = State::Running;
+		}
+	}
+
+	fn title(&self) -> String {
+		strings::title_convert(
+			self.repo.as_ref().map_or("", |r| r.repo_id.as_str()),
+		)
+	}
+
+	fn block(&self) -> Block<'_> {
+		Block::default()
+			.title(Span::styled(self.title(), self.theme.title(true)))
+			.borders(Borders::ALL)
+			.border_type(BorderType::Thick)
+			.border_style(self.theme.block(true))
+	}
+
+	fn draw_gauge(&self, f: &mut Frame, area: Rect) {
+		let progress = self.job.progress().unwrap_or_default().progress;
+		let files = self.repo.as_ref().map_or(0, CachedRepo::copied_files);
+
+		f.render_widget(
+			Gauge::default()
+				.label(strings::convert_progress(files))
+				.block(self.block())
+				.gauge_style(self.theme.push_gauge())
+				.percent(u16::from(progress)),
+			area,
+		);
+	}
+
+	fn message(&self) -> (String, bool) {
+		match &self.state {
+			State::Refused(reason) => (reason.clone(), true),
+			State::Confirm => (
+				self.repo.as_ref().map_or_else(String::new, |r| {
+					strings::convert_confirm(
+						r.copied_files(),
+						&bytes_to_string(r.copied_size()),
+					)
+				}),
+				false,
+			),
+			State::Done(Ok(result)) => {
+				let mut lines = vec![strings::convert_summary(
+					result,
+					&bytes_to_string(result.reclaimed),
+				)];
+				lines.extend(result.errors.iter().map(|(path, e)| {
+					format!("{}: {e}", path.display())
+				}));
+
+				(lines.join("\n"), !result.errors.is_empty())
+			}
+			State::Done(Err(e)) => (e.to_string(), true),
+			State::Running => (String::new(), false),
+		}
+	}
+
+	fn draw_message(&self, f: &mut Frame, area: Rect) {
+		let (text, failed) = self.message();
+		let style = if failed {
+			self.theme.text_danger()
+		} else {
+			self.theme.text(true, false)
+		};
+
+		f.render_widget(
+			Paragraph::new(Span::styled(text, style))
+				.block(self.block())
+				.wrap(Wrap { trim: false }),
+			area,
+		);
+	}
+}
+
+impl DrawableComponent for ConvertPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if !self.visible {
+			return Ok(());
+		}
+
+		if matches!(self.state, State::Running) {
+			let area = ui::rect_inside(
+				ui::Size::new(30, 3),
+				ui::Size::new(rect.width.saturating_sub(4), 3),
+				ui::centered_rect(60, 20, rect),
+			);
+			f.render_widget(Clear, area);
+			self.draw_gauge(f, area);
+		} else {
+			let area = ui::centered_rect(60, 30, rect);
+			f.render_widget(Clear, area);
+			self.draw_message(f, area);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for ConvertPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::confirm_convert(&self.key_config),
+				matches!(self.state, State::Confirm),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			if key_match(key, keys.exit_popup) {
+				self.hide();
+			} else if key_match(key, keys.enter)
+				&& matches!(self.state, State::Confirm)
+			{
+				self.convert();
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
@@ -0,0 +1,371 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState, ScrollType, VerticalScroll,
+	},
+	hub::{HubCache, RevisionRef},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	string_utils::trim_length_left,
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+use std::{borrow::Cow, cell::Cell, path::PathBuf};
+
+
+/// a file of a cached snapshot, see `InternalEvent::OpenFoundFile`
+#[derive(Debug, Clone)]
+pub struct FoundFile {
+	/// folder of the repo, unique across cache roots
+	pub repo_path: PathBuf,
+	pub reference: RevisionRef,
+	/// path relative to the snapshot folder
+	pub path: PathBuf,
+}
+
+struct FindEntry {
+	file: FoundFile,
+	/// `repo@rev/path` as matched and shown
+	text: String,
+}
+
+/// fuzzy matches the query against the files of all cached snapshots
+pub struct FuzzyFindPopup {
+	visible: bool,
+	entries: Vec<FindEntry>,
+	query: String,
+	/// entry index and matched char indices, best match first
+	matches: Vec<(usize, Vec<usize>)>,
+	matcher: SkimMatcherV2,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl FuzzyFindPopup {
+	/// creates the hidden popup
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			entries: Vec::new(),
+			query: String::new(),
+			matches: Vec::new(),
+			matcher: SkimMatcherV2::default(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			queue: env.queue.clone(),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// lists the files of every revision of every repo in `cache`
+	/// with an empty query
+	pub fn open(&mut self, cache: &HubCache) -> Result<()> {
+		self.entries = cache
+			.repos
+			.iter()
+			.flat_map(|repo| {
+				repo.revision_refs().into_iter().filter_map(move |reference| {
+					let revision = repo.revision(&reference.commit_hash)?;
+					let rev = reference.name.clone().unwrap_or_else(|| {
+						strings::short_hash(&reference.commit_hash)
+					});
+
+					Some(revision.files.iter().map(move |file| FindEntry {
+						text: format!(
+							"{}@{rev}/{}",
+							repo.repo_id,
+							file.path.display()
+						),
+						file: FoundFile {
+							repo_path: repo.path.clone(),
+							reference: reference.clone(),
+							path: file.path.clone(),
+						},
+					}))
+				})
+			})
+			.flatten()
+			.collect();
+
+		self.query.clear();
+		self.update_matches();
+
+		self.show()
+	}
+
+	fn update_matches(&mut self) {
+		if self.query.is_empty() {
+			self.matches =
+				(0..self.entries.len()).map(|idx| (idx, Vec::new())).collect();
+		} else {
+			let mut scored: Vec<_> = self
+				.entries
+				.iter()
+				.enumerate()
+				.filter_map(|(idx, entry)| {
+					self.matcher
+						.fuzzy_indices(&entry.text, &self.query)
+						.map(|(score, indices)| (score, idx, indices))
+				})
+				.collect();
+			scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+
+			self.matches = scored
+				.into_iter()
+				.map(|(_, idx, indices)| (idx, indices))
+				.collect();
+		}
+
+		self.selection = 0;
+	}
+
+	fn move_selection(&mut self, scroll: ScrollType) -> bool {
+		let count = self.matches.len();
+		if count == 0 {
+			return false;
+		}
+
+		let page = self.current_height.get().saturating_sub(1).max(1);
+		let old = self.selection;
+		self.selection = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => count - 1,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(count - 1);
+
+		old != self.selection
+	}
+
+	fn pick(&mut self) {
+		if let Some((idx, _)) = self.matches.get(self.selection) {
+			self.queue.push(InternalEvent::OpenFoundFile(
+				self.entries[*idx].file.clone(),
+			));
+			self.hide();
+		}
+	}
+
+	/// `text` cut from the left to fit `width`, matched chars
+	/// highlighted against the dimmed rest
+	fn match_to_line(
+		&self,
+		text: &str,
+		indices: &[usize],
+		width: usize,
+		selected: bool,
+	) -> Line<'static> {
+		let shown = trim_length_left(text, width);
+		let skipped = text.chars().count() - shown.chars().count();
+		let highlight = !self.query.is_empty();
+
+		Line::from(
+			shown
+				.chars()
+				.enumerate()
+				.map(|(idx, c)| {
+					let matched = indices.contains(&(idx + skipped));
+					Span::styled(
+						c.to_string(),
+						self.theme.text(!highlight || matched, selected),
+					)
+				})
+				.collect::<Vec<_>>(),
+		)
+	}
+
+	fn draw_query(&self, f: &mut Frame, area: Rect) {
+		f.render_widget(
+			Paragraph::new(Line::from(vec![
+				Span::styled("> ", self.theme.text(false, false)),
+				Span::styled(
+					self.query.as_str(),
+					self.theme.text(true, false),
+				),
+				Span::styled("_", self.theme.text(false, false)),
+			])),
+			area,
+		);
+	}
+}
+
+impl DrawableComponent for FuzzyFindPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if !self.visible {
+			return Ok(());
+		}
+
+		let area = ui::centered_rect(80, 70, rect);
+		f.render_widget(Clear, area);
+
+		let block = Block::default()
+			.title(Span::styled(
+				strings::title_fuzzy_find(
+					self.matches.len(),
+					self.entries.len(),
+				),
+				self.theme.title(true),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(true));
+		let inner = block.inner(area);
+		f.render_widget(block, area);
+
+		let chunks = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints([Constraint::Length(1), Constraint::Min(1)])
+			.split(inner);
+
+		self.draw_query(f, chunks[0]);
+
+		let height = usize::from(chunks[1].height);
+		let width = usize::from(chunks[1].width.saturating_sub(1));
+		self.current_height.set(height);
+		self.scroll.update(self.selection, self.matches.len(), height);
+
+		if self.matches.is_empty() {
+			ui::draw_list_block(
+				f,
+				chunks[1],
+				Block::default(),
+				std::iter::once(Span::styled(
+					Cow::from(strings::fuzzy_find_empty()),
+					self.theme.text(false, false),
+				)),
+			);
+		} else {
+			let items = self
+				.matches
+				.iter()
+				.enumerate()
+				.skip(self.scroll.get_top())
+				.take(height)
+				.map(|(idx, (entry, indices))| {
+					self.match_to_line(
+						&self.entries[*entry].text,
+						indices,
+						width,
+						idx == self.selection,
+					)
+				});
+
+			ui::draw_list_block(f, chunks[1], Block::default(), items);
+		}
+
+		self.scroll.draw(f, area, &self.theme);
+
+		Ok(())
+	}
+}
+
+impl Component for FuzzyFindPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					!self.matches.is_empty(),
+					true,
+				)
+				.order(order::NAV),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::open_found_file(&self.key_config),
+				!self.matches.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			let typed = match key.code {
+				KeyCode::Char(c)
+					if (key.modifiers - KeyModifiers::SHIFT).is_empty() =>
+				{
+					Some(c)
+				}
+				_ => None,
+			};
+
+			if let Some(c) = typed {
+				self.query.push(c);
+				self.update_matches();
+			} else if key.code == KeyCode::Backspace {
+				if self.query.pop().is_some() {
+					self.update_matches();
+				}
+			} else if key_match(key, keys.exit_popup) {
+				self.hide();
+			} else if key_match(key, keys.enter) {
+				self.pick();
+			} else if key_match(key, keys.move_up) {
+				self.move_selection(ScrollType::Up);
+			} else if key_match(key, keys.move_down) {
+				self.move_selection(ScrollType::Down);
+			} else if key_match(key, keys.home)
+				|| key_match(key, keys.shift_up)
+			{
+				self.move_selection(ScrollType::Home);
+			} else if key_match(key, keys.end)
+				|| key_match(key, keys.shift_down)
+			{
+				self.move_selection(ScrollType::End);
+			} else if key_match(key, keys.page_up) {
+				self.move_selection(ScrollType::PageUp);
+			} else if key_match(key, keys.page_down) {
+				self.move_selection(ScrollType::PageDown);
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
@@ -0,0 +1,17 @@
+mod convert;
+mod fuzzy_find;
+mod msg;
+mod problems;
+mod revisions;
+mod snapshot_diff;
+mod verify;
+mod view_input;
+
+pub use convert::{ConvertOpen, ConvertPopup};
+pub use fuzzy_find::{FoundFile, FuzzyFindPopup};
+pub use msg::MsgPopup;
+pub use problems::ProblemsPopup;
+pub use revisions::{RevisionsOpen, RevisionsPopup};
+pub use snapshot_diff::{SnapshotDiffOpen, SnapshotDiffPopup};
+pub use verify::{VerifyOpen, VerifyPopup};
+pub use view_input::{ViewInputKind, ViewInputPopup};
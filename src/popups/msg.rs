@@ -0,0 +1,126 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+	Frame,
+};
+
+/// shows a warning until it is dismissed
+pub struct MsgPopup {
+	visible: bool,
+	title: String,
+	msg: String,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl MsgPopup {
+	/// creates the hidden popup
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			title: String::new(),
+			msg: String::new(),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// shows `msg`, replacing a warning still open
+	pub fn show_warning(&mut self, msg: &str) -> Result<()> {
+		self.title = strings::msg_title_warning();
+		self.msg = msg.to_string();
+
+		self.show()
+	}
+}
+
+impl DrawableComponent for MsgPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if !self.visible {
+			return Ok(());
+		}
+
+		let area = ui::centered_rect(60, 30, rect);
+		f.render_widget(Clear, area);
+		f.render_widget(
+			Paragraph::new(Span::styled(
+				self.msg.as_str(),
+				self.theme.text_danger(),
+			))
+			.block(
+				Block::default()
+					.title(Span::styled(
+						self.title.as_str(),
+						self.theme.text_danger(),
+					))
+					.borders(Borders::ALL)
+					.border_type(BorderType::Thick)
+					.border_style(self.theme.block(true)),
+			)
+			.wrap(Wrap { trim: false }),
+			area,
+		);
+
+		Ok(())
+	}
+}
+
+impl Component for MsgPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::close_msg(&self.key_config),
+				true,
+				self.visible,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			if key_match(key, self.key_config.keys.enter)
+				|| key_match(key, self.key_config.keys.exit_popup)
+			{
+				self.hide();
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
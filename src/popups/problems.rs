@@ -0,0 +1,257 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState, ScrollType, VerticalScroll,
+	},
+	hub::{HubCache, Problem},
+	keys::{key_match, SharedKeyConfig},
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear},
+	Frame,
+};
+use std::{borrow::Cow, cell::Cell, path::Path, rc::Rc};
+
+const KIND_WIDTH: usize = 14;
+
+/// lists the integrity problems of all repos
+pub struct ProblemsPopup {
+	visible: bool,
+	cache: Rc<HubCache>,
+	/// `(repo, problem)` indices
+	rows: Vec<(usize, usize)>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl ProblemsPopup {
+	/// creates the hidden popup
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			cache: Rc::default(),
+			rows: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// shows the problems of `cache`, starting at those of `repo_id`
+	pub fn open(
+		&mut self,
+		cache: Rc<HubCache>,
+		repo_id: Option<&str>,
+	) -> Result<()> {
+		self.rows = cache
+			.repos
+			.iter()
+			.enumerate()
+			.flat_map(|(repo_idx, repo)| {
+				(0..repo.problems.len()).map(move |idx| (repo_idx, idx))
+			})
+			.collect();
+
+		self.selection = repo_id
+			.and_then(|id| {
+				self.rows
+					.iter()
+					.position(|(repo, _)| cache.repos[*repo].repo_id == id)
+			})
+			.unwrap_or_default();
+		self.cache = cache;
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, scroll: ScrollType) -> bool {
+		let count = self.rows.len();
+		if count == 0 {
+			return false;
+		}
+
+		let page = self.current_height.get().saturating_sub(1).max(1);
+		let old = self.selection;
+		self.selection = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => count - 1,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(count - 1);
+
+		old != self.selection
+	}
+
+	fn problem_to_line<'a>(
+		repo_id: &'a str,
+		repo_path: &Path,
+		problem: &Problem,
+		selected: bool,
+		theme: &SharedTheme,
+	) -> Line<'a> {
+		// relative to the cache root
+		let path = repo_path
+			.parent()
+			.and_then(|root| problem.path.strip_prefix(root).ok())
+			.unwrap_or(&problem.path);
+
+		Line::from(vec![
+			Span::styled(
+				format!(
+					"{:KIND_WIDTH$}",
+					strings::problem_kind(problem.kind)
+				),
+				theme.text_danger(),
+			),
+			Span::styled(
+				format!("{repo_id}  {}", path.display()),
+				theme.text(true, selected),
+			),
+		])
+	}
+}
+
+impl DrawableComponent for ProblemsPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if !self.visible {
+			return Ok(());
+		}
+
+		let area = ui::centered_rect(80, 70, rect);
+		let height = usize::from(area.height.saturating_sub(2));
+		self.current_height.set(height);
+		self.scroll.update(self.selection, self.rows.len(), height);
+
+		f.render_widget(Clear, area);
+
+		let block = Block::default()
+			.title(Span::styled(
+				strings::title_problems(self.rows.len()),
+				self.theme.title(true),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(true));
+
+		if self.rows.is_empty() {
+			ui::draw_list_block(
+				f,
+				area,
+				block,
+				std::iter::once(Span::styled(
+					Cow::from(strings::problems_empty()),
+					self.theme.text(false, false),
+				)),
+			);
+		} else {
+			let items = self
+				.rows
+				.iter()
+				.enumerate()
+				.skip(self.scroll.get_top())
+				.take(height)
+				.map(|(idx, (repo_idx, problem_idx))| {
+					let repo = &self.cache.repos[*repo_idx];
+					Self::problem_to_line(
+						&repo.repo_id,
+						&repo.path,
+						&repo.problems[*problem_idx],
+						idx == self.selection,
+						&self.theme,
+					)
+				});
+
+			ui::draw_list_block(f, area, block, items);
+		}
+
+		self.scroll.draw(f, area, &self.theme);
+
+		Ok(())
+	}
+}
+
+impl Component for ProblemsPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					!self.rows.is_empty(),
+					true,
+				)
+				.order(order::NAV),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			if key_match(key, keys.exit_popup)
+				|| key_match(key, keys.open_problems)
+			{
+				self.hide();
+			} else if key_match(key, keys.move_up) {
+				self.move_selection(ScrollType::Up);
+			} else if key_match(key, keys.move_down) {
+				self.move_selection(ScrollType::Down);
+			} else if key_match(key, keys.home)
+				|| key_match(key, keys.shift_up)
+			{
+				self.move_selection(ScrollType::Home);
+			} else if key_match(key, keys.end)
+				|| key_match(key, keys.shift_down)
+			{
+				self.move_selection(ScrollType::End);
+			} else if key_match(key, keys.page_up) {
+				self.move_selection(ScrollType::PageUp);
+			} else if key_match(key, keys.page_down) {
+				self.move_selection(ScrollType::PageDown);
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
@@ -0,0 +1,279 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState, ScrollType, VerticalScroll,
+	},
+	hub::RevisionRef,
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear},
+	Frame,
+};
+use std::cell::Cell;
+use unicode_width::UnicodeWidthStr;
+
+
+/// revisions of a repo to pick from, see `InternalEvent::OpenRevisions`
+pub struct RevisionsOpen {
+	pub repo_id: String,
+	pub refs: Vec<RevisionRef>,
+	/// revision shown in the file tree, selected initially
+	pub current: Option<RevisionRef>,
+	/// `repo@commit` to compare the picked revision with,
+	/// `None` to show the picked revision
+	pub compare: Option<String>,
+}
+
+/// lists the refs and detached snapshots of a repo
+/// to switch the file tree to one of them
+pub struct RevisionsPopup {
+	visible: bool,
+	repo_id: String,
+	refs: Vec<RevisionRef>,
+	compare: Option<String>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl RevisionsPopup {
+	/// creates the hidden popup
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			repo_id: String::new(),
+			refs: Vec::new(),
+			compare: None,
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			queue: env.queue.clone(),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// shows the revisions of `open`
+	pub fn open(&mut self, open: RevisionsOpen) -> Result<()> {
+		self.selection = open
+			.current
+			.and_then(|current| {
+				open.refs.iter().position(|r| *r == current)
+			})
+			.unwrap_or_default();
+		self.repo_id = open.repo_id;
+		self.refs = open.refs;
+		self.compare = open.compare;
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, scroll: ScrollType) -> bool {
+		let count = self.refs.len();
+		if count == 0 {
+			return false;
+		}
+
+		let page = self.current_height.get().saturating_sub(1).max(1);
+		let old = self.selection;
+		self.selection = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => count - 1,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(count - 1);
+
+		old != self.selection
+	}
+
+	fn pick(&mut self) {
+		if let Some(revision) = self.refs.get(self.selection) {
+			self.queue.push(if self.compare.is_some() {
+				InternalEvent::CompareRevision(revision.clone())
+			} else {
+				InternalEvent::SelectRevision(revision.clone())
+			});
+			self.hide();
+		}
+	}
+
+	fn ref_to_line(
+		&self,
+		revision: &RevisionRef,
+		name_width: usize,
+		selected: bool,
+	) -> Line<'static> {
+		let hash = strings::short_hash(&revision.commit_hash);
+		let (name, name_style, hash_style) = match &revision.name {
+			Some(name) => (
+				name.clone(),
+				self.theme.text(true, selected),
+				self.theme.commit_hash(selected),
+			),
+			None => (
+				strings::refs_detached(),
+				self.theme.text(false, selected),
+				self.theme.text(false, selected),
+			),
+		};
+
+		Line::from(vec![
+			Span::styled(format!("{name:name_width$}  "), name_style),
+			Span::styled(hash, hash_style),
+		])
+	}
+}
+
+impl DrawableComponent for RevisionsPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if !self.visible {
+			return Ok(());
+		}
+
+		let area = ui::centered_rect(50, 50, rect);
+		let height = usize::from(area.height.saturating_sub(2));
+		self.current_height.set(height);
+		self.scroll.update(self.selection, self.refs.len(), height);
+
+		f.render_widget(Clear, area);
+
+		let block = Block::default()
+			.title(Span::styled(
+				self.compare.as_deref().map_or_else(
+					|| strings::title_revisions(&self.repo_id),
+					strings::title_compare_with,
+				),
+				self.theme.title(true),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(true));
+
+		let name_width = self
+			.refs
+			.iter()
+			.map(|r| {
+				r.name.as_deref().map_or(0, UnicodeWidthStr::width)
+			})
+			.max()
+			.unwrap_or_default()
+			.max(strings::refs_detached().width());
+
+		let items = self
+			.refs
+			.iter()
+			.enumerate()
+			.skip(self.scroll.get_top())
+			.take(height)
+			.map(|(idx, revision)| {
+				self.ref_to_line(
+					revision,
+					name_width,
+					idx == self.selection,
+				)
+			});
+
+		ui::draw_list_block(f, area, block, items);
+		self.scroll.draw(f, area, &self.theme);
+
+		Ok(())
+	}
+}
+
+impl Component for RevisionsPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					!self.refs.is_empty(),
+					true,
+				)
+				.order(order::NAV),
+			);
+			out.push(CommandInfo::new(
+				if self.compare.is_some() {
+					strings::commands::compare_revision(&self.key_config)
+				} else {
+					strings::commands::show_revision(&self.key_config)
+				},
+				!self.refs.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			if key_match(key, keys.exit_popup)
+				|| key_match(key, keys.select_revision)
+				|| key_match(key, keys.compare_commits)
+			{
+				self.hide();
+			} else if key_match(key, keys.enter) {
+				self.pick();
+			} else if key_match(key, keys.move_up) {
+				self.move_selection(ScrollType::Up);
+			} else if key_match(key, keys.move_down) {
+				self.move_selection(ScrollType::Down);
+			} else if key_match(key, keys.home)
+				|| key_match(key, keys.shift_up)
+			{
+				self.move_selection(ScrollType::Home);
+			} else if key_match(key, keys.end)
+				|| key_match(key, keys.shift_down)
+			{
+				self.move_selection(ScrollType::End);
+			} else if key_match(key, keys.page_up) {
+				self.move_selection(ScrollType::PageUp);
+			} else if key_match(key, keys.page_down) {
+				self.move_selection(ScrollType::PageDown);
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
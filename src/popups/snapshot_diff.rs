@@ -0,0 +1,470 @@
+use crate::{
+	app::Environment,
+	components::{
+		bytes_delta_to_string, is_binary, read_head, visibility_blocking,
+		CommandBlocking, CommandInfo, Component, DrawableComponent,
+		EventState, ScrollType, VerticalScroll,
+	},
+	hub::{diff_revisions, CachedRevision, ChangeKind, FileChange},
+	keys::{key_match, SharedKeyConfig},
+	string_utils::tabs_to_spaces,
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+use similar::{ChangeTag, TextDiff};
+use std::{borrow::Cow, cell::Cell, path::PathBuf};
+
+/// files larger than this are not diffed line by line
+const MAX_DIFF_SIZE: u64 = 512 * 1024;
+/// unchanged lines shown around every change
+const CONTEXT_LINES: usize = 3;
+/// width of the size delta column
+const DELTA_WIDTH: usize = 10;
+
+/// two snapshots of a repo to compare, see `InternalEvent::OpenSnapshotDiff`
+pub struct SnapshotDiffOpen {
+	/// `repo@old..new` shown in the title
+	pub title: String,
+	/// snapshot folder of the older revision
+	pub old: PathBuf,
+	/// snapshot folder of the newer revision
+	pub new: PathBuf,
+	pub changes: Vec<FileChange>,
+}
+
+impl SnapshotDiffOpen {
+	/// compares the snapshots `old` and `new` of the repo `repo_id`
+	pub fn new(
+		repo_id: &str,
+		old: &CachedRevision,
+		new: &CachedRevision,
+	) -> Self {
+		Self {
+			title: format!(
+				"{repo_id}@{}..{}",
+				strings::short_hash(&old.commit_hash),
+				strings::short_hash(&new.commit_hash)
+			),
+			old: old.path.clone(),
+			new: new.path.clone(),
+			changes: diff_revisions(old, new),
+		}
+	}
+}
+
+/// files added, removed and modified between two snapshots
+/// next to a line diff of the selected text file
+pub struct SnapshotDiffPopup {
+	visible: bool,
+	title: String,
+	old: PathBuf,
+	new: PathBuf,
+	changes: Vec<FileChange>,
+	selection: usize,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	/// line diff of the selected change, or why there is none
+	diff: Result<Vec<Line<'static>>, String>,
+	diff_scroll: usize,
+	diff_height: Cell<usize>,
+	diff_focused: bool,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl SnapshotDiffPopup {
+	/// creates the hidden popup
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			title: String::new(),
+			old: PathBuf::new(),
+			new: PathBuf::new(),
+			changes: Vec::new(),
+			selection: 0,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			diff: Ok(Vec::new()),
+			diff_scroll: 0,
+			diff_height: Cell::new(0),
+			diff_focused: false,
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// shows the changes of `open`
+	pub fn open(&mut self, open: SnapshotDiffOpen) -> Result<()> {
+		self.title = open.title;
+		self.old = open.old;
+		self.new = open.new;
+		self.changes = open.changes;
+		self.selection = 0;
+		self.diff_focused = false;
+		self.selection_changed();
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, scroll: ScrollType) -> bool {
+		let count = self.changes.len();
+		if count == 0 {
+			return false;
+		}
+
+		let page = self.current_height.get().saturating_sub(1).max(1);
+		let old = self.selection;
+		self.selection = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => count - 1,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(count - 1);
+
+		if old == self.selection {
+			return false;
+		}
+
+		self.selection_changed();
+		true
+	}
+
+	fn scroll_diff(&mut self, scroll: ScrollType) -> bool {
+		let lines = self.diff.as_ref().map_or(0, Vec::len);
+		let page = self.diff_height.get().saturating_sub(1).max(1);
+		let max = lines.saturating_sub(self.diff_height.get());
+
+		let old = self.diff_scroll;
+		self.diff_scroll = match scroll {
+			ScrollType::Up => old.saturating_sub(1),
+			ScrollType::Down => old.saturating_add(1),
+			ScrollType::Home => 0,
+			ScrollType::End => max,
+			ScrollType::PageUp => old.saturating_sub(page),
+			ScrollType::PageDown => old.saturating_add(page),
+		}
+		.min(max);
+
+		old != self.diff_scroll
+	}
+
+	fn selection_changed(&mut self) {
+		self.diff_scroll = 0;
+		self.diff = self.changes.get(self.selection).map_or_else(
+			|| Ok(Vec::new()),
+			|change| self.line_diff(change),
+		);
+	}
+
+	/// text of the file at `path`, empty for `None`
+	fn read_text(path: Option<PathBuf>) -> Result<String, String> {
+		let Some(path) = path else {
+			return Ok(String::new());
+		};
+
+		match read_head(&path, MAX_DIFF_SIZE) {
+			Ok((_, true)) => Err(strings::diff_too_large()),
+			Ok((content, _)) if is_binary(&content) => {
+				Err(strings::diff_binary())
+			}
+			Ok((content, _)) => Ok(tabs_to_spaces(
+				String::from_utf8_lossy(&content).into_owned(),
+			)),
+			Err(e) => Err(strings::code_load_error(&e.to_string())),
+		}
+	}
+
+	fn line_diff(
+		&self,
+		change: &FileChange,
+	) -> Result<Vec<Line<'static>>, String> {
+		let old = Self::read_text(
+			(change.kind != ChangeKind::Added)
+				.then(|| self.old.join(&change.path)),
+		)?;
+		let new = Self::read_text(
+			(change.kind != ChangeKind::Removed)
+				.then(|| self.new.join(&change.path)),
+		)?;
+
+		let diff = TextDiff::from_lines(&old, &new);
+		let mut lines = Vec::new();
+
+		for (idx, group) in
+			diff.grouped_ops(CONTEXT_LINES).iter().enumerate()
+		{
+			if idx > 0 {
+				lines.push(Line::styled(
+					strings::diff_hunk_separator(),
+					self.theme.diff_hunk_marker(false),
+				));
+			}
+
+			for op in group {
+				for line in diff.iter_changes(op) {
+					let sign = match line.tag() {
+						ChangeTag::Insert => '+',
+						ChangeTag::Delete => '-',
+						ChangeTag::Equal => ' ',
+					};
+					lines.push(Line::styled(
+						format!(
+							"{sign}{}",
+							line.value().trim_end_matches(['\r', '\n'])
+						),
+						self.theme.diff_line(line.tag()),
+					));
+				}
+			}
+		}
+
+		if lines.is_empty() {
+			return Err(strings::diff_no_line_changes());
+		}
+
+		Ok(lines)
+	}
+
+	fn change_to_line(
+		&self,
+		change: &FileChange,
+		width: usize,
+		selected: bool,
+	) -> Line<'static> {
+		let path = change.path.to_string_lossy();
+		let path_width = width.saturating_sub(2 + DELTA_WIDTH);
+		let path = strings::ellipsis_trim_start(&path, path_width);
+		let delta = bytes_delta_to_string(change.size_delta());
+
+		Line::styled(
+			format!(
+				"{} {path:path_width$}{delta:>DELTA_WIDTH$}",
+				strings::change_kind_symbol(change.kind),
+			),
+			self.theme.file_change(change.kind, selected),
+		)
+	}
+
+	fn draw_changes(&self, f: &mut Frame, area: Rect) {
+		let height = usize::from(area.height.saturating_sub(2));
+		let width = usize::from(area.width.saturating_sub(2));
+		self.current_height.set(height);
+		self.scroll.update(self.selection, self.changes.len(), height);
+
+		let block = Block::default()
+			.title(Span::styled(
+				strings::title_snapshot_diff(
+					&self.title,
+					self.changes.len(),
+				),
+				self.theme.title(!self.diff_focused),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(!self.diff_focused));
+
+		if self.changes.is_empty() {
+			ui::draw_list_block(
+				f,
+				area,
+				block,
+				std::iter::once(Span::styled(
+					Cow::from(strings::snapshot_diff_empty()),
+					self.theme.text(false, false),
+				)),
+			);
+		} else {
+			let items = self
+				.changes
+				.iter()
+				.enumerate()
+				.skip(self.scroll.get_top())
+				.take(height)
+				.map(|(idx, change)| {
+					self.change_to_line(
+						change,
+						width,
+						idx == self.selection,
+					)
+				});
+
+			ui::draw_list_block(f, area, block, items);
+		}
+
+		if !self.diff_focused {
+			self.scroll.draw(f, area, &self.theme);
+		}
+	}
+
+	fn draw_diff(&self, f: &mut Frame, area: Rect) {
+		self.diff_height
+			.set(usize::from(area.height.saturating_sub(2)));
+
+		let title = self.changes.get(self.selection).map_or_else(
+			String::new,
+			|change| change.path.to_string_lossy().into_owned(),
+		);
+		let block = Block::default()
+			.title(Span::styled(
+				title,
+				self.theme.title(self.diff_focused),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(self.diff_focused));
+
+		let paragraph = match &self.diff {
+			Ok(lines) => Paragraph::new(lines.clone()).scroll((
+				u16::try_from(self.diff_scroll).unwrap_or(u16::MAX),
+				0,
+			)),
+			Err(msg) => Paragraph::new(Span::styled(
+				msg.as_str(),
+				self.theme.text(false, false),
+			)),
+		};
+
+		f.render_widget(paragraph.block(block), area);
+
+		if self.diff_focused {
+			ui::draw_scrollbar(
+				f,
+				area,
+				&self.theme,
+				self.diff.as_ref().map_or(0, |lines| {
+					lines.len().saturating_sub(self.diff_height.get())
+				}),
+				self.diff_scroll,
+				ui::Orientation::Vertical,
+			);
+		}
+	}
+}
+
+impl DrawableComponent for SnapshotDiffPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if !self.visible {
+			return Ok(());
+		}
+
+		let area = ui::centered_rect(90, 90, rect);
+		f.render_widget(Clear, area);
+
+		let chunks = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints([
+				Constraint::Percentage(40),
+				Constraint::Percentage(60),
+			])
+			.split(area);
+
+		self.draw_changes(f, chunks[0]);
+		self.draw_diff(f, chunks[1]);
+
+		Ok(())
+	}
+}
+
+impl Component for SnapshotDiffPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					!self.changes.is_empty(),
+					true,
+				)
+				.order(order::NAV),
+			);
+			out.push(CommandInfo::new(
+				if self.diff_focused {
+					strings::commands::select_changes(&self.key_config)
+				} else {
+					strings::commands::select_diff(&self.key_config)
+				},
+				self.diff_focused || self.diff.is_ok(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			let scroll = if key_match(key, keys.move_up) {
+				Some(ScrollType::Up)
+			} else if key_match(key, keys.move_down) {
+				Some(ScrollType::Down)
+			} else if key_match(key, keys.home)
+				|| key_match(key, keys.shift_up)
+			{
+				Some(ScrollType::Home)
+			} else if key_match(key, keys.end)
+				|| key_match(key, keys.shift_down)
+			{
+				Some(ScrollType::End)
+			} else if key_match(key, keys.page_up) {
+				Some(ScrollType::PageUp)
+			} else if key_match(key, keys.page_down) {
+				Some(ScrollType::PageDown)
+			} else {
+				None
+			};
+
+			if key_match(key, keys.exit_popup)
+				|| key_match(key, keys.compare_commits)
+			{
+				self.hide();
+			} else if key_match(key, keys.move_right) {
+				self.diff_focused = self.diff.is_ok();
+			} else if key_match(key, keys.move_left) {
+				self.diff_focused = false;
+			} else if let Some(scroll) = scroll {
+				if self.diff_focused {
+					self.scroll_diff(scroll);
+				} else {
+					self.move_selection(scroll);
+				}
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
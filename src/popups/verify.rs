@@ -0,0 +1,252 @@
+use crate::{
+	app::Environment,
+	asyncjob::AsyncSingleJob,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState, ScrollType, VerticalScroll,
+	},
+	hub::{AsyncHubNotification, AsyncVerifyJob, VerifyItem, VerifyResult},
+	keys::{key_match, SharedKeyConfig},
+	strings::{self, order},
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Gauge},
+	Frame,
+};
+use std::cell::Cell;
+
+/// what to verify, see `InternalEvent::OpenVerify`
+pub struct VerifyOpen {
+	/// names the verified repo, revision or file
+	pub title: String,
+	pub items: Vec<VerifyItem>,
+	/// files that cannot be verified
+	pub skipped: usize,
+}
+
+/// rehashes blobs showing a gauge and then the mismatches found
+pub struct VerifyPopup {
+	visible: bool,
+	title: String,
+	blobs: usize,
+	job: AsyncSingleJob<AsyncVerifyJob>,
+	result: Option<VerifyResult>,
+	scroll: VerticalScroll,
+	current_height: Cell<usize>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl VerifyPopup {
+	/// creates the hidden popup
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			title: String::new(),
+			blobs: 0,
+			job: AsyncSingleJob::new(env.sender_hub.clone()),
+			result: None,
+			scroll: VerticalScroll::new(),
+			current_height: Cell::new(0),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// starts verifying `open.items`
+	pub fn open(&mut self, open: VerifyOpen) -> Result<()> {
+		self.title = open.title;
+		self.blobs = open.items.len();
+		self.result = None;
+		self.scroll.reset();
+		self.job.spawn(AsyncVerifyJob::new(open.items, open.skipped));
+
+		self.show()
+	}
+
+	/// true while blobs are hashed
+	pub fn any_work_pending(&self) -> bool {
+		self.job.is_pending()
+	}
+
+	/// picks up the result of a finished job
+	pub fn update_hub(&mut self, ev: AsyncHubNotification) {
+		if ev == AsyncHubNotification::Verify {
+			if let Some(result) =
+				self.job.take_last().and_then(|job| job.take_result())
+			{
+				self.result = Some(result);
+			}
+		}
+	}
+
+	fn result_lines(result: &VerifyResult) -> Vec<String> {
+		let mut lines = vec![strings::verify_summary(result)];
+
+		for mismatch in &result.mismatches {
+			lines.push(mismatch.path.display().to_string());
+			lines.extend(
+				strings::verify_mismatch(
+					&mismatch.expected,
+					&mismatch.actual,
+				)
+				.lines()
+				.map(String::from),
+			);
+		}
+
+		for (path, error) in &result.errors {
+			lines.push(format!("{}: {error}", path.display()));
+		}
+
+		lines
+	}
+
+	fn draw_gauge(&self, f: &mut Frame, area: Rect) {
+		let progress = self.job.progress().unwrap_or_default().progress;
+
+		f.render_widget(
+			Gauge::default()
+				.label(strings::verify_progress(self.blobs))
+				.block(
+					Block::default()
+						.title(Span::styled(
+							strings::title_verify(&self.title),
+							self.theme.title(true),
+						))
+						.borders(Borders::ALL)
+						.border_type(BorderType::Thick)
+						.border_style(self.theme.block(true)),
+				)
+				.gauge_style(self.theme.push_gauge())
+				.percent(u16::from(progress)),
+			area,
+		);
+	}
+
+	fn draw_result(&self, f: &mut Frame, area: Rect, result: &VerifyResult) {
+		let lines = Self::result_lines(result);
+		let height = usize::from(area.height.saturating_sub(2));
+		self.current_height.set(height);
+		self.scroll.update_no_selection(lines.len(), height);
+
+		let failed = !result.mismatches.is_empty() || !result.errors.is_empty();
+		let style = if failed {
+			self.theme.text_danger()
+		} else {
+			self.theme.text(true, false)
+		};
+
+		let block = Block::default()
+			.title(Span::styled(
+				strings::title_verify(&self.title),
+				self.theme.title(true),
+			))
+			.borders(Borders::ALL)
+			.border_style(self.theme.block(true));
+
+		ui::draw_list_block(
+			f,
+			area,
+			block,
+			lines
+				.into_iter()
+				.skip(self.scroll.get_top())
+				.take(height)
+				.map(|line| Span::styled(line, style)),
+		);
+
+		self.scroll.draw(f, area, &self.theme);
+	}
+}
+
+impl DrawableComponent for VerifyPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if !self.visible {
+			return Ok(());
+		}
+
+		if let Some(result) = &self.result {
+			let area = ui::centered_rect(70, 50, rect);
+			f.render_widget(Clear, area);
+			self.draw_result(f, area, result);
+		} else {
+			let area = ui::rect_inside(
+				ui::Size::new(30, 3),
+				ui::Size::new(rect.width.saturating_sub(4), 3),
+				ui::centered_rect(60, 20, rect),
+			);
+			f.render_widget(Clear, area);
+			self.draw_gauge(f, area);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for VerifyPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					self.result.is_some(),
+					true,
+				)
+				.order(order::NAV),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			if key_match(key, keys.exit_popup) {
+				self.hide();
+			} else if key_match(key, keys.move_up) {
+				self.scroll.move_top(ScrollType::Up);
+			} else if key_match(key, keys.move_down) {
+				self.scroll.move_top(ScrollType::Down);
+			} else if key_match(key, keys.page_up) {
+				self.scroll.move_top(ScrollType::PageUp);
+			} else if key_match(key, keys.page_down) {
+				self.scroll.move_top(ScrollType::PageDown);
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
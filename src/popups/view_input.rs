@@ -0,0 +1,232 @@
+use crate::{
+	app::Environment,
+	components::{
+		hex::{parse_offset, parse_pattern},
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState, ViewJump,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// width of the popup
+const POPUP_WIDTH: u16 = 50;
+
+/// what a view asks for, see `InternalEvent::OpenViewInput`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewInputKind {
+	Offset,
+	Pattern,
+	/// text searched for again on every edit
+	Text,
+}
+
+/// asks for an offset to jump to or a byte pattern to search
+/// in the hex view, or for text to search in the stream view
+pub struct ViewInputPopup {
+	visible: bool,
+	kind: ViewInputKind,
+	text: String,
+	invalid: bool,
+	queue: Queue,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl ViewInputPopup {
+	/// creates the hidden popup
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			kind: ViewInputKind::Offset,
+			text: String::new(),
+			invalid: false,
+			queue: env.queue.clone(),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// shows an empty input for `kind`
+	pub fn open(&mut self, kind: ViewInputKind) -> Result<()> {
+		self.kind = kind;
+		self.text.clear();
+		self.invalid = false;
+
+		self.show()
+	}
+
+	fn edited(&mut self) {
+		self.invalid = false;
+
+		if self.kind == ViewInputKind::Text {
+			self.queue.push(InternalEvent::ViewJump(ViewJump::Text(
+				self.text.clone(),
+			)));
+		}
+	}
+
+	fn confirm(&mut self) {
+		let jump = match self.kind {
+			ViewInputKind::Offset => {
+				parse_offset(&self.text).map(ViewJump::Offset)
+			}
+			ViewInputKind::Pattern => {
+				parse_pattern(&self.text).map(ViewJump::Bytes)
+			}
+			// already searched while typing
+			ViewInputKind::Text => {
+				self.hide();
+				return;
+			}
+		};
+
+		match jump {
+			Some(jump) => {
+				self.queue.push(InternalEvent::ViewJump(jump));
+				self.hide();
+			}
+			None => self.invalid = true,
+		}
+	}
+
+	fn hint(&self) -> Span<'static> {
+		match (self.kind, self.invalid) {
+			(ViewInputKind::Offset, false) => Span::styled(
+				strings::hint_offset(),
+				self.theme.text(false, false),
+			),
+			(ViewInputKind::Pattern, false) => Span::styled(
+				strings::hint_pattern(),
+				self.theme.text(false, false),
+			),
+			(ViewInputKind::Offset, true) => Span::styled(
+				strings::invalid_offset(),
+				self.theme.text_danger(),
+			),
+			(ViewInputKind::Pattern, true) => Span::styled(
+				strings::invalid_pattern(),
+				self.theme.text_danger(),
+			),
+			(ViewInputKind::Text, _) => Span::styled(
+				strings::hint_text(),
+				self.theme.text(false, false),
+			),
+		}
+	}
+}
+
+impl DrawableComponent for ViewInputPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if !self.visible {
+			return Ok(());
+		}
+
+		let area = ui::centered_rect_absolute(POPUP_WIDTH, 4, rect);
+		f.render_widget(Clear, area);
+
+		let title = match self.kind {
+			ViewInputKind::Offset => strings::title_goto_offset(),
+			ViewInputKind::Pattern => strings::title_search_bytes(),
+			ViewInputKind::Text => strings::title_search_text(),
+		};
+
+		f.render_widget(
+			Paragraph::new(vec![
+				Line::from(vec![
+					Span::styled("> ", self.theme.text(false, false)),
+					Span::styled(
+						self.text.as_str(),
+						self.theme.text(true, false),
+					),
+					Span::styled("_", self.theme.text(false, false)),
+				]),
+				Line::from(self.hint()),
+			])
+			.block(
+				Block::default()
+					.title(Span::styled(title, self.theme.title(true)))
+					.borders(Borders::ALL)
+					.border_style(self.theme.block(true)),
+			),
+			area,
+		);
+
+		Ok(())
+	}
+}
+
+impl Component for ViewInputPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			out.push(CommandInfo::new(
+				strings::commands::confirm_input(&self.key_config),
+				!self.text.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if let Event::Key(key) = ev {
+			match key.code {
+				KeyCode::Char(c)
+					if (key.modifiers - KeyModifiers::SHIFT).is_empty() =>
+				{
+					self.text.push(c);
+					self.edited();
+				}
+				KeyCode::Backspace if self.text.pop().is_some() => {
+					self.edited();
+				}
+				_ if key_match(key, self.key_config.keys.exit_popup) => {
+					self.hide();
+				}
+				_ if key_match(key, self.key_config.keys.enter) => {
+					self.confirm();
+				}
+				_ => {}
+			}
+		}
+
+		Ok(EventState::Consumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
@@ -0,0 +1,35 @@
+//! custom progress
+
+use easy_cast::{Conv, ConvFloat};
+use std::cmp;
+
+/// percentage of progress
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct ProgressPercent {
+	/// percent 0..100
+	pub progress: u8,
+}
+
+impl ProgressPercent {
+	/// `current` out of `total`, a zero `total` counts as done
+	pub fn new(current: usize, total: usize) -> Self {
+		let total = f64::conv(cmp::max(current, total));
+		let progress = f64::conv(current) / total * 100.0;
+		let progress = u8::try_conv_nearest(progress).unwrap_or(100);
+		Self { progress }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_progress_percent() {
+		assert_eq!(ProgressPercent::new(0, 4).progress, 0);
+		assert_eq!(ProgressPercent::new(1, 3).progress, 33);
+		assert_eq!(ProgressPercent::new(4, 4).progress, 100);
+		assert_eq!(ProgressPercent::new(0, 0).progress, 100);
+	}
+}
@@ -1,3 +1,11 @@
+use crate::{
+	components::ViewJump,
+	hub::RevisionRef,
+	popups::{
+		ConvertOpen, FoundFile, ViewInputKind, RevisionsOpen,
+		SnapshotDiffOpen, VerifyOpen,
+	},
+};
 // use crate::{
 // 	components::FuzzyFinderTarget,
 // 	popups::{
@@ -77,12 +85,32 @@ pub enum AppTabs {
 	Status,
 	// Log,
 	Files,
+	Datasets,
+	Modules,
 	// Stashing,
 	// Stashlist,
 }
 
 
-// pub enum InternalEvent {
+pub enum InternalEvent {
+	/// verify blobs and show the outcome
+	OpenVerify(VerifyOpen),
+	/// ask to link the copied files of a repo
+	OpenConvert(ConvertOpen),
+	/// pick one of the revisions of a repo
+	OpenRevisions(RevisionsOpen),
+	/// show the files of the picked revision
+	SelectRevision(RevisionRef),
+	/// compare the revision shown in the file tree with the picked one
+	CompareRevision(RevisionRef),
+	/// show the changes between two snapshots
+	OpenSnapshotDiff(SnapshotDiffOpen),
+	/// show a file picked in the fuzzy finder in the files tab
+	OpenFoundFile(FoundFile),
+	/// ask for an offset, byte pattern or text for the focused view
+	OpenViewInput(ViewInputKind),
+	/// move the focused hex or text view to an offset or the next match
+	ViewJump(ViewJump),
 // 	///
 // 	ConfirmAction(Action),
 // 	///
@@ -157,30 +185,30 @@ pub enum AppTabs {
 // 	RewordCommit(CommitId),
 // 	///
 // 	CommitSearch(LogFilterSearchOptions),
-// }
+}
 
-// /// single threaded simple queue for components to communicate with each other
-// #[derive(Clone, Default)]
-// pub struct Queue {
-// 	data: Rc<RefCell<VecDeque<InternalEvent>>>,
-// }
+/// single threaded simple queue for components to communicate with each other
+#[derive(Clone, Default)]
+pub struct Queue {
+	data: Rc<RefCell<VecDeque<InternalEvent>>>,
+}
 
-// impl Queue {
-// 	pub fn new() -> Self {
-// 		Self {
-// 			data: Rc::new(RefCell::new(VecDeque::new())),
-// 		}
-// 	}
+impl Queue {
+	pub fn new() -> Self {
+		Self {
+			data: Rc::new(RefCell::new(VecDeque::new())),
+		}
+	}
 
-// 	pub fn push(&self, ev: InternalEvent) {
-// 		self.data.borrow_mut().push_back(ev);
-// 	}
+	pub fn push(&self, ev: InternalEvent) {
+		self.data.borrow_mut().push_back(ev);
+	}
 
-// 	pub fn pop(&self) -> Option<InternalEvent> {
-// 		self.data.borrow_mut().pop_front()
-// 	}
+	pub fn pop(&self) -> Option<InternalEvent> {
+		self.data.borrow_mut().pop_front()
+	}
 
-// 	pub fn clear(&self) {
-// 		self.data.borrow_mut().clear();
-// 	}
-// }
+	// pub fn clear(&self) {
+	// 	self.data.borrow_mut().clear();
+	// }
+}
@@ -4,7 +4,16 @@ use std::borrow::Cow;
 use unicode_truncate::UnicodeTruncateStr;
 use unicode_width::UnicodeWidthStr;
 
-use crate::keys::SharedKeyConfig;
+use crate::{
+	args::CacheRoot,
+	components::BlobFormat,
+	hub::{
+		ChangeKind, ConvertResult, FileKind, ProblemKind, RepoType,
+		VerifyResult,
+	},
+	keys::SharedKeyConfig,
+	modules::ModuleLink,
+};
 
 pub mod order {
 	pub const RARE_ACTION: i8 = 30;
@@ -35,6 +44,9 @@ pub static POPUP_SUBTITLE_REMOTES: &str = "Details";
 pub static POPUP_TITLE_FUZZY_FIND: &str = "Fuzzy Finder";
 pub static POPUP_TITLE_LOG_SEARCH: &str = "Search";
 
+/// chars of a commit or build hash shown in lists and titles
+pub const HASH_LENGTH: usize = 8;
+
 pub static POPUP_FAIL_COPY: &str = "Failed to copy text";
 pub static POPUP_SUCCESS_COPY: &str = "Copied Text";
 pub static POPUP_COMMIT_SHA_INVALID: &str = "Invalid commit sha";
@@ -47,6 +59,13 @@ pub mod symbol {
 	pub const FOLDER_ICON_EXPANDED: &str = "\u{25be}"; //▾
 	pub const EMPTY_STR: &str = "";
 	pub const ELLIPSIS: char = '\u{2026}'; // …
+	pub const REPO_MODEL: &str = "\u{25c6}"; //◆
+	pub const REPO_DATASET: &str = "\u{25a4}"; //▤
+	pub const REPO_SPACE: &str = "\u{25b2}"; //▲
+	pub const SORT_ASCENDING: &str = "\u{2191}"; //↑
+	pub const SORT_DESCENDING: &str = "\u{2193}"; //↓
+	pub const BAR_FILLED: &str = "#";
+	pub const BAR_EMPTY: &str = " ";
 }
 
 pub fn title_branches() -> String {
@@ -79,6 +98,18 @@ pub fn tab_files(key_config: &SharedKeyConfig) -> String {
 		key_config.get_hint(key_config.keys.tab_files)
 	)
 }
+pub fn tab_datasets(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"Datasets [{}]",
+		key_config.get_hint(key_config.keys.tab_datasets)
+	)
+}
+pub fn tab_modules(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"Modules [{}]",
+		key_config.get_hint(key_config.keys.tab_modules)
+	)
+}
 pub fn tab_stashing(key_config: &SharedKeyConfig) -> String {
 	format!(
 		"Stashing [{}]",
@@ -106,6 +137,19 @@ pub fn msg_title_error(_key_config: &SharedKeyConfig) -> String {
 pub fn msg_title_info(_key_config: &SharedKeyConfig) -> String {
 	"Info".to_string()
 }
+pub fn msg_title_warning() -> String {
+	"Warning".to_string()
+}
+pub fn low_free_space(root: &CacheRoot, free: &str, floor: &str) -> String {
+	format!(
+		"only {free} free on the file system of {} ({}), below the floor of {floor}",
+		root.path.display(),
+		root.label
+	)
+}
+pub fn budget_gauge(used: &str, budget: &str) -> String {
+	format!("{used} / {budget}")
+}
 pub fn commit_title() -> String {
 	"Commit".to_string()
 }
@@ -342,6 +386,374 @@ pub fn confirm_msg_undo_commit() -> String {
 pub fn loading_text(_key_config: &SharedKeyConfig) -> String {
 	"Loading ...".to_string()
 }
+pub fn cache_roots(roots: &[CacheRoot]) -> String {
+	roots
+		.iter()
+		.map(|root| {
+			format!(
+				"{}{}: {} ({})",
+				root.label,
+				if root.writable { "" } else { " ro" },
+				root.path.display(),
+				root.source
+			)
+		})
+		.collect::<Vec<_>>()
+		.join(" | ")
+}
+/// label of a repo's root, read-only roots are marked
+pub fn root_tag(root: &CacheRoot) -> String {
+	if root.writable {
+		format!("[{}]", root.label)
+	} else {
+		format!("[{} ro]", root.label)
+	}
+}
+/// marker of repos and revisions cached in more than one root
+pub fn duplicate_badge(roots: usize) -> String {
+	if roots > 1 {
+		format!("{roots}x")
+	} else {
+		String::new()
+	}
+}
+pub fn title_repos(
+	count: usize,
+	filter: Option<RepoType>,
+	scan_percent: Option<u8>,
+) -> String {
+	let filter = filter.map_or_else(String::new, |t| {
+		format!(" {}", repo_type_name(t))
+	});
+
+	scan_percent.map_or_else(
+		|| format!("Repositories ({count}){filter}"),
+		|percent| {
+			format!("Repositories ({count}){filter} scanning {percent}%")
+		},
+	)
+}
+pub const fn repo_type_icon(repo_type: RepoType) -> &'static str {
+	match repo_type {
+		RepoType::Model => symbol::REPO_MODEL,
+		RepoType::Dataset => symbol::REPO_DATASET,
+		RepoType::Space => symbol::REPO_SPACE,
+	}
+}
+pub const fn repo_type_name(repo_type: RepoType) -> &'static str {
+	match repo_type {
+		RepoType::Model => "models",
+		RepoType::Dataset => "datasets",
+		RepoType::Space => "spaces",
+	}
+}
+pub fn repo_table_header() -> [String; 8] {
+	[
+		"repo".to_string(),
+		"type".to_string(),
+		"size".to_string(),
+		"revs".to_string(),
+		"files".to_string(),
+		"refs".to_string(),
+		"modified".to_string(),
+		"accessed".to_string(),
+	]
+}
+pub const fn sort_symbol(reverse: bool) -> &'static str {
+	if reverse {
+		symbol::SORT_DESCENDING
+	} else {
+		symbol::SORT_ASCENDING
+	}
+}
+pub fn time_unknown() -> String {
+	"-".to_string()
+}
+pub fn title_disk_usage(location: Option<&str>, total: &str) -> String {
+	location.map_or_else(
+		|| format!("Disk usage ({total} on disk)"),
+		|location| format!("Disk usage: {location} ({total} on disk)"),
+	)
+}
+pub fn title_revision_files(location: Option<&str>) -> String {
+	location.map_or_else(
+		|| "Files".to_string(),
+		|location| format!("Files at {location}"),
+	)
+}
+pub fn refs_detached() -> String {
+	"(no ref)".to_string()
+}
+pub fn title_revisions(repo_id: &str) -> String {
+	format!("Revisions of {repo_id}")
+}
+pub fn title_compare_with(location: &str) -> String {
+	format!("Compare {location} with")
+}
+pub fn title_snapshot_diff(location: &str, count: usize) -> String {
+	format!("Changes {location} ({count})")
+}
+pub fn snapshot_diff_empty() -> String {
+	"no files changed".to_string()
+}
+pub const fn change_kind_symbol(kind: ChangeKind) -> &'static str {
+	match kind {
+		ChangeKind::Added => "+",
+		ChangeKind::Removed => "-",
+		ChangeKind::Modified => "~",
+	}
+}
+pub fn diff_hunk_separator() -> String {
+	"...".to_string()
+}
+pub fn diff_too_large() -> String {
+	"file too large for a line diff".to_string()
+}
+pub fn diff_binary() -> String {
+	"binary file, no line diff".to_string()
+}
+pub fn diff_no_line_changes() -> String {
+	"no lines changed".to_string()
+}
+pub const fn file_kind(kind: FileKind) -> &'static str {
+	match kind {
+		FileKind::Lfs => "lfs",
+		FileKind::Git => "git",
+		FileKind::Symlink => "link",
+		FileKind::Copy => "copy",
+	}
+}
+pub fn title_cache_summary() -> String {
+	"Cache".to_string()
+}
+pub fn title_largest_repos() -> String {
+	"Largest repos".to_string()
+}
+pub fn title_recent_repos() -> String {
+	"Recently added".to_string()
+}
+pub fn summary_total() -> String {
+	"total size".to_string()
+}
+pub fn summary_unique() -> String {
+	"unique blobs".to_string()
+}
+pub fn summary_problems() -> String {
+	"problems".to_string()
+}
+pub fn summary_free(root: &CacheRoot) -> String {
+	format!("free {}", root_tag(root))
+}
+pub fn summary_unknown() -> String {
+	"?".to_string()
+}
+pub fn disk_usage_header() -> [String; 5] {
+	[
+		"apparent".to_string(),
+		"on disk".to_string(),
+		"shared".to_string(),
+		"frees".to_string(),
+		"name".to_string(),
+	]
+}
+pub fn repo_health_badge(problems: usize) -> String {
+	if problems == 0 {
+		" ok".to_string()
+	} else {
+		format!("!{problems:>2}")
+	}
+}
+pub fn title_problems(count: usize) -> String {
+	format!("Problems ({count})")
+}
+pub fn problems_empty() -> String {
+	"no problems found".to_string()
+}
+pub fn problem_kind(kind: ProblemKind) -> &'static str {
+	match kind {
+		ProblemKind::DanglingSymlink => "missing blob",
+		ProblemKind::OrphanBlob => "orphan blob",
+		ProblemKind::IncompleteDownload => "incomplete",
+		ProblemKind::DanglingRef => "dangling ref",
+		ProblemKind::StaleLock => "stale lock",
+		ProblemKind::CopiedFiles => "no dedup",
+	}
+}
+pub fn title_fuzzy_find(matches: usize, total: usize) -> String {
+	format!("{POPUP_TITLE_FUZZY_FIND} ({matches}/{total})")
+}
+pub fn fuzzy_find_empty() -> String {
+	"no matching file".to_string()
+}
+pub fn title_verify(target: &str) -> String {
+	format!("Verify {target}")
+}
+pub fn verify_progress(blobs: usize) -> String {
+	format!("hashing {blobs} blobs")
+}
+pub fn verify_summary(result: &VerifyResult) -> String {
+	format!(
+		"{} ok, {} mismatched, {} unreadable, {} skipped",
+		result.ok,
+		result.mismatches.len(),
+		result.errors.len(),
+		result.skipped
+	)
+}
+pub fn verify_mismatch(expected: &str, actual: &str) -> String {
+	format!("  expected {expected}\n  actual   {actual}")
+}
+pub fn title_convert(repo_id: &str) -> String {
+	format!("Convert {repo_id} to symlinks")
+}
+pub fn convert_confirm(files: usize, size: &str) -> String {
+	format!(
+		"{files} files ({size}) are stored as copies, deduplication is disabled.\n\nMove them into blobs/ and link them back? Duplicate copies are deleted."
+	)
+}
+pub fn convert_read_only() -> String {
+	"the cache root is read-only".to_string()
+}
+pub fn convert_no_symlinks() -> String {
+	"the filesystem of the cache root does not support symlinks".to_string()
+}
+pub fn convert_progress(files: usize) -> String {
+	format!("linking {files} files")
+}
+pub fn convert_summary(result: &ConvertResult, reclaimed: &str) -> String {
+	format!(
+		"{} files linked, {reclaimed} reclaimed, \
+		 {} kept as copies, {} failed",
+		result.linked,
+		result.kept,
+		result.errors.len()
+	)
+}
+pub fn title_datasets(count: usize, orphaned: &str) -> String {
+	format!("Datasets ({count}) {orphaned} orphaned")
+}
+pub fn dataset_table_header() -> [String; 3] {
+	[
+		"dataset / config / split".to_string(),
+		"rows".to_string(),
+		"size".to_string(),
+	]
+}
+pub fn dataset_split(name: &str, files: usize) -> String {
+	format!("    {name} ({files} files)")
+}
+pub fn dataset_cache_files(count: usize) -> String {
+	format!("    {count} map results")
+}
+pub fn dataset_orphans(count: usize) -> String {
+	format!("    {count} orphaned map results")
+}
+pub fn datasets_empty(root: &str) -> String {
+	format!("no datasets cached in {root}")
+}
+pub fn title_modules(count: usize, stale: usize) -> String {
+	format!("Remote code modules ({count}) {stale} stale")
+}
+pub const fn module_link(link: ModuleLink) -> &'static str {
+	match link {
+		ModuleLink::Cached => "cached",
+		ModuleLink::RevisionGone => "revision gone",
+		ModuleLink::RepoGone => "repo gone",
+		ModuleLink::Local => "local",
+	}
+}
+pub fn modules_empty(root: &str) -> String {
+	format!("no remote code modules in {root}")
+}
+pub fn short_hash(hash: &str) -> String {
+	hash.chars().take(HASH_LENGTH).collect()
+}
+pub fn title_code() -> String {
+	"Code".to_string()
+}
+pub fn title_hex(name: &str, format: Option<BlobFormat>) -> String {
+	match format {
+		Some(format) => format!("{name} ({})", blob_format(format)),
+		None => name.to_string(),
+	}
+}
+pub const fn blob_format(format: BlobFormat) -> &'static str {
+	match format {
+		BlobFormat::Safetensors => "safetensors",
+		BlobFormat::Gguf => "GGUF",
+		BlobFormat::Zip => "zip",
+		BlobFormat::Pickle => "pickle",
+		BlobFormat::Protobuf => "protobuf?",
+		BlobFormat::Parquet => "parquet",
+	}
+}
+pub fn hex_position(offset: u64, len: u64) -> String {
+	format!("{offset:#x} / {len:#x}")
+}
+pub fn hex_searching(percent: u8) -> String {
+	format!("searching {percent}%")
+}
+pub fn hex_found(offset: u64) -> String {
+	format!("found at {offset:#x}")
+}
+pub fn hex_not_found() -> String {
+	"pattern not found".to_string()
+}
+pub fn hex_beyond_end(len: u64) -> String {
+	format!("file ends at {len:#x}")
+}
+pub fn title_goto_offset() -> String {
+	"Go to offset".to_string()
+}
+pub fn title_search_bytes() -> String {
+	"Search bytes".to_string()
+}
+pub fn hint_offset() -> String {
+	"decimal or 0x hex".to_string()
+}
+pub fn hint_pattern() -> String {
+	"hex bytes (de ad be ef) or \"text\"".to_string()
+}
+pub fn invalid_offset() -> String {
+	"not an offset, use decimal or 0x hex".to_string()
+}
+pub fn invalid_pattern() -> String {
+	"not a pattern, use hex bytes or \"text\"".to_string()
+}
+pub fn title_search_text() -> String {
+	"Search text".to_string()
+}
+pub fn hint_text() -> String {
+	"searched from the cursor while typing, ignoring case".to_string()
+}
+pub fn title_record(name: &str, line: u64) -> String {
+	format!("{name} (record {})", line + 1)
+}
+pub fn stream_position(line: u64, lines: u64, complete: bool) -> String {
+	let more = if complete { "" } else { "+" };
+	format!("line {} / {lines}{more}", line + 1)
+}
+pub fn stream_indexing(percent: u8) -> String {
+	format!("indexing {percent}%")
+}
+pub fn stream_nowrap(column: u16) -> String {
+	format!("col {}", column + 1)
+}
+pub fn text_found(line: u64) -> String {
+	format!("found in line {}", line + 1)
+}
+pub fn text_not_found() -> String {
+	"text not found".to_string()
+}
+pub fn record_invalid(e: &str) -> String {
+	format!("not a JSON record: {e}")
+}
+pub fn code_load_error(e: &str) -> String {
+	format!("error loading file: {e}")
+}
+pub fn repo_list_empty() -> String {
+	"no repositories in cache".to_string()
+}
 // pub fn create_branch_popup_title(
 // 	_key_config: &SharedKeyConfig,
 // ) -> String {
@@ -482,11 +894,11 @@ pub fn ellipsis_trim_start(s: &str, width: usize) -> Cow<str> {
 // 	}
 // }
 
-// pub mod commands {
-// 	// use crate::components::CommandText;
-// 	use crate::keys::SharedKeyConfig;
+pub mod commands {
+	use crate::components::CommandText;
+	use crate::keys::SharedKeyConfig;
 
-// 	static CMD_GROUP_GENERAL: &str = "-- General --";
+	static CMD_GROUP_GENERAL: &str = "-- General --";
 // 	static CMD_GROUP_DIFF: &str = "-- Diff --";
 // 	static CMD_GROUP_CHANGES: &str = "-- Changes --";
 // 	static CMD_GROUP_COMMIT_POPUP: &str = "-- Commit Popup --";
@@ -576,32 +988,32 @@ pub fn ellipsis_trim_start(s: &str, width: usize) -> Cow<str> {
 // 			CMD_GROUP_GENERAL,
 // 		)
 // 	}
-// 	pub fn navigate_tree(
-// 		key_config: &SharedKeyConfig,
-// 	) -> CommandText {
-// 		CommandText::new(
-// 			format!(
-// 				"Nav [{}{}{}{}]",
-// 				key_config.get_hint(key_config.keys.move_up),
-// 				key_config.get_hint(key_config.keys.move_down),
-// 				key_config.get_hint(key_config.keys.move_right),
-// 				key_config.get_hint(key_config.keys.move_left)
-// 			),
-// 			"navigate tree view, collapse, expand",
-// 			CMD_GROUP_GENERAL,
-// 		)
-// 	}
-// 	pub fn scroll(key_config: &SharedKeyConfig) -> CommandText {
-// 		CommandText::new(
-// 			format!(
-// 				"Scroll [{}{}]",
-// 				key_config.get_hint(key_config.keys.move_up),
-// 				key_config.get_hint(key_config.keys.move_down)
-// 			),
-// 			"scroll up or down in focused view",
-// 			CMD_GROUP_GENERAL,
-// 		)
-// 	}
+	pub fn navigate_tree(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Nav [{}{}{}{}]",
+				key_config.get_hint(key_config.keys.move_up),
+				key_config.get_hint(key_config.keys.move_down),
+				key_config.get_hint(key_config.keys.move_right),
+				key_config.get_hint(key_config.keys.move_left)
+			),
+			"navigate tree view, collapse, expand",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn scroll(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Scroll [{}{}]",
+				key_config.get_hint(key_config.keys.move_up),
+				key_config.get_hint(key_config.keys.move_down)
+			),
+			"scroll up or down in focused view",
+			CMD_GROUP_GENERAL,
+		)
+	}
 // 	pub fn commit_list_mark(
 // 		key_config: &SharedKeyConfig,
 // 		marked: bool,
@@ -807,16 +1219,368 @@ pub fn ellipsis_trim_start(s: &str, width: usize) -> Cow<str> {
 // 			CMD_GROUP_GENERAL,
 // 		)
 // 	}
-// 	pub fn close_popup(key_config: &SharedKeyConfig) -> CommandText {
-// 		CommandText::new(
-// 			format!(
-// 				"Close [{}]",
-// 				key_config.get_hint(key_config.keys.exit_popup),
-// 			),
-// 			"close overlay (e.g commit, help)",
-// 			CMD_GROUP_GENERAL,
-// 		)
-// 	}
+	pub fn close_popup(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Close [{}]",
+				key_config.get_hint(key_config.keys.exit_popup),
+			),
+			"close overlay (e.g commit, help)",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_files(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"To files [{}]",
+				key_config.get_hint(key_config.keys.toggle_workarea),
+			),
+			"focus the snapshot files",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_code(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"To code [{}]",
+				key_config.get_hint(key_config.keys.toggle_workarea),
+			),
+			"focus the code of the selected module file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_preview(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"To preview [{}]",
+				key_config.get_hint(key_config.keys.move_right),
+			),
+			"focus the preview of the selected file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_tree(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"To tree [{}]",
+				key_config.get_hint(key_config.keys.move_left),
+			),
+			"focus the snapshot file tree",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_revision(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Revisions [{}]",
+				key_config.get_hint(key_config.keys.select_revision),
+			),
+			"pick a revision of the repo by ref or commit",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn goto_offset(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Offset [{}]",
+				key_config.get_hint(key_config.keys.goto_offset),
+			),
+			"jump to an offset in the file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn search_bytes(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search [{}]",
+				key_config.get_hint(key_config.keys.search),
+			),
+			"search the file for a byte pattern",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn search_text(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search [{}]",
+				key_config.get_hint(key_config.keys.search),
+			),
+			"search the lines from the cursor on while typing",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn search_next(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next [{}]",
+				key_config.get_hint(key_config.keys.search_next),
+			),
+			"search for the pattern again after the last match",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn toggle_wrap(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Wrap [{}]",
+				key_config.get_hint(key_config.keys.toggle_wrap),
+			),
+			"wrap long lines or cut them and scroll sideways",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn scroll_sideways(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Sideways [{}{}]",
+				key_config.get_hint(key_config.keys.scroll_left),
+				key_config.get_hint(key_config.keys.scroll_right),
+			),
+			"scroll cut lines left or right",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn show_record(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Record [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"pretty print the JSON record at the cursor",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn hide_record(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Lines [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"back to the lines of the file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn confirm_input(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Go [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"jump or search",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn show_revision(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Show [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"show the files of the selected revision",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn compare_revision(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Compare [{}]",
+				key_config.get_hint(key_config.keys.compare_commits),
+			),
+			"compare the shown revision with another snapshot",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_diff(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"To diff [{}]",
+				key_config.get_hint(key_config.keys.move_right),
+			),
+			"focus the line diff of the selected file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_changes(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"To changes [{}]",
+				key_config.get_hint(key_config.keys.move_left),
+			),
+			"focus the list of changed files",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_modules(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"To modules [{}]",
+				key_config.get_hint(key_config.keys.toggle_workarea),
+			),
+			"focus the module list",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn select_repos(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"To repos [{}]",
+				key_config.get_hint(key_config.keys.toggle_workarea),
+			),
+			"focus the repository list",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn verify_repo(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Verify repo [{}]",
+				key_config.get_hint(key_config.keys.verify),
+			),
+			"rehash all blobs of the repo",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn verify_file(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Verify file [{}]",
+				key_config.get_hint(key_config.keys.verify),
+			),
+			"rehash the blob of the selected file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn verify_revision(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Verify revision [{}]",
+				key_config.get_hint(key_config.keys.verify_revision),
+			),
+			"rehash all blobs of the shown revision",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn repo_type_filter(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Type [{}]",
+				key_config.get_hint(key_config.keys.repo_type_filter),
+			),
+			"cycle showing all repos, models, datasets or spaces",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn scroll_columns(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Columns [{}{}]",
+				key_config.get_hint(key_config.keys.move_left),
+				key_config.get_hint(key_config.keys.move_right)
+			),
+			"scroll the table columns left or right",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn usage_descend(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Open [{}{}]",
+				key_config.get_hint(key_config.keys.enter),
+				key_config.get_hint(key_config.keys.tree_expand_recursive),
+			),
+			"open the selected entry, or follow the largest ones down",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn usage_climb(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Back [{}{}]",
+				key_config.get_hint(key_config.keys.move_left),
+				key_config.get_hint(key_config.keys.tree_collapse_recursive),
+			),
+			"go up one level, or back to the whole cache",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn sort_column(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Sort [{}]",
+				key_config.get_hint(key_config.keys.sort_column),
+			),
+			"sort the repos by the next column",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn sort_reverse(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reverse [{}]",
+				key_config.get_hint(key_config.keys.sort_reverse),
+			),
+			"reverse the sort order of the repos",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn convert_layout(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Link copies [{}]",
+				key_config.get_hint(key_config.keys.convert_layout),
+			),
+			"replace copied snapshot files with links into blobs",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn confirm_convert(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Convert [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"start linking the copied files",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn open_problems(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Problems [{}]",
+				key_config.get_hint(key_config.keys.open_problems),
+			),
+			"list integrity problems of the cache",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn find_file(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Find [{}]",
+				key_config.get_hint(key_config.keys.file_find),
+			),
+			"fuzzy find a file in all snapshots",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn open_found_file(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Open [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"show the file in the files tab",
+			CMD_GROUP_GENERAL,
+		)
+	}
 // 	pub fn scroll_popup(key_config: &SharedKeyConfig) -> CommandText {
 // 		CommandText::new(
 // 			format!(
@@ -828,17 +1592,17 @@ pub fn ellipsis_trim_start(s: &str, width: usize) -> Cow<str> {
 // 			CMD_GROUP_GENERAL,
 // 		)
 // 	}
-// 	pub fn close_msg(key_config: &SharedKeyConfig) -> CommandText {
-// 		CommandText::new(
-// 			format!(
-// 				"Close [{}]",
-// 				key_config.get_hint(key_config.keys.enter),
-// 			),
-// 			"close msg popup (e.g msg)",
-// 			CMD_GROUP_GENERAL,
-// 		)
-// 		.hide_help()
-// 	}
+	pub fn close_msg(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Close [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"close msg popup (e.g msg)",
+			CMD_GROUP_GENERAL,
+		)
+		.hide_help()
+	}
 // 	pub fn validate_msg(key_config: &SharedKeyConfig) -> CommandText {
 // 		CommandText::new(
 // 			format!(
@@ -1274,16 +2038,16 @@ pub fn ellipsis_trim_start(s: &str, width: usize) -> Cow<str> {
 // 			CMD_GROUP_GENERAL,
 // 		)
 // 	}
-// 	pub fn quit(key_config: &SharedKeyConfig) -> CommandText {
-// 		CommandText::new(
-// 			format!(
-// 				"Quit [{}]",
-// 				key_config.get_hint(key_config.keys.exit),
-// 			),
-// 			"quit gitui application",
-// 			CMD_GROUP_GENERAL,
-// 		)
-// 	}
+	pub fn quit(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Quit [{}]",
+				key_config.get_hint(key_config.keys.exit),
+			),
+			"quit lazyhf",
+			CMD_GROUP_GENERAL,
+		)
+	}
 // 	pub fn confirm_action(
 // 		key_config: &SharedKeyConfig,
 // 	) -> CommandText {
@@ -1870,4 +2634,4 @@ pub fn ellipsis_trim_start(s: &str, width: usize) -> Cow<str> {
 // 			CMD_GROUP_LOG,
 // 		)
 // 	}
-// }
+}
@@ -0,0 +1,77 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DatasetListComponent, DrawableComponent, EventState,
+	},
+	datasets::DatasetsCache,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{layout::Rect, Frame};
+use std::rc::Rc;
+
+/// browses the cache of the `datasets` library
+pub struct DatasetsTab {
+	visible: bool,
+	list: DatasetListComponent,
+}
+
+impl DatasetsTab {
+	/// creates the tab, filled by `set_cache`
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			list: DatasetListComponent::new(env),
+		}
+	}
+
+	/// applies a new scan result
+	pub fn set_cache(&mut self, cache: Rc<DatasetsCache>) {
+		self.list.set_cache(cache);
+	}
+}
+
+impl DrawableComponent for DatasetsTab {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.is_visible() {
+			self.list.draw(f, rect)?;
+		}
+		Ok(())
+	}
+}
+
+impl Component for DatasetsTab {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			return self.list.commands(out, force_all);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.visible {
+			return self.list.event(ev);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
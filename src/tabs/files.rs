@@ -1,35 +1,259 @@
-use std::path::Path;
+use std::{path::PathBuf, rc::Rc};
 
 use crate::{
 	app::Environment,
-	// components::{
-	// 	visibility_blocking, CommandBlocking, CommandInfo, Component,
-	// 	DrawableComponent, EventState, RevisionFilesComponent,
-	// },
 	components::{
-		DrawableComponent, RevisionFilesComponent, Component
-    }
-	// AsyncNotification,
+		command_pump, event_pump, visibility_blocking,
+		CommandBlocking, CommandInfo, Component, DrawableComponent,
+		EventState, RepoListComponent, RevisionFilesComponent, ViewJump,
+	},
+	hub::{verify_items, DirStamp, HubCache, RevisionRef},
+	keys::{key_match, SharedKeyConfig},
+	popups::{
+		ConvertOpen, FoundFile, RevisionsOpen, SnapshotDiffOpen,
+		VerifyOpen,
+	},
+	progress::ProgressPercent,
+	queue::{InternalEvent, Queue},
+	strings, AsyncAppNotification,
 };
 use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::layout::{Constraint, Direction, Layout};
 // use asyncgit::sync::{self, RepoPathRef};
 
 pub struct FilesTab {
 	// repo: RepoPathRef,
 	visible: bool,
+	repos: RepoListComponent,
 	files: RevisionFilesComponent,
+	/// folder and folder stamps of the repo currently shown in `files`
+	shown: Option<(PathBuf, Vec<DirStamp>)>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
 }
 
 impl FilesTab {
-	///
+	/// creates the tab, filled by `set_cache`
 	pub fn new(env: &Environment) -> Self {
 		Self {
 			visible: false,
+			repos: RepoListComponent::new(env),
 			files: RevisionFilesComponent::new(env),
+			shown: None,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
 			// repo: env.repo.clone(),
 		}
 	}
 
+	/// applies a new scan result, the file tree is only rebuilt
+	/// if the selected repo changed on disk
+	pub fn set_cache(&mut self, cache: Rc<HubCache>) -> Result<()> {
+		self.repos.set_cache(cache);
+		self.selection_changed()
+	}
+
+	/// shows the progress of a running scan, `None` once it is done
+	pub fn set_scan_progress(&mut self, progress: Option<ProgressPercent>) {
+		self.repos.set_scan_progress(progress);
+	}
+
+	/// forwards syntax highlighting progress to the file preview
+	pub fn update_app(&mut self, ev: AsyncAppNotification) {
+		self.files.update(ev);
+	}
+
+	/// true while the previewed file is highlighted or searched
+	pub fn any_work_pending(&self) -> bool {
+		self.files.any_work_pending()
+	}
+
+	/// forwards a jump or search to the hex or stream preview
+	pub fn view_jump(&mut self, jump: &ViewJump) {
+		self.files.view_jump(jump);
+	}
+
+	/// shows the files of `revision` of the selected repo
+	pub fn select_revision(&mut self, revision: &RevisionRef) {
+		self.files.select_revision(revision);
+		if !self.files.focused() {
+			self.toggle_focus();
+		}
+	}
+
+	/// selects the repo, revision and file of `found`
+	pub fn open_found_file(&mut self, found: &FoundFile) -> Result<()> {
+		if !self.repos.select_repo(&found.repo_path) {
+			return Ok(());
+		}
+		self.selection_changed()?;
+
+		self.files.select_file(&found.reference, &found.path);
+		if !self.files.focused() {
+			self.toggle_focus();
+		}
+
+		Ok(())
+	}
+
+	/// queues comparing the revision shown in the file tree with `revision`,
+	/// the older of both being the base
+	pub fn compare_revision(&self, revision: &RevisionRef) {
+		let Some(repo) = self.repos.selected_repo() else {
+			return;
+		};
+		let Some((shown, other)) = self
+			.files
+			.selected_revision()
+			.zip(repo.revision(&revision.commit_hash))
+		else {
+			return;
+		};
+
+		let (old, new) = if other.last_modified <= shown.last_modified {
+			(other, shown)
+		} else {
+			(shown, other)
+		};
+
+		self.queue.push(InternalEvent::OpenSnapshotDiff(
+			SnapshotDiffOpen::new(&repo.repo_id, old, new),
+		));
+	}
+
+	/// id of the repo under the cursor
+	pub fn selected_repo_id(&self) -> Option<&str> {
+		self.repos.selected_repo().map(|r| r.repo_id.as_str())
+	}
+
+	fn toggle_focus(&mut self) {
+		let repos_focused = self.repos.focused();
+		self.repos.focus(!repos_focused);
+		self.files.focus(repos_focused);
+	}
+
+	/// queues verifying the selected file when the files have focus,
+	/// the shown revision if `revision` is set or the whole repo otherwise.
+	/// with the files focused but no file selected nothing is verified
+	fn verify(&self, revision: bool) -> bool {
+		let Some(repo) = self.repos.selected_repo() else {
+			return false;
+		};
+		let commit = self
+			.files
+			.selected_revision()
+			.map(|rev| rev.commit_hash.as_str());
+		let files_focused = self.files.focused();
+		let file = if files_focused {
+			self.files.selected_file()
+		} else {
+			None
+		};
+
+		let (title, (items, skipped)) = match (commit, file) {
+			(Some(commit), _) if revision => (
+				format!("{}@{commit}", repo.repo_id),
+				verify_items(repo, Some(commit), None),
+			),
+			(Some(commit), Some(file)) => (
+				file.display().to_string(),
+				verify_items(repo, Some(commit), Some(&file)),
+			),
+			(_, None) if !revision && !files_focused => {
+				(repo.repo_id.clone(), verify_items(repo, None, None))
+			}
+			_ => return false,
+		};
+
+		self.queue.push(InternalEvent::OpenVerify(VerifyOpen {
+			title,
+			items,
+			skipped,
+		}));
+
+		true
+	}
+
+	/// queues asking to link the copied files of the selected repo
+	fn convert(&self) -> bool {
+		let Some(repo) = self.repos.selected_repo() else {
+			return false;
+		};
+		if repo.copied_files() == 0 {
+			return false;
+		}
+
+		self.queue.push(InternalEvent::OpenConvert(ConvertOpen {
+			repo: repo.clone(),
+			writable: self
+				.repos
+				.selected_root()
+				.is_some_and(|root| root.writable),
+		}));
+
+		true
+	}
+
+	/// queues picking the revision shown in the file tree,
+	/// or the one to compare it with if `compare` is set
+	fn pick_revision(&self, compare: bool) -> bool {
+		let Some(repo) = self.repos.selected_repo() else {
+			return false;
+		};
+		let current = self.files.selected_ref();
+
+		let (refs, compare) = if compare {
+			let Some(current) = current else {
+				return false;
+			};
+			let hash = strings::short_hash(&current.commit_hash);
+			let refs = repo
+				.revision_refs()
+				.into_iter()
+				.filter(|r| r.commit_hash != current.commit_hash)
+				.collect();
+
+			(refs, Some(format!("{}@{hash}", repo.repo_id)))
+		} else {
+			(repo.revision_refs(), None)
+		};
+
+		self.queue.push(InternalEvent::OpenRevisions(RevisionsOpen {
+			repo_id: repo.repo_id.clone(),
+			refs,
+			current: current.cloned(),
+			compare,
+		}));
+
+		true
+	}
+
+	fn selection_changed(&mut self) -> Result<()> {
+		let repo = self.repos.selected_repo();
+		let shown =
+			repo.map(|repo| (repo.path.clone(), repo.stamps.clone()));
+
+		if shown == self.shown {
+			return Ok(());
+		}
+
+		let same_folder = self
+			.shown
+			.as_ref()
+			.zip(shown.as_ref())
+			.is_some_and(|((old, _), (new, _))| old == new);
+		match repo {
+			Some(repo) if same_folder => self.files.reload_repo(repo)?,
+			Some(repo) => self.files.set_repo(repo)?,
+			None => self.files.clear(),
+		}
+
+		self.shown = shown;
+
+		Ok(())
+	}
+
 	// pub fn update(&mut self) -> Result<()> {
 	// 	if self.is_visible() {
 	// 		if let Ok(head) = sync::get_head(&self.repo.borrow()) {
@@ -64,39 +288,134 @@ impl FilesTab {
 impl DrawableComponent for FilesTab {
 	fn draw(
 		&self,
-        f : &mut ratatui::Frame,
+		f: &mut ratatui::Frame,
 		rect: ratatui::layout::Rect,
 	) -> Result<()> {
 		if self.is_visible() {
-			self.files.draw(f, rect)?;
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[
+						Constraint::Percentage(35),
+						Constraint::Percentage(65),
+					]
+					.as_ref(),
+				)
+				.split(rect);
+
+			self.repos.draw(f, chunks[0])?;
+			self.files.draw(f, chunks[1])?;
 		}
 		Ok(())
 	}
 }
 
 impl Component for FilesTab {
-	// fn commands(
-	// 	&self,
-	// 	out: &mut Vec<CommandInfo>,
-	// 	force_all: bool,
-	// ) -> CommandBlocking {
-	// 	if self.visible || force_all {
-	// 		return self.files.commands(out, force_all);
-	// 	}
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			command_pump(
+				out,
+				force_all,
+				&[&self.repos, &self.files],
+			);
 
-	// 	visibility_blocking(self)
-	// }
+			let files_focused = self.files.focused();
+			let has_repo = self.repos.selected_repo().is_some();
+			let has_revision = self.files.selected_revision().is_some();
 
-	// fn event(
-	// 	&mut self,
-	// 	ev: &crossterm::event::Event,
-	// ) -> Result<EventState> {
-	// 	if self.visible {
-	// 		return self.files.event(ev);
-	// 	}
+			out.push(CommandInfo::new(
+				if files_focused {
+					strings::commands::select_repos(&self.key_config)
+				} else {
+					strings::commands::select_files(&self.key_config)
+				},
+				has_revision || files_focused,
+				true,
+			));
+			out.push(CommandInfo::new(
+				if files_focused {
+					strings::commands::verify_file(&self.key_config)
+				} else {
+					strings::commands::verify_repo(&self.key_config)
+				},
+				if files_focused {
+					self.files.selected_file().is_some()
+				} else {
+					has_repo
+				},
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::verify_revision(&self.key_config),
+				has_revision,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::select_revision(&self.key_config),
+				self.repos
+					.selected_repo()
+					.is_some_and(|r| !r.revisions.is_empty()),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::compare_revision(&self.key_config),
+				has_revision
+					&& self
+						.repos
+						.selected_repo()
+						.is_some_and(|r| r.revisions.len() > 1),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::convert_layout(&self.key_config),
+				self.repos
+					.selected_repo()
+					.is_some_and(|r| r.copied_files() > 0),
+				true,
+			));
+		}
 
-	// 	Ok(EventState::NotConsumed)
-	// }
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if event_pump(ev, &mut [&mut self.repos, &mut self.files])?
+			.is_consumed()
+		{
+			self.selection_changed()?;
+			return Ok(EventState::Consumed);
+		}
+
+		if let Event::Key(key) = ev {
+			let keys = &self.key_config.keys;
+			if key_match(key, keys.toggle_workarea) {
+				if self.shown.is_some() || self.files.focused() {
+					self.toggle_focus();
+					return Ok(EventState::Consumed);
+				}
+			} else if key_match(key, keys.verify) {
+				return Ok(self.verify(false).into());
+			} else if key_match(key, keys.verify_revision) {
+				return Ok(self.verify(true).into());
+			} else if key_match(key, keys.convert_layout) {
+				return Ok(self.convert().into());
+			} else if key_match(key, keys.select_revision) {
+				return Ok(self.pick_revision(false).into());
+			} else if key_match(key, keys.compare_commits) {
+				return Ok(self.pick_revision(true).into());
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
 
 	fn is_visible(&self) -> bool {
 		self.visible
@@ -104,11 +423,12 @@ impl Component for FilesTab {
 
 	fn hide(&mut self) {
 		self.visible = false;
+		self.files.hide();
 	}
 
 	fn show(&mut self) -> Result<()> {
 		self.visible = true;
-		// self.update()?;
+		self.files.show()?;
 		Ok(())
 	}
 }
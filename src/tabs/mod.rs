@@ -1,5 +1,9 @@
+mod datasets;
 mod files;
+mod modules;
 mod status;
 
+pub use datasets::DatasetsTab;
 pub use files::FilesTab;
+pub use modules::ModulesTab;
 pub use status::Status;
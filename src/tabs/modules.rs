@@ -0,0 +1,158 @@
+use crate::{
+	app::Environment,
+	components::{
+		command_pump, event_pump, visibility_blocking,
+		CommandBlocking, CommandInfo, Component, DrawableComponent,
+		EventState, ModuleListComponent, SyntaxTextComponent, ViewJump,
+	},
+	hub::HubCache,
+	keys::{key_match, SharedKeyConfig},
+	modules::ModulesCache,
+	strings, AsyncAppNotification,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	Frame,
+};
+use std::rc::Rc;
+
+/// audits the remote code `transformers` copied into its module cache
+pub struct ModulesTab {
+	visible: bool,
+	list: ModuleListComponent,
+	code: SyntaxTextComponent,
+	key_config: SharedKeyConfig,
+}
+
+impl ModulesTab {
+	/// creates the tab, filled by `set_modules`
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			list: ModuleListComponent::new(env),
+			code: SyntaxTextComponent::new(env),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	/// applies a new scan of the module cache
+	pub fn set_modules(&mut self, modules: Rc<ModulesCache>) {
+		self.list.set_modules(modules);
+		self.selection_changed();
+	}
+
+	/// relinks the modules to a new scan of the hub cache
+	pub fn set_hub(&mut self, hub: Rc<HubCache>) {
+		self.list.set_hub(hub);
+	}
+
+	/// forwards syntax highlighting progress to the code view
+	pub fn update_app(&mut self, ev: AsyncAppNotification) {
+		self.code.update(ev);
+	}
+
+	/// true while the shown file is highlighted or searched
+	pub fn any_work_pending(&self) -> bool {
+		self.code.any_work_pending()
+	}
+
+	/// forwards a jump or search to the hex or stream code view
+	pub fn view_jump(&mut self, jump: &ViewJump) {
+		self.code.view_jump(jump);
+	}
+
+	fn toggle_focus(&mut self) {
+		let list_focused = self.list.focused();
+		self.list.focus(!list_focused);
+		self.code.focus(list_focused);
+	}
+
+	fn selection_changed(&mut self) {
+		match self.list.selected_file() {
+			Some(path) => self.code.load_file(&path),
+			None => self.code.clear(),
+		}
+	}
+}
+
+impl DrawableComponent for ModulesTab {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.is_visible() {
+			let chunks = Layout::default()
+				.direction(Direction::Horizontal)
+				.constraints([
+					Constraint::Percentage(40),
+					Constraint::Percentage(60),
+				])
+				.split(rect);
+
+			self.list.draw(f, chunks[0])?;
+			self.code.draw(f, chunks[1])?;
+		}
+		Ok(())
+	}
+}
+
+impl Component for ModulesTab {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			command_pump(out, force_all, &[&self.list, &self.code]);
+
+			let code_focused = self.code.focused();
+			out.push(CommandInfo::new(
+				if code_focused {
+					strings::commands::select_modules(&self.key_config)
+				} else {
+					strings::commands::select_code(&self.key_config)
+				},
+				code_focused || self.code.path().is_some(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if !self.visible {
+			return Ok(EventState::NotConsumed);
+		}
+
+		if event_pump(ev, &mut [&mut self.list, &mut self.code])?
+			.is_consumed()
+		{
+			self.selection_changed();
+			return Ok(EventState::Consumed);
+		}
+
+		if let Event::Key(key) = ev {
+			if key_match(key, self.key_config.keys.toggle_workarea)
+				&& (self.code.focused() || self.code.path().is_some())
+			{
+				self.toggle_focus();
+				return Ok(EventState::Consumed);
+			}
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
@@ -1,35 +1,47 @@
-use std::path::Path;
-
 use crate::{
 	app::Environment,
-	// components::{
-	// 	visibility_blocking, CommandBlocking, CommandInfo, Component,
-	// 	DrawableComponent, EventState, RevisionFilesComponent,
-	// },
 	components::{
-		DrawableComponent, RevisionFilesComponent, Component
-    }
+		visibility_blocking, CacheDashboardComponent, CommandBlocking,
+		CommandInfo, Component, DiskUsageComponent, DrawableComponent,
+		EventState,
+	},
+	hub::HubCache,
 	// AsyncNotification,
 };
 use anyhow::Result;
+use ratatui::layout::{Constraint, Direction, Layout};
+use std::rc::Rc;
 // use asyncgit::sync::{self, RepoPathRef};
 
 pub struct Status {
 	// repo: RepoPathRef,
 	visible: bool,
-	files: RevisionFilesComponent,
+	dashboard: CacheDashboardComponent,
+	usage: DiskUsageComponent,
 }
 
 impl Status {
-	///
+	/// creates the tab, filled by `set_cache`
 	pub fn new(env: &Environment) -> Self {
 		Self {
 			visible: false,
-			files: RevisionFilesComponent::new(env),
+			dashboard: CacheDashboardComponent::new(env),
+			usage: DiskUsageComponent::new(env),
 			// repo: env.repo.clone(),
 		}
 	}
 
+	/// applies a new scan result
+	pub fn set_cache(&mut self, cache: Rc<HubCache>) {
+		self.dashboard.set_cache(cache.clone());
+		self.usage.set_cache(cache);
+	}
+
+	/// refreshes the free space of the roots after a finished scan
+	pub fn update_free_space(&mut self) {
+		self.dashboard.update_free_space();
+	}
+
 	// pub fn update(&mut self) -> Result<()> {
 	// 	if self.is_visible() {
 	// 		if let Ok(head) = sync::get_head(&self.repo.borrow()) {
@@ -68,35 +80,44 @@ impl DrawableComponent for Status {
 		rect: ratatui::layout::Rect,
 	) -> Result<()> {
 		if self.is_visible() {
-			self.files.draw(f, rect)?;
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints([
+					Constraint::Length(self.dashboard.height()),
+					Constraint::Min(0),
+				])
+				.split(rect);
+
+			self.dashboard.draw(f, chunks[0])?;
+			self.usage.draw(f, chunks[1])?;
 		}
 		Ok(())
 	}
 }
 
 impl Component for Status {
-	// fn commands(
-	// 	&self,
-	// 	out: &mut Vec<CommandInfo>,
-	// 	force_all: bool,
-	// ) -> CommandBlocking {
-	// 	if self.visible || force_all {
-	// 		return self.files.commands(out, force_all);
-	// 	}
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.visible || force_all {
+			return self.usage.commands(out, force_all);
+		}
 
-	// 	visibility_blocking(self)
-	// }
+		visibility_blocking(self)
+	}
 
-	// fn event(
-	// 	&mut self,
-	// 	ev: &crossterm::event::Event,
-	// ) -> Result<EventState> {
-	// 	if self.visible {
-	// 		return self.files.event(ev);
-	// 	}
+	fn event(
+		&mut self,
+		ev: &crossterm::event::Event,
+	) -> Result<EventState> {
+		if self.visible {
+			return self.usage.event(ev);
+		}
 
-	// 	Ok(EventState::NotConsumed)
-	// }
+		Ok(EventState::NotConsumed)
+	}
 
 	fn is_visible(&self) -> bool {
 		self.visible
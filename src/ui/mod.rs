@@ -3,7 +3,7 @@ mod scrollbar;
 mod scrolllist;
 mod stateful_paragraph;
 pub mod style;
-// mod syntax_text;
+mod syntax_text;
 
 use filetreelist::MoveSelection;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
@@ -12,7 +12,9 @@ pub use scrolllist::{draw_list, draw_list_block};
 pub use stateful_paragraph::{
 	ParagraphState, ScrollPos, StatefulParagraph,
 };
-// pub use syntax_text::{AsyncSyntaxJob, SyntaxText};
+pub use syntax_text::{
+	AsyncSyntaxJob, SyntaxText, DEFAULT_SYNTAX_THEME,
+};
 
 use crate::keys::{key_match, SharedKeyConfig};
 
@@ -1,6 +1,11 @@
+use crate::{
+    hub::{ChangeKind, RepoType},
+    ui::DEFAULT_SYNTAX_THEME,
+};
 use anyhow::Result;
 use ratatui::style::{Color, Modifier, Style};
 use ron::ser::{to_string_pretty, PrettyConfig};
+use similar::ChangeTag;
 use serde::{Deserialize, Serialize};
 use std::{fs::File, io::Write, path::PathBuf, rc::Rc};
 use struct_patch::Patch;
@@ -32,6 +37,10 @@ pub struct Theme {
     push_gauge_fg: Color,
     tag_fg: Color,
     branch_fg: Color,
+    repo_model_fg: Color,
+    repo_dataset_fg: Color,
+    repo_space_fg: Color,
+    stale_fg: Color,
     line_break: String,
     block_title_focused: Color,
     syntax: String,
@@ -96,6 +105,20 @@ impl Theme {
             })
     }
 
+    pub fn repo_type(&self, repo_type: RepoType, selected: bool) -> Style {
+        let style = Style::default().fg(match repo_type {
+            RepoType::Model => self.repo_model_fg,
+            RepoType::Dataset => self.repo_dataset_fg,
+            RepoType::Space => self.repo_space_fg,
+        });
+
+        if selected {
+            style.bg(self.selection_bg)
+        } else {
+            style
+        }
+    }
+
     pub fn text(&self, enabled: bool, selected: bool) -> Style {
         match (enabled, selected) {
             (false, false) => Style::default().fg(self.disabled_fg),
@@ -115,6 +138,26 @@ impl Theme {
         self.apply_select(style, selected)
     }
 
+    /// file added, removed or modified between two snapshots
+    pub fn file_change(&self, kind: ChangeKind, selected: bool) -> Style {
+        let color = match kind {
+            ChangeKind::Added => self.diff_file_added,
+            ChangeKind::Removed => self.diff_file_removed,
+            ChangeKind::Modified => self.diff_file_modified,
+        };
+
+        self.apply_select(Style::default().fg(color), selected)
+    }
+
+    /// line of an inline diff
+    pub fn diff_line(&self, tag: ChangeTag) -> Style {
+        match tag {
+            ChangeTag::Insert => Style::default().fg(self.diff_line_add),
+            ChangeTag::Delete => Style::default().fg(self.diff_line_delete),
+            ChangeTag::Equal => Style::default(),
+        }
+    }
+
     const fn apply_select(&self, style: Style, selected: bool) -> Style {
         if selected {
             if self.use_selection_fg {
@@ -143,6 +186,17 @@ impl Theme {
         }
     }
 
+    /// repos unused for longer than the stale threshold
+    pub fn stale(&self, selected: bool) -> Style {
+        let style = Style::default().fg(self.stale_fg);
+
+        if selected {
+            style.bg(self.selection_bg)
+        } else {
+            style
+        }
+    }
+
     pub fn text_danger(&self) -> Style {
         Style::default().fg(self.danger_fg)
     }
@@ -206,6 +260,15 @@ impl Theme {
             .bg(self.push_gauge_bg)
     }
 
+    /// gauge of the cache usage, `danger_fg` once over budget
+    pub fn budget_gauge(&self, over_budget: bool) -> Style {
+        self.push_gauge().fg(if over_budget {
+            self.danger_fg
+        } else {
+            self.push_gauge_fg
+        })
+    }
+
     pub fn attention_block() -> Style {
         Style::default().fg(Color::Yellow)
     }
@@ -284,12 +347,15 @@ impl Default for Theme {
             push_gauge_fg: Color::Reset,
             tag_fg: Color::LightMagenta,
             branch_fg: Color::LightYellow,
+            repo_model_fg: Color::LightBlue,
+            repo_dataset_fg: Color::LightGreen,
+            repo_space_fg: Color::LightMagenta,
+            stale_fg: Color::Yellow,
             line_break: "¶".to_string(),
             block_title_focused: Color::Reset,
             // Available themes can be found in:
             // [ThemeSet::load_defaults function](https://github.com/trishume/syntect/blob/7fe13c0fd53cdfa0f9fea1aa14c5ba37f81d8b71/src/dumps.rs#L215).
-            syntax: String::from("base16-eighties.dark"),
-            // syntax: DEFAULT_SYNTAX_THEME.to_string(),
+            syntax: DEFAULT_SYNTAX_THEME.to_string(),
         }
     }
 }
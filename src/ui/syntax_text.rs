@@ -1,10 +1,6 @@
-use asyncgit::{
-	asyncjob::{AsyncJob, RunParams},
-	ProgressPercent,
-};
+use anyhow::{anyhow, Result};
 use once_cell::sync::{Lazy, OnceCell};
 use ratatui::text::{Line, Span};
-use scopetime::scope_time;
 use std::{
 	ops::Range,
 	path::{Path, PathBuf},
@@ -19,7 +15,11 @@ use syntect::{
 	parsing::{ParseState, ScopeStack, SyntaxSet},
 };
 
-use crate::{AsyncAppNotification, SyntaxHighlightProgress};
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	progress::ProgressPercent,
+	AsyncAppNotification, SyntaxHighlightProgress,
+};
 
 pub const DEFAULT_SYNTAX_THEME: &str = "base16-eighties.dark";
 
@@ -61,7 +61,7 @@ impl AsyncProgressBuffer {
 
 	pub fn update(&mut self, current: usize) -> bool {
 		self.current = current;
-		self.last_send.map_or(true, |last_send| {
+		self.last_send.is_none_or(|last_send| {
 			last_send.elapsed() > self.min_interval
 		})
 	}
@@ -73,10 +73,8 @@ impl SyntaxText {
 		file_path: &Path,
 		params: &RunParams<AsyncAppNotification, ProgressPercent>,
 		syntax: &str,
-	) -> asyncgit::Result<Self> {
-		scope_time!("syntax_highlighting");
+	) -> Result<Self> {
 		let mut state = {
-			scope_time!("syntax_highlighting.0");
 			let plain_text = || SYNTAX_SET.find_syntax_plain_text();
 			let syntax = SYNTAX_SET
 				.find_syntax_for_file(file_path)
@@ -89,9 +87,8 @@ impl SyntaxText {
 			ParseState::new(syntax)
 		};
 
-		let theme = THEME.get_or_try_init(|| -> Result<Theme, asyncgit::Error> {
-			let theme_path = crate::args::get_app_config_path()
-				.map_err(|e| asyncgit::Error::Generic(e.to_string()))?.join(format!("{syntax}.tmTheme"));
+		let theme = THEME.get_or_try_init(|| -> Result<Theme> {
+			let theme_path = crate::args::get_app_config_path()?.join(format!("{syntax}.tmTheme"));
 
 			match ThemeSet::get_theme(&theme_path) {
 				Ok(t) => return Ok(t),
@@ -104,7 +101,7 @@ impl SyntaxText {
 			}
 
 			log::error!("the syntax theme '{syntax}' cannot be found. Using default theme ('{DEFAULT_SYNTAX_THEME}') instead");
-			Ok(theme_set.themes.remove(DEFAULT_SYNTAX_THEME).expect("the default theme should be there"))
+			theme_set.themes.remove(DEFAULT_SYNTAX_THEME).ok_or_else(|| anyhow!("the default theme should be there"))
 		})?;
 
 		let highlighter = Highlighter::new(theme);
@@ -130,9 +127,7 @@ impl SyntaxText {
 					.parse_line(line, &SYNTAX_SET)
 					.map_err(|e| {
 						log::error!("syntax error: {:?}", e);
-						asyncgit::Error::Generic(
-							"syntax error".to_string(),
-						)
+						anyhow!("syntax error")
 					})?;
 				let iter = RangedHighlightIterator::new(
 					&mut highlight_state,
@@ -167,11 +162,7 @@ impl SyntaxText {
 		})
 	}
 
-	///
-	#[expect(
-		clippy::missing_const_for_fn,
-		reason = "as of 1.86.0 clippy wants this to be const even though that breaks"
-	)]
+	/// file the text was read from
 	pub fn path(&self) -> &Path {
 		&self.path
 	}
@@ -251,7 +242,7 @@ impl AsyncSyntaxJob {
 		}
 	}
 
-	///
+	/// the highlighted text, once the job finished
 	pub fn result(&self) -> Option<SyntaxText> {
 		if let Ok(mut state) = self.state.lock() {
 			if let Some(state) = state.take() {
@@ -273,8 +264,11 @@ impl AsyncJob for AsyncSyntaxJob {
 	fn run(
 		&mut self,
 		params: RunParams<Self::Notification, Self::Progress>,
-	) -> asyncgit::Result<Self::Notification> {
-		let mut state_mutex = self.state.lock()?;
+	) -> Result<Self::Notification> {
+		let mut state_mutex = self
+			.state
+			.lock()
+			.map_err(|_| anyhow!("syntax state poisoned"))?;
 
 		if let Some(state) = state_mutex.take() {
 			*state_mutex = Some(match state {
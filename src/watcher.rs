@@ -0,0 +1,99 @@
+use anyhow::Result;
+use crossbeam_channel::{unbounded, Sender};
+use notify::{Error, RecommendedWatcher, RecursiveMode, Watcher};
+use notify_debouncer_mini::{new_debouncer, DebouncedEvent};
+use std::{
+	path::PathBuf,
+	thread,
+	time::Duration,
+};
+
+/// watches the cache roots and reports changed paths
+pub struct CacheWatcher {
+	receiver: crossbeam_channel::Receiver<Vec<PathBuf>>,
+}
+
+impl CacheWatcher {
+	/// starts watching all `roots` recursively
+	pub fn new(roots: &[PathBuf]) -> Self {
+		log::trace!(
+			"recommended watcher: {:?}",
+			RecommendedWatcher::kind()
+		);
+
+		let (tx, rx) = std::sync::mpsc::channel();
+
+		let roots = roots.to_vec();
+		thread::spawn(move || {
+			let timeout = Duration::from_secs(2);
+			if let Err(e) = create_watcher(timeout, tx, &roots) {
+				log::error!("watch cache roots: {e}");
+			}
+		});
+
+		let (out_tx, out_rx) = unbounded();
+		thread::spawn(move || {
+			if let Err(e) = Self::forwarder(&rx, &out_tx) {
+				//maybe we need to restart the forwarder now?
+				log::error!("notify receive error: {e}");
+			}
+		});
+
+		Self { receiver: out_rx }
+	}
+
+	/// receives the changed paths of every debounced batch
+	pub fn receiver(&self) -> crossbeam_channel::Receiver<Vec<PathBuf>> {
+		self.receiver.clone()
+	}
+
+	fn forwarder(
+		receiver: &std::sync::mpsc::Receiver<
+			Result<Vec<DebouncedEvent>, Error>,
+		>,
+		sender: &Sender<Vec<PathBuf>>,
+	) -> Result<()> {
+		loop {
+			let ev = receiver.recv()?;
+
+			match ev {
+				Ok(ev) => {
+					log::debug!("notify events: {}", ev.len());
+
+					for (idx, e) in ev.iter().enumerate() {
+						log::debug!("notify [{idx}]: {e:?}");
+					}
+
+					if !ev.is_empty() {
+						sender.send(
+							ev.into_iter().map(|e| e.path).collect(),
+						)?;
+					}
+				}
+				Err(e) => log::error!("notify error: {e}"),
+			}
+		}
+	}
+}
+
+fn create_watcher(
+	timeout: Duration,
+	tx: std::sync::mpsc::Sender<Result<Vec<DebouncedEvent>, Error>>,
+	roots: &[PathBuf],
+) -> Result<()> {
+	let mut bouncer = new_debouncer(timeout, tx)?;
+
+	for root in roots {
+		// network mounts may not support watching, keep the others
+		if let Err(e) =
+			bouncer.watcher().watch(root, RecursiveMode::Recursive)
+		{
+			log::error!("watch {}: {e}", root.display());
+		}
+	}
+
+	// the watcher lives as long as the process
+	std::mem::forget(bouncer);
+
+	Ok(())
+}